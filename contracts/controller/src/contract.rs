@@ -1,13 +1,23 @@
 use crate::error::ContractError;
 use crate::handler::{
-    commit, get_commitment, get_commitment_timestamp, get_is_valid_name, get_max_commitment_age,
-    get_min_commitment_age, get_min_registration_duration, get_node_info_from_name,
-    get_nodehash_from_name, get_owner, get_price, get_registrar, get_rent_price,
-    get_token_id_from_name, owner_register, owner_renew, register, renew, set_config,
-    set_enable_registration, withdraw,
+    accept_ownership, add_blocked_names, add_claims, add_reserved_names, batch_renew, claim,
+    claim_referral_rewards, commit, deposit_renewal_credit, distribute, get_batch_rent_price,
+    get_blocked_names, get_claim_merkle_root, get_claims, get_commitment, get_commitment_deposit,
+    get_commitment_timestamp, get_cw20_tokens, get_fee_shares, get_is_valid_name,
+    get_max_commitment_age, get_min_commitment_age, get_min_registration_duration,
+    get_node_info_from_name, get_nodehash_from_name, get_normalized_name, get_owner,
+    get_payment_config, get_premium_config, get_price, get_referral_fee, get_referral_stats,
+    get_registrar, get_renewal_credit, get_renewal_window, get_rent_price, get_rent_prices,
+    get_reserved_names, get_roles, get_tlds, get_token_id_from_name, migrate_price_tiers,
+    owner_register, owner_renew, process_renewals, propose_owner, receive_cw20, register,
+    remove_blocked_names, remove_claims, remove_reserved_names, remove_tld, renew,
+    set_claim_merkle_root, set_commitment_deposit, set_config, set_cw20_token,
+    set_enable_registration, set_fee_shares, set_payment_config, set_premium, set_price_override,
+    set_price_tiers, set_referral_fee, set_renewal_window, set_role, set_tld, sweep_commitments,
+    sweep_legacy_commitments, validate_price_tiers, withdraw, withdraw_renewal_credit, Payment,
 };
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
-use crate::state::{Config, CONFIG};
+use crate::state::{Config, CONFIG, PRICE_TIERS};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
@@ -21,15 +31,15 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     let registrar_address = deps.api.addr_canonicalize(msg.registrar_address.as_str())?;
     let owner = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut price_tiers = msg.price_tiers;
+    validate_price_tiers(&mut price_tiers)?;
+    PRICE_TIERS.save(deps.storage, &price_tiers)?;
     CONFIG.save(
         deps.storage,
         &Config {
             max_commitment_age: msg.max_commitment_age,
             min_commitment_age: msg.min_commitment_age,
             min_registration_duration: msg.min_registration_duration,
-            tier1_price: msg.tier1_price,
-            tier2_price: msg.tier2_price,
-            tier3_price: msg.tier3_price,
             enable_registration: msg.enable_registration,
             registrar_address,
             owner,
@@ -56,21 +66,55 @@ pub fn execute(
             secret,
             resolver,
             address,
+            referrer,
         } => register(
-            deps, env, info, name, owner, duration, secret, resolver, address,
+            deps,
+            env,
+            info.sender,
+            Payment::Native(info.funds),
+            name,
+            owner,
+            duration,
+            secret,
+            resolver,
+            address,
+            referrer,
         ),
-        ExecuteMsg::Renew { name, duration } => renew(deps, env, info, name, duration),
+        ExecuteMsg::Renew {
+            name,
+            duration,
+            referrer,
+        } => renew(
+            deps,
+            env,
+            info.sender,
+            Payment::Native(info.funds),
+            name,
+            duration,
+            referrer,
+        ),
+        ExecuteMsg::ClaimReferralRewards {} => claim_referral_rewards(deps, env, info),
+        ExecuteMsg::DepositRenewalCredit { name } => deposit_renewal_credit(deps, env, info, name),
+        ExecuteMsg::WithdrawRenewalCredit { name } => {
+            withdraw_renewal_credit(deps, env, info, name)
+        }
+        ExecuteMsg::ProcessRenewals { names } => process_renewals(deps, env, info, names),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::BatchRenew { names } => batch_renew(deps, env, info, names),
+        ExecuteMsg::Claim {
+            name,
+            duration,
+            proof,
+            resolver,
+            address,
+        } => claim(deps, env, info, name, duration, proof, resolver, address),
 
         // Only owner
         ExecuteMsg::SetConfig {
             max_commitment_age,
             min_commitment_age,
             min_registration_duration,
-            tier1_price,
-            tier2_price,
-            tier3_price,
             registrar_address,
-            owner,
             enable_registration,
         } => set_config(
             deps,
@@ -79,14 +123,30 @@ pub fn execute(
             max_commitment_age,
             min_commitment_age,
             min_registration_duration,
-            tier1_price,
-            tier2_price,
-            tier3_price,
             registrar_address,
-            owner,
             enable_registration,
         ),
+        ExecuteMsg::ProposeOwner { owner } => propose_owner(deps, env, info, owner),
+        ExecuteMsg::AcceptOwnership {} => accept_ownership(deps, env, info),
+        ExecuteMsg::SetRole { role, address } => set_role(deps, env, info, role, address),
         ExecuteMsg::Withdraw {} => withdraw(deps, env, info),
+        ExecuteMsg::SetFeeShares { shares } => set_fee_shares(deps, env, info, shares),
+        ExecuteMsg::Distribute {} => distribute(deps, env, info),
+        ExecuteMsg::SetReferralFee { bps } => set_referral_fee(deps, env, info, bps),
+        ExecuteMsg::SetRenewalWindow { window } => set_renewal_window(deps, env, info, window),
+        ExecuteMsg::SetCw20Token {
+            token,
+            price_multiplier,
+        } => set_cw20_token(deps, env, info, token, price_multiplier),
+        ExecuteMsg::SetCommitmentDeposit { amount } => {
+            set_commitment_deposit(deps, env, info, amount)
+        }
+        ExecuteMsg::SweepCommitments { start_after, limit } => {
+            sweep_commitments(deps, env, info, start_after, limit)
+        }
+        ExecuteMsg::SweepLegacyCommitments { limit } => {
+            sweep_legacy_commitments(deps, env, info, limit)
+        }
         ExecuteMsg::OwnerRegister {
             name,
             owner,
@@ -98,11 +158,54 @@ pub fn execute(
         ExecuteMsg::SetEnableRegistration {
             enable_registration,
         } => set_enable_registration(deps, env, info, enable_registration),
+        ExecuteMsg::SetTld {
+            tld,
+            registrar_address,
+            price_tiers,
+            min_registration_duration,
+            enable_registration,
+        } => set_tld(
+            deps,
+            env,
+            info,
+            tld,
+            registrar_address,
+            price_tiers,
+            min_registration_duration,
+            enable_registration,
+        ),
+        ExecuteMsg::RemoveTld { tld } => remove_tld(deps, env, info, tld),
+        ExecuteMsg::SetPriceTiers { tiers } => set_price_tiers(deps, env, info, tiers),
+        ExecuteMsg::SetPriceOverride {
+            label,
+            price_per_year,
+        } => set_price_override(deps, env, info, label, price_per_year),
+        ExecuteMsg::AddReservedNames { names, claimant } => {
+            add_reserved_names(deps, env, info, names, claimant)
+        }
+        ExecuteMsg::RemoveReservedNames { names } => remove_reserved_names(deps, env, info, names),
+        ExecuteMsg::AddBlockedNames { names } => add_blocked_names(deps, env, info, names),
+        ExecuteMsg::RemoveBlockedNames { names } => remove_blocked_names(deps, env, info, names),
+        ExecuteMsg::AddClaims { claims } => add_claims(deps, env, info, claims),
+        ExecuteMsg::RemoveClaims { names } => remove_claims(deps, env, info, names),
+        ExecuteMsg::SetClaimMerkleRoot { merkle_root } => {
+            set_claim_merkle_root(deps, env, info, merkle_root)
+        }
+        ExecuteMsg::SetPremium {
+            start_price,
+            duration,
+            half_life,
+        } => set_premium(deps, env, info, start_price, duration, half_life),
+        ExecuteMsg::SetPaymentConfig {
+            oracle,
+            accepted_assets,
+            slippage_tolerance,
+        } => set_payment_config(deps, env, info, oracle, accepted_assets, slippage_tolerance),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetCommitment {
             name,
@@ -113,19 +216,54 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         } => to_binary(&get_commitment(
             &name, &owner, &secret, &resolver, &address,
         )?),
-        QueryMsg::CommitmentTimestamp { commitment } => {
-            to_binary(&get_commitment_timestamp(deps, commitment)?)
+        QueryMsg::CommitmentTimestamp {
+            committer,
+            commitment,
+        } => to_binary(&get_commitment_timestamp(deps, committer, commitment)?),
+        QueryMsg::RentPrice { name, duration } => {
+            to_binary(&get_rent_price(deps, env, name, duration)?)
         }
-        QueryMsg::RentPrice { name, duration } => to_binary(&get_rent_price(deps, name, duration)?),
+        QueryMsg::BatchRentPrice { names } => to_binary(&get_batch_rent_price(deps, env, names)?),
 
+        QueryMsg::CommitmentDeposit {} => to_binary(&get_commitment_deposit(deps)?),
         QueryMsg::MaxCommitmentAge {} => to_binary(&get_max_commitment_age(deps)?),
         QueryMsg::MinCommitmentAge {} => to_binary(&get_min_commitment_age(deps)?),
         QueryMsg::MinRegistrationDuration {} => to_binary(&get_min_registration_duration(deps)?),
         QueryMsg::GetPrice {} => to_binary(&get_price(deps)?),
+        QueryMsg::GetPremium {} => to_binary(&get_premium_config(deps)?),
+        QueryMsg::GetPaymentConfig {} => to_binary(&get_payment_config(deps)?),
+        QueryMsg::RentPrices { name, duration } => {
+            to_binary(&get_rent_prices(deps, env, name, duration)?)
+        }
+        QueryMsg::ReservedNames { start_after, limit } => {
+            to_binary(&get_reserved_names(deps, start_after, limit)?)
+        }
+        QueryMsg::BlockedNames { start_after, limit } => {
+            to_binary(&get_blocked_names(deps, start_after, limit)?)
+        }
+        QueryMsg::ListTlds { start_after, limit } => {
+            to_binary(&get_tlds(deps, start_after, limit)?)
+        }
+        QueryMsg::Claims { start_after, limit } => {
+            to_binary(&get_claims(deps, start_after, limit)?)
+        }
+        QueryMsg::ClaimMerkleRoot {} => to_binary(&get_claim_merkle_root(deps)?),
+        QueryMsg::Roles {} => to_binary(&get_roles(deps)?),
+        QueryMsg::RenewalCredit { name, depositor } => {
+            to_binary(&get_renewal_credit(deps, name, depositor)?)
+        }
+        QueryMsg::RenewalWindow {} => to_binary(&get_renewal_window(deps)?),
+        QueryMsg::ReferralFee {} => to_binary(&get_referral_fee(deps)?),
+        QueryMsg::ReferralStats { referrer } => to_binary(&get_referral_stats(deps, referrer)?),
+        QueryMsg::FeeShares {} => to_binary(&get_fee_shares(deps)?),
+        QueryMsg::Cw20Tokens { start_after, limit } => {
+            to_binary(&get_cw20_tokens(deps, start_after, limit)?)
+        }
         QueryMsg::Registrar {} => to_binary(&get_registrar(deps)?),
         QueryMsg::Owner {} => to_binary(&get_owner(deps)?),
 
         QueryMsg::IsValidName { name } => to_binary(&get_is_valid_name(&name)?),
+        QueryMsg::NormalizeName { name } => to_binary(&get_normalized_name(&name)?),
         QueryMsg::GetTokenId { name } => to_binary(&get_token_id_from_name(&name)?),
         QueryMsg::GetNodehash { name } => to_binary(&get_nodehash_from_name(deps, &name)?),
         QueryMsg::GetNodeInfo { name } => to_binary(&get_node_info_from_name(deps, &name)?),
@@ -133,6 +271,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    migrate_price_tiers(deps.storage)?;
     Ok(Response::default())
 }
@@ -1,5 +1,6 @@
 use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
+use tns::admin::AdminError;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -12,6 +13,9 @@ pub enum ContractError {
     #[error("NotOwner: Sender is {sender}, but owner is {owner}.")]
     NotOwner { sender: String, owner: String },
 
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
     #[error("RecommitTooEarly: You can recommit again after {commit_expired}. Current time is {current}.")]
     RecommitTooEarly { commit_expired: u64, current: u64 },
 
@@ -31,6 +35,21 @@ pub enum ContractError {
     #[error("InvalidName")]
     InvalidName {},
 
+    #[error("ReservedName")]
+    ReservedName { name: String },
+
+    #[error("BlockedName")]
+    BlockedName { name: String },
+
+    #[error("ClaimNotFound")]
+    ClaimNotFound { name: String },
+
+    #[error("AlreadyClaimed")]
+    AlreadyClaimed { name: String },
+
+    #[error("InvalidProof")]
+    InvalidProof {},
+
     #[error("NameTooShort")]
     NameTooShort {},
 
@@ -40,9 +59,15 @@ pub enum ContractError {
         min_duration: u64,
     },
 
+    #[error("NoPriceTier")]
+    NoPriceTier { length: u32 },
+
     #[error("InsufficientFund")]
     InsufficientFund { amount: Uint128, required: Uint128 },
 
+    #[error("UnexpectedFund")]
+    UnexpectedFund { denom: String },
+
     #[error("RegistrationDisabled")]
     RegistrationDisabled {},
 
@@ -1,26 +1,73 @@
 use crate::error::ContractError;
 use crate::msg::{
-    CommitmentTimestampResponse, GetCommitmentResponse, IsValidNameResponse,
-    MaxCommitmentAgeResponse, MinCommitmentAgeResponse, MinRegistrationDurationResponse,
-    NodeInfoResponse, NodehashResponse, OwnerResponse, PriceResponse, RegistrarResponse,
-    RentPriceResponse, TokenIdResponse,
+    BatchRentPriceResponse, BlockedNamesResponse, ClaimEntry, ClaimMerkleRootResponse,
+    ClaimsResponse, CommitmentDepositResponse, CommitmentTimestampResponse, Cw20HookMsg, Cw20Token,
+    Cw20TokensResponse, FeeShare, FeeSharesResponse, GetCommitmentResponse, IsValidNameResponse,
+    ListTldsResponse, MaxCommitmentAgeResponse, MinCommitmentAgeResponse,
+    MinRegistrationDurationResponse, NodeInfoResponse, NodehashResponse, NormalizeNameResponse,
+    OwnerResponse, PaymentConfigResponse, PremiumResponse, PriceOverride, PriceResponse, PriceTier,
+    ReferralFeeResponse, ReferralStatsResponse, RegistrarResponse, RenewalCreditResponse,
+    RenewalWindowResponse, RentPriceResponse, RentPricesResponse, ReservedName,
+    ReservedNamesResponse, TldResponse, TokenIdResponse,
+};
+use crate::state::{
+    Claim, Commitment, FeeRecipient, PaymentConfig, Premium, RenewalCredit, Reservation, Tld,
+    BLOCKED_NAMES, CLAIMS, CLAIM_MERKLE_ROOT, COMMITMENTS, COMMITMENT_DEPOSIT, CONFIG, CW20_TOKENS,
+    FEE_SHARES, LEGACY_COMMITMENTS, LEGACY_PRICE_CONFIG, MERKLE_CLAIMED, PAYMENT_CONFIG,
+    PENDING_DEPOSITS, PREMIUM, PRICE_OVERRIDES, PRICE_TIERS, REFERRALS, REFERRAL_FEE_BPS,
+    REGISTER_FEE_DENOM, REGISTRATIONS, RENEWAL_CREDITS, RENEWAL_WINDOW, RESERVED_NAMES, TLDS,
+    TOTAL_RENEWAL_CREDITS, UNCLAIMED_REFERRAL_REWARDS,
 };
-use crate::state::{COMMITMENTS, CONFIG, REGISTER_FEE_DENOM};
 use cosmwasm_std::{
-    to_binary, BalanceResponse, BankQuery, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    QueryRequest, Response, StdError, StdResult, Uint128, WasmMsg, WasmQuery,
+    from_binary, to_binary, Addr, BalanceResponse, BankQuery, CanonicalAddr, Coin, CosmosMsg,
+    Decimal, Deps, DepsMut, Env, MessageInfo, Order, QueryRequest, Response, StdError, StdResult,
+    Storage, Uint128, WasmMsg, WasmQuery,
 };
+use cw20::Cw20ReceiveMsg;
+use cw_storage_plus::Bound;
 use hex;
+use terra_cosmwasm::TerraQuerier;
 use terraswap::asset::{Asset, AssetInfo};
+use terraswap::querier::query_token_balance;
+use tns::admin::{self, Role, RolesResponse};
+use tns::oracle::{ExchangeRateResponse, QueryMsg as OracleQueryMsg};
 use tns::registrar::{
-    ExecuteMsg as RegistrarExecuteMsg, Extension, GetBaseNodeResponse, GetRegistryResponse,
-    IsAvailableResponse, QueryMsg as RegistrarQueryMsg,
+    ExecuteMsg as RegistrarExecuteMsg, Extension, GetBaseNodeResponse, GetExpiresResponse,
+    GetGracePeriodResponse, GetRegistryResponse, IsAvailableResponse,
+    QueryMsg as RegistrarQueryMsg,
 };
 use tns::registry::ExecuteMsg as RegistryExecuteMsg;
 use tns::resolver::ExecuteMsg as ResolverExecuteMsg;
-use tns::utils::{get_label_from_name, get_token_id_from_label, keccak256};
+use tns::utils::{get_label_from_name, get_token_id_from_label, keccak256, normalize_name};
 use unicode_segmentation::UnicodeSegmentation;
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+// 30 days
+const DEFAULT_RENEWAL_WINDOW: u64 = 2_592_000;
+
+const PREMIUM_PRECISION: u128 = 1_000_000_000_000_000_000;
+// 2^(-i/16) for i in 0..=16, scaled by PREMIUM_PRECISION
+const PREMIUM_DECAY_STEPS: [u128; 17] = [
+    1_000_000_000_000_000_000,
+    957_603_280_698_573_646,
+    917_004_043_204_671_231,
+    878_126_080_186_649_741,
+    840_896_415_253_714_543,
+    805_245_165_974_627_154,
+    771_105_412_703_970_411,
+    738_413_072_969_749_655,
+    707_106_781_186_547_524,
+    677_127_773_468_446_364,
+    648_419_777_325_504_832,
+    620_928_906_036_742_024,
+    594_603_557_501_360_533,
+    569_394_317_378_345_826,
+    545_253_866_332_628_829,
+    522_136_891_213_706_920,
+    500_000_000_000_000_000,
+];
+
 fn only_owner(deps: Deps, info: &MessageInfo) -> Result<bool, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
@@ -33,28 +80,279 @@ fn only_owner(deps: Deps, info: &MessageInfo) -> Result<bool, ContractError> {
     Ok(true)
 }
 
-pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-    only_owner(deps.as_ref(), &info)?;
+fn only_role(deps: Deps, info: &MessageInfo, role: Role) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    admin::only_role(deps.storage, deps.api, &config.owner, &info.sender, role)?;
+    Ok(())
+}
+
+/// Funds paying for a registration or renewal
+pub enum Payment {
+    Native(Vec<Coin>),
+    Cw20 { token: Addr, amount: Uint128 },
+}
 
-    let balance_response: BalanceResponse =
-        deps.querier.query(&QueryRequest::Bank(BankQuery::Balance {
-            address: env.contract.address.to_string(),
+fn load_payment_config(storage: &dyn Storage) -> StdResult<PaymentConfig> {
+    let payment_config = PAYMENT_CONFIG.may_load(storage)?;
+    Ok(payment_config.unwrap_or(PaymentConfig {
+        oracle: None,
+        accepted_assets: vec![AssetInfo::NativeToken {
             denom: String::from(REGISTER_FEE_DENOM),
-        }))?;
-    let amount = balance_response.amount.amount;
-    let total_asset = Asset {
-        info: AssetInfo::NativeToken {
+        }],
+        slippage_tolerance: Decimal::zero(),
+    }))
+}
+
+fn accepted_denoms(payment_config: &PaymentConfig) -> Vec<String> {
+    payment_config
+        .accepted_assets
+        .iter()
+        .filter_map(|asset_info| match asset_info {
+            AssetInfo::NativeToken { denom } => Some(denom.clone()),
+            AssetInfo::Token { .. } => None,
+        })
+        .collect()
+}
+
+/// Splits "alice.luna" into its label and TLD, names without a TLD use the default one.
+pub fn split_name(name: &str) -> (&str, Option<&str>) {
+    match name.rsplit_once('.') {
+        Some((label, tld)) => (label, Some(tld)),
+        None => (name, None),
+    }
+}
+
+// Label of `name` and the settings of the TLD it is registered under
+fn resolve_tld(deps: Deps, name: &str) -> StdResult<(String, Tld)> {
+    let (label, tld) = split_name(name);
+    let tld = match tld {
+        Some(tld) => TLDS
+            .may_load(deps.storage, tld.to_string())?
+            .ok_or_else(|| StdError::generic_err(format!("Unknown TLD {}", tld)))?,
+        None => {
+            let config = CONFIG.load(deps.storage)?;
+            Tld {
+                registrar_address: config.registrar_address,
+                price_tiers: PRICE_TIERS.load(deps.storage)?,
+                min_registration_duration: config.min_registration_duration,
+                enable_registration: config.enable_registration,
+            }
+        }
+    };
+    Ok((label.to_string(), tld))
+}
+
+/// Converts an amount of REGISTER_FEE_DENOM into `asset_info`.
+fn convert_price(
+    deps: Deps,
+    payment_config: &PaymentConfig,
+    amount: Uint128,
+    asset_info: &AssetInfo,
+) -> StdResult<Uint128> {
+    match asset_info {
+        AssetInfo::NativeToken { denom } if denom == REGISTER_FEE_DENOM => return Ok(amount),
+        // Whitelisted CW20 tokens are priced by their multiplier instead of the oracle
+        AssetInfo::Token { contract_addr } => {
+            if let Some(price_multiplier) =
+                CW20_TOKENS.may_load(deps.storage, contract_addr.clone())?
+            {
+                return Ok(amount * price_multiplier);
+            }
+        }
+        _ => {}
+    }
+    let rate = match (&payment_config.oracle, asset_info) {
+        (Some(oracle), _) => {
+            let response: ExchangeRateResponse =
+                deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                    contract_addr: deps.api.addr_humanize(oracle)?.to_string(),
+                    msg: to_binary(&OracleQueryMsg::ExchangeRate {
+                        asset: asset_info.clone(),
+                    })?,
+                }))?;
+            response.rate
+        }
+        (None, AssetInfo::NativeToken { denom }) => {
+            let response = TerraQuerier::new(&deps.querier)
+                .query_exchange_rates(REGISTER_FEE_DENOM, vec![denom.as_str()])?;
+            response
+                .exchange_rates
+                .into_iter()
+                .find(|item| &item.quote_denom == denom)
+                .map(|item| item.exchange_rate)
+                .ok_or_else(|| StdError::generic_err(format!("No exchange rate for {}", denom)))?
+        }
+        (None, AssetInfo::Token { contract_addr }) => {
+            return Err(StdError::generic_err(format!(
+                "No price feed for token {}",
+                contract_addr
+            )))
+        }
+    };
+    Ok(amount * rate)
+}
+
+// Fees collected in every accepted asset, deposits of live commitments excluded
+fn collected_fees(deps: Deps, env: &Env) -> StdResult<Vec<Asset>> {
+    let payment_config = load_payment_config(deps.storage)?;
+    let unclaimed_rewards = UNCLAIMED_REFERRAL_REWARDS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let mut fees: Vec<Asset> = vec![];
+    for denom in accepted_denoms(&payment_config) {
+        let balance_response: BalanceResponse =
+            deps.querier.query(&QueryRequest::Bank(BankQuery::Balance {
+                address: env.contract.address.to_string(),
+                denom,
+            }))?;
+        let mut amount = balance_response.amount.amount;
+        // Deposits of live commitments and renewal credits are not earned yet
+        if balance_response.amount.denom == REGISTER_FEE_DENOM {
+            let pending = PENDING_DEPOSITS.may_load(deps.storage)?.unwrap_or_default();
+            let credits = TOTAL_RENEWAL_CREDITS
+                .may_load(deps.storage)?
+                .unwrap_or_default();
+            amount = amount.saturating_sub(pending + credits);
+        }
+        let info = AssetInfo::NativeToken {
             denom: balance_response.amount.denom,
-        },
-        amount,
+        };
+        let amount = amount.saturating_sub(asset_amount(&unclaimed_rewards, &info));
+        if amount.is_zero() {
+            continue;
+        }
+        fees.push(Asset { info, amount });
+    }
+    for token in CW20_TOKENS.keys(deps.storage, None, None, Order::Ascending) {
+        let token = String::from_utf8(token)?;
+        let amount = query_token_balance(
+            &deps.querier,
+            deps.api.addr_validate(&token)?,
+            env.contract.address.clone(),
+        )?;
+        let info = AssetInfo::Token {
+            contract_addr: token,
+        };
+        let amount = amount.saturating_sub(asset_amount(&unclaimed_rewards, &info));
+        if amount.is_zero() {
+            continue;
+        }
+        fees.push(Asset { info, amount });
+    }
+    Ok(fees)
+}
+
+// Splits `fees` between the fee recipients, the last one also gets the rounding dust
+fn distribute_fees(
+    deps: Deps,
+    fees: &[Asset],
+    fee_recipients: &[FeeRecipient],
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for fee in fees.iter() {
+        let mut remaining = fee.amount;
+        for (i, fee_recipient) in fee_recipients.iter().enumerate() {
+            let amount = if i + 1 == fee_recipients.len() {
+                remaining
+            } else {
+                fee.amount * fee_recipient.share
+            };
+            remaining = remaining.checked_sub(amount)?;
+            if amount.is_zero() {
+                continue;
+            }
+            let share = Asset {
+                info: fee.info.clone(),
+                amount,
+            };
+            let recipient = deps.api.addr_humanize(&fee_recipient.recipient)?;
+            messages.push(share.into_msg(&deps.querier, recipient)?);
+        }
+    }
+    Ok(messages)
+}
+
+fn fees_to_string(fees: &[Asset]) -> String {
+    fees.iter()
+        .map(|fee| fee.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Sends the collected fees to the owner, or splits them by the fee shares if set.
+pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::Treasury)?;
+
+    let fees = collected_fees(deps.as_ref(), &env)?;
+    let fee_recipients = FEE_SHARES.may_load(deps.storage)?.unwrap_or_default();
+    let messages = if fee_recipients.is_empty() {
+        fees.iter()
+            .map(|fee| fee.clone().into_msg(&deps.querier, info.sender.clone()))
+            .collect::<StdResult<Vec<CosmosMsg>>>()?
+    } else {
+        distribute_fees(deps.as_ref(), &fees, &fee_recipients)?
     };
-    let message = total_asset.into_msg(&deps.querier, info.sender);
     Ok(Response::new()
-        .add_message(message?)
+        .add_messages(messages)
         .add_attribute("method", "withdraw")
-        .add_attribute("amount", amount))
+        .add_attribute("amount", fees_to_string(&fees)))
 }
 
+pub fn distribute(deps: DepsMut, env: Env, _info: MessageInfo) -> Result<Response, ContractError> {
+    let fee_recipients = FEE_SHARES.may_load(deps.storage)?.unwrap_or_default();
+    if fee_recipients.is_empty() {
+        return Err(ContractError::BadRequest {
+            msg: String::from("fee shares are not set"),
+        });
+    }
+    let fees = collected_fees(deps.as_ref(), &env)?;
+    let messages = distribute_fees(deps.as_ref(), &fees, &fee_recipients)?;
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "distribute")
+        .add_attribute("amount", fees_to_string(&fees)))
+}
+
+pub fn set_fee_shares(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    shares: Vec<FeeShare>,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::Treasury)?;
+    if !shares.is_empty() {
+        let total = shares
+            .iter()
+            .fold(Decimal::zero(), |total, fee_share| total + fee_share.share);
+        if total != Decimal::one() {
+            return Err(ContractError::BadRequest {
+                msg: String::from("fee shares must add up to 1"),
+            });
+        }
+    }
+    let fee_recipients = shares
+        .iter()
+        .map(|fee_share| {
+            Ok(FeeRecipient {
+                recipient: deps.api.addr_canonicalize(&fee_share.recipient)?,
+                share: fee_share.share,
+            })
+        })
+        .collect::<StdResult<Vec<FeeRecipient>>>()?;
+    FEE_SHARES.save(deps.storage, &fee_recipients)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_fee_shares")
+        .add_attribute(
+            "shares",
+            shares
+                .iter()
+                .map(|fee_share| format!("{}:{}", fee_share.recipient, fee_share.share))
+                .collect::<Vec<String>>()
+                .join(","),
+        ))
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn set_config(
     deps: DepsMut,
     _env: Env,
@@ -62,27 +360,18 @@ pub fn set_config(
     max_commitment_age: u64,
     min_commitment_age: u64,
     min_registration_duration: u64,
-    tier1_price: u64,
-    tier2_price: u64,
-    tier3_price: u64,
     registrar_address: String,
-    owner: String,
     enable_registration: bool,
 ) -> Result<Response, ContractError> {
     only_owner(deps.as_ref(), &info)?;
     let mut config = CONFIG.load(deps.storage)?;
 
     let registrar_address = deps.api.addr_canonicalize(registrar_address.as_str())?;
-    let owner = deps.api.addr_canonicalize(owner.as_str())?;
 
     config.max_commitment_age = max_commitment_age;
     config.min_commitment_age = min_commitment_age;
     config.min_registration_duration = min_registration_duration;
-    config.tier1_price = tier1_price;
-    config.tier2_price = tier2_price;
-    config.tier3_price = tier3_price;
     config.registrar_address = registrar_address.clone();
-    config.owner = owner.clone();
     config.enable_registration = enable_registration;
 
     CONFIG.save(deps.storage, &config)?;
@@ -94,15 +383,489 @@ pub fn set_config(
             "min_registration_duration",
             min_registration_duration.to_string(),
         )
-        .add_attribute("tier1_price", tier1_price.to_string())
-        .add_attribute("tier2_price", tier2_price.to_string())
-        .add_attribute("tier3_price", tier3_price.to_string())
         .add_attribute("registrar_address", registrar_address.clone().to_string())
         .add_attribute(
             "enable_registration",
             enable_registration.clone().to_string(),
+        ))
+}
+
+/// First step of an ownership transfer, the new owner has to accept it.
+pub fn propose_owner(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    admin::propose_owner(deps.storage, deps.api, &owner)?;
+    Ok(Response::new()
+        .add_attribute("method", "propose_owner")
+        .add_attribute("pending_owner", owner))
+}
+
+pub fn accept_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let owner = admin::accept_ownership(deps.storage, deps.api, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.owner = owner;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("method", "accept_ownership")
+        .add_attribute("owner", info.sender))
+}
+
+pub fn set_role(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    role: Role,
+    address: Option<String>,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    admin::set_role(
+        deps.storage,
+        deps.api,
+        &Role::all(),
+        &role,
+        address.as_deref(),
+    )?;
+    Ok(Response::new()
+        .add_attribute("method", "set_role")
+        .add_attribute("role", role.to_string())
+        .add_attribute("address", address.unwrap_or_default()))
+}
+
+// Sorts the tiers by min_len and rejects empty or overlapping ranges
+pub fn validate_price_tiers(tiers: &mut [PriceTier]) -> Result<(), ContractError> {
+    tiers.sort_by_key(|tier| tier.min_len);
+    for (i, tier) in tiers.iter().enumerate() {
+        if let Some(max_len) = tier.max_len {
+            if max_len < tier.min_len {
+                return Err(ContractError::BadRequest {
+                    msg: format!("Tier {} has max_len below min_len", i),
+                });
+            }
+        }
+        if let Some(next) = tiers.get(i + 1) {
+            if tier.max_len.map_or(true, |max_len| max_len >= next.min_len) {
+                return Err(ContractError::BadRequest {
+                    msg: format!("Tier {} overlaps the next tier", i),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn set_price_tiers(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    mut tiers: Vec<PriceTier>,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::PricingAdmin)?;
+    validate_price_tiers(&mut tiers)?;
+    PRICE_TIERS.save(deps.storage, &tiers)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_price_tiers")
+        .add_attribute("tiers", tiers.len().to_string()))
+}
+
+pub fn set_price_override(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    label: String,
+    price_per_year: Option<u64>,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::PricingAdmin)?;
+    if !get_is_valid_name(&label)?.is_valid_name {
+        return Err(ContractError::InvalidName {});
+    }
+    match price_per_year {
+        Some(price_per_year) => {
+            PRICE_OVERRIDES.save(deps.storage, label.clone(), &price_per_year)?
+        }
+        None => PRICE_OVERRIDES.remove(deps.storage, label.clone()),
+    }
+    Ok(Response::new()
+        .add_attribute("method", "set_price_override")
+        .add_attribute("label", label)
+        .add_attribute(
+            "price_per_year",
+            price_per_year.map_or(String::from("none"), |price| price.to_string()),
+        ))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn set_tld(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    tld: String,
+    registrar_address: String,
+    mut price_tiers: Vec<PriceTier>,
+    min_registration_duration: u64,
+    enable_registration: bool,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    if normalize_name(&tld).ok().as_deref() != Some(tld.as_str()) {
+        return Err(ContractError::BadRequest {
+            msg: format!("Invalid TLD {}", tld),
+        });
+    }
+    validate_price_tiers(&mut price_tiers)?;
+    TLDS.save(
+        deps.storage,
+        tld.clone(),
+        &Tld {
+            registrar_address: deps.api.addr_canonicalize(&registrar_address)?,
+            price_tiers,
+            min_registration_duration,
+            enable_registration,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("method", "set_tld")
+        .add_attribute("tld", tld)
+        .add_attribute("registrar_address", registrar_address)
+        .add_attribute(
+            "min_registration_duration",
+            min_registration_duration.to_string(),
+        )
+        .add_attribute("enable_registration", enable_registration.to_string()))
+}
+
+pub fn remove_tld(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    tld: String,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    TLDS.remove(deps.storage, tld.clone());
+    Ok(Response::new()
+        .add_attribute("method", "remove_tld")
+        .add_attribute("tld", tld))
+}
+
+pub fn add_reserved_names(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    names: Vec<String>,
+    claimant: Option<String>,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
+    let reservation = Reservation {
+        claimant: match &claimant {
+            Some(claimant) => Some(deps.api.addr_canonicalize(claimant)?),
+            None => None,
+        },
+    };
+    for name in names.iter() {
+        if !get_is_valid_name(name)?.is_valid_name {
+            return Err(ContractError::InvalidName {});
+        }
+        RESERVED_NAMES.save(deps.storage, name.clone(), &reservation)?;
+    }
+    Ok(Response::new()
+        .add_attribute("method", "add_reserved_names")
+        .add_attribute("names", names.join(","))
+        .add_attribute("claimant", claimant.unwrap_or_default()))
+}
+
+pub fn remove_reserved_names(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    names: Vec<String>,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
+    for name in names.iter() {
+        RESERVED_NAMES.remove(deps.storage, name.clone());
+    }
+    Ok(Response::new()
+        .add_attribute("method", "remove_reserved_names")
+        .add_attribute("names", names.join(",")))
+}
+
+pub fn add_blocked_names(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    names: Vec<String>,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
+    for name in names.iter() {
+        BLOCKED_NAMES.save(deps.storage, name.clone(), &true)?;
+    }
+    Ok(Response::new()
+        .add_attribute("method", "add_blocked_names")
+        .add_attribute("names", names.join(",")))
+}
+
+pub fn remove_blocked_names(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    names: Vec<String>,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
+    for name in names.iter() {
+        BLOCKED_NAMES.remove(deps.storage, name.clone());
+    }
+    Ok(Response::new()
+        .add_attribute("method", "remove_blocked_names")
+        .add_attribute("names", names.join(",")))
+}
+
+pub fn add_claims(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    claims: Vec<ClaimEntry>,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
+    for entry in claims.iter() {
+        if !get_is_valid_name(&entry.name)?.is_valid_name {
+            return Err(ContractError::InvalidName {});
+        }
+        CLAIMS.save(
+            deps.storage,
+            entry.name.clone(),
+            &Claim {
+                claimant: deps.api.addr_canonicalize(&entry.claimant)?,
+                duration: entry.duration,
+            },
+        )?;
+    }
+    Ok(Response::new()
+        .add_attribute("method", "add_claims")
+        .add_attribute(
+            "names",
+            claims
+                .iter()
+                .map(|entry| entry.name.clone())
+                .collect::<Vec<String>>()
+                .join(","),
+        ))
+}
+
+pub fn remove_claims(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    names: Vec<String>,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
+    for name in names.iter() {
+        CLAIMS.remove(deps.storage, name.clone());
+    }
+    Ok(Response::new()
+        .add_attribute("method", "remove_claims")
+        .add_attribute("names", names.join(",")))
+}
+
+pub fn set_claim_merkle_root(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    merkle_root: Option<String>,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
+    match &merkle_root {
+        Some(merkle_root) => {
+            let root = hex::decode(merkle_root).map_err(|_| ContractError::BadRequest {
+                msg: String::from("merkle_root must be hex encoded"),
+            })?;
+            if root.len() != 32 {
+                return Err(ContractError::BadRequest {
+                    msg: String::from("merkle_root must be 32 bytes"),
+                });
+            }
+            CLAIM_MERKLE_ROOT.save(deps.storage, &root)?;
+        }
+        None => CLAIM_MERKLE_ROOT.remove(deps.storage),
+    }
+    Ok(Response::new()
+        .add_attribute("method", "set_claim_merkle_root")
+        .add_attribute("merkle_root", merkle_root.unwrap_or_default()))
+}
+
+fn verify_claim_proof(
+    deps: Deps,
+    name: &str,
+    claimant: &str,
+    duration: u64,
+    proof: &[String],
+) -> Result<(), ContractError> {
+    let root = CLAIM_MERKLE_ROOT
+        .may_load(deps.storage)?
+        .ok_or(ContractError::ClaimNotFound {
+            name: name.to_string(),
+        })?;
+    let mut hash = keccak256(format!("{}:{}:{}", name, claimant, duration).as_bytes());
+    for sibling in proof.iter() {
+        let sibling = hex::decode(sibling).map_err(|_| ContractError::InvalidProof {})?;
+        hash = if hash <= sibling {
+            keccak256(&[hash, sibling].concat())
+        } else {
+            keccak256(&[sibling, hash].concat())
+        };
+    }
+    if hash != root {
+        return Err(ContractError::InvalidProof {});
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn claim(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    duration: Option<u64>,
+    proof: Option<Vec<String>>,
+    resolver: Option<String>,
+    address: Option<String>,
+) -> Result<Response, ContractError> {
+    let duration = match CLAIMS.may_load(deps.storage, name.clone())? {
+        Some(claim) => {
+            let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+            if claim.claimant != sender {
+                return Err(ContractError::Unauthorized {
+                    description: Some(String::from("Sender is not the claimant")),
+                });
+            }
+            CLAIMS.remove(deps.storage, name.clone());
+            claim.duration
+        }
+        None => {
+            let (duration, proof) = match (duration, proof) {
+                (Some(duration), Some(proof)) => (duration, proof),
+                _ => return Err(ContractError::ClaimNotFound { name }),
+            };
+            verify_claim_proof(deps.as_ref(), &name, info.sender.as_str(), duration, &proof)?;
+            if MERKLE_CLAIMED
+                .may_load(deps.storage, name.clone())?
+                .is_some()
+            {
+                return Err(ContractError::AlreadyClaimed { name });
+            }
+            MERKLE_CLAIMED.save(deps.storage, name.clone(), &true)?;
+            duration
+        }
+    };
+    validate_name(deps.as_ref(), name.clone())?;
+
+    let messages = _register(
+        deps.branch(),
+        env,
+        name.clone(),
+        info.sender.to_string(),
+        duration,
+        resolver,
+        address,
+    )?;
+
+    let node_info = get_node_info_from_name(deps.as_ref(), &name)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "claim")
+        .add_attribute("name", name)
+        .add_attribute("claimant", info.sender)
+        .add_attribute("token_id", node_info.token_id)
+        .add_attribute("nodehash", format!("{:?}", node_info.node)))
+}
+
+pub fn set_premium(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    start_price: u64,
+    duration: u64,
+    half_life: u64,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::PricingAdmin)?;
+    if half_life == 0 {
+        return Err(ContractError::BadRequest {
+            msg: String::from("half_life must be positive"),
+        });
+    }
+    PREMIUM.save(
+        deps.storage,
+        &Premium {
+            start_price,
+            duration,
+            half_life,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("method", "set_premium")
+        .add_attribute("start_price", start_price.to_string())
+        .add_attribute("duration", duration.to_string())
+        .add_attribute("half_life", half_life.to_string()))
+}
+
+pub fn set_payment_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    oracle: Option<String>,
+    accepted_assets: Vec<AssetInfo>,
+    slippage_tolerance: Decimal,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::PricingAdmin)?;
+    if accepted_assets.is_empty() {
+        return Err(ContractError::BadRequest {
+            msg: String::from("accepted_assets must not be empty"),
+        });
+    }
+    if slippage_tolerance > Decimal::one() {
+        return Err(ContractError::BadRequest {
+            msg: String::from("slippage_tolerance must not exceed 1"),
+        });
+    }
+    // CW20 tokens have their own whitelist, see SetCw20Token
+    if accepted_assets
+        .iter()
+        .any(|asset_info| matches!(asset_info, AssetInfo::Token { .. }))
+    {
+        return Err(ContractError::BadRequest {
+            msg: String::from("accepted_assets only takes native denoms, use SetCw20Token"),
+        });
+    }
+    let canonical_oracle = match &oracle {
+        Some(oracle) => Some(deps.api.addr_canonicalize(oracle)?),
+        None => None,
+    };
+    PAYMENT_CONFIG.save(
+        deps.storage,
+        &PaymentConfig {
+            oracle: canonical_oracle,
+            accepted_assets: accepted_assets.clone(),
+            slippage_tolerance,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("method", "set_payment_config")
+        .add_attribute("oracle", oracle.unwrap_or_default())
+        .add_attribute(
+            "accepted_assets",
+            accepted_assets
+                .iter()
+                .map(|asset_info| asset_info.to_string())
+                .collect::<Vec<String>>()
+                .join(","),
         )
-        .add_attribute("owner", owner.clone().to_string()))
+        .add_attribute("slippage_tolerance", slippage_tolerance.to_string()))
 }
 
 pub fn set_enable_registration(
@@ -111,7 +874,7 @@ pub fn set_enable_registration(
     info: MessageInfo,
     enable_registration: bool,
 ) -> Result<Response, ContractError> {
-    only_owner(deps.as_ref(), &info)?;
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
     let mut config = CONFIG.load(deps.storage)?;
     config.enable_registration = enable_registration;
     CONFIG.save(deps.storage, &config)?;
@@ -120,33 +883,203 @@ pub fn set_enable_registration(
         .add_attribute("enable_registration", enable_registration.to_string()))
 }
 
+pub fn set_commitment_deposit(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
+    COMMITMENT_DEPOSIT.save(deps.storage, &amount)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_commitment_deposit")
+        .add_attribute("amount", amount.to_string()))
+}
+
+// Returns the deposit paid with a commitment
+fn validate_commitment_deposit(deps: Deps, info: &MessageInfo) -> Result<Uint128, ContractError> {
+    let deposit = received_fee_denom(info)?;
+    let required = COMMITMENT_DEPOSIT
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    if deposit < required {
+        return Err(ContractError::InsufficientFund {
+            amount: deposit,
+            required,
+        });
+    }
+    Ok(deposit)
+}
+
+// Amount of REGISTER_FEE_DENOM sent, no other denom is accepted
+fn received_fee_denom(info: &MessageInfo) -> Result<Uint128, ContractError> {
+    if let Some(unexpected) = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom != REGISTER_FEE_DENOM && !coin.amount.is_zero())
+    {
+        return Err(ContractError::UnexpectedFund {
+            denom: unexpected.denom.clone(),
+        });
+    }
+    Ok(info
+        .funds
+        .iter()
+        .find(|coin| coin.denom == REGISTER_FEE_DENOM)
+        .map(|coin| coin.amount)
+        .unwrap_or_default())
+}
+
+fn release_deposit(storage: &mut dyn Storage, deposit: Uint128) -> StdResult<()> {
+    let pending = PENDING_DEPOSITS.may_load(storage)?.unwrap_or_default();
+    PENDING_DEPOSITS.save(storage, &pending.saturating_sub(deposit))
+}
+
 pub fn commit(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     commitment: String,
 ) -> Result<Response, ContractError> {
-    validate_enable_registration(deps.as_ref())?;
+    validate_enable_registration(deps.as_ref(), None)?;
+    if hex::decode(&commitment).map_or(true, |hash| hash.len() != 32) {
+        return Err(ContractError::BadRequest {
+            msg: String::from("commitment must be a hex encoded 32 bytes hash"),
+        });
+    }
 
     let config = CONFIG.load(deps.storage)?;
-
-    let last_commit_time = COMMITMENTS
-        .may_load(deps.storage, commitment.clone())?
-        .unwrap_or(0);
     let current = env.block.time.seconds();
-
-    if last_commit_time + config.max_commitment_age > current {
-        return Err(ContractError::RecommitTooEarly {
-            commit_expired: last_commit_time + config.max_commitment_age,
-            current,
-        });
+    let committer = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let key = (committer.to_vec(), commitment.clone());
+
+    if let Some(last_commitment) = COMMITMENTS.may_load(deps.storage, key.clone())? {
+        if last_commitment.timestamp + config.max_commitment_age > current {
+            return Err(ContractError::RecommitTooEarly {
+                commit_expired: last_commitment.timestamp + config.max_commitment_age,
+                current,
+            });
+        }
+        // The deposit of the expired commitment is forfeited
+        release_deposit(deps.storage, last_commitment.deposit)?;
     }
 
-    COMMITMENTS.save(deps.storage, commitment.clone(), &current)?;
+    let deposit = validate_commitment_deposit(deps.as_ref(), &info)?;
+    COMMITMENTS.save(
+        deps.storage,
+        key,
+        &Commitment {
+            committer,
+            timestamp: current,
+            deposit,
+        },
+    )?;
+    let pending = PENDING_DEPOSITS.may_load(deps.storage)?.unwrap_or_default();
+    PENDING_DEPOSITS.save(deps.storage, &(pending + deposit))?;
 
     Ok(Response::new()
         .add_attribute("method", "commit")
-        .add_attribute("commitment", commitment))
+        .add_attribute("commitment", commitment)
+        .add_attribute("deposit", deposit.to_string()))
+}
+
+// Composite keys are the length prefixed committer followed by the commitment
+fn commitment_key(key: &[u8]) -> (Vec<u8>, String) {
+    let committer_len = u16::from_be_bytes([key[0], key[1]]) as usize;
+    (
+        key[2..2 + committer_len].to_vec(),
+        String::from_utf8_lossy(&key[2 + committer_len..]).to_string(),
+    )
+}
+
+// Older versions priced names by three tiers kept in CONFIG
+pub fn migrate_price_tiers(storage: &mut dyn Storage) -> StdResult<()> {
+    if PRICE_TIERS.may_load(storage)?.is_some() {
+        return Ok(());
+    }
+    let legacy = LEGACY_PRICE_CONFIG.load(storage)?;
+    PRICE_TIERS.save(
+        storage,
+        &vec![
+            PriceTier {
+                min_len: 3,
+                max_len: Some(3),
+                price_per_year: legacy.tier1_price,
+            },
+            PriceTier {
+                min_len: 4,
+                max_len: Some(4),
+                price_per_year: legacy.tier2_price,
+            },
+            PriceTier {
+                min_len: 5,
+                max_len: None,
+                price_per_year: legacy.tier3_price,
+            },
+        ],
+    )?;
+    // Drops the legacy fields from the stored config
+    let config = CONFIG.load(storage)?;
+    CONFIG.save(storage, &config)
+}
+
+pub fn sweep_legacy_commitments(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let keys = LEGACY_COMMITMENTS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<Vec<Vec<u8>>>();
+    for key in keys.iter() {
+        LEGACY_COMMITMENTS.remove(deps.storage, String::from_utf8_lossy(key).to_string());
+    }
+    Ok(Response::new()
+        .add_attribute("method", "sweep_legacy_commitments")
+        .add_attribute("removed", keys.len().to_string()))
+}
+
+pub fn sweep_commitments(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let current = env.block.time.seconds();
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|key| hex::decode(key).map(Bound::exclusive))
+        .transpose()
+        .map_err(|_| ContractError::BadRequest {
+            msg: String::from("start_after must be the last key of a previous sweep"),
+        })?;
+
+    let commitments = COMMITMENTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(Vec<u8>, Commitment)>>>()?;
+    let last = commitments.last().map(|(key, _)| hex::encode(key));
+    let mut removed = 0u32;
+    let mut forfeited = Uint128::zero();
+    for (key, commitment) in commitments {
+        if commitment.timestamp + config.max_commitment_age < current {
+            COMMITMENTS.remove(deps.storage, commitment_key(&key));
+            forfeited += commitment.deposit;
+            removed += 1;
+        }
+    }
+    release_deposit(deps.storage, forfeited)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "sweep_commitments")
+        .add_attribute("removed", removed.to_string())
+        .add_attribute("forfeited", forfeited.to_string())
+        .add_attribute("last", last.unwrap_or_default()))
 }
 
 fn validate_name(deps: Deps, name: String) -> Result<(), ContractError> {
@@ -160,18 +1093,50 @@ fn validate_name(deps: Deps, name: String) -> Result<(), ContractError> {
     Ok(())
 }
 
+fn validate_not_blocked(deps: Deps, name: &str) -> Result<(), ContractError> {
+    let (label, _) = split_name(name);
+    if BLOCKED_NAMES
+        .may_load(deps.storage, label.to_string())?
+        .is_some()
+    {
+        return Err(ContractError::BlockedName {
+            name: name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// Reserved names can only be registered by their claimant outside of OwnerRegister
+fn validate_not_reserved(deps: Deps, name: &str, sender: &str) -> Result<(), ContractError> {
+    let (label, _) = split_name(name);
+    if let Some(reservation) = RESERVED_NAMES.may_load(deps.storage, label.to_string())? {
+        let sender = deps.api.addr_canonicalize(sender)?;
+        if reservation.claimant != Some(sender) {
+            return Err(ContractError::ReservedName {
+                name: name.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Removes a mature commitment of `sender` and returns its deposit
 pub fn consume_commitment(
     deps: DepsMut,
     env: Env,
+    sender: &str,
     commitment: String,
-) -> Result<(), ContractError> {
+) -> Result<Uint128, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let commit_time = COMMITMENTS.may_load(deps.storage, commitment.clone())?;
-    if commit_time.is_none() {
-        return Err(ContractError::ConsumeNonexistCommitment { commitment });
-    }
-
-    let commit_time = commit_time.unwrap();
+    // Only the committer can reveal, commitments of other senders are not found
+    let key = (
+        deps.api.addr_canonicalize(sender)?.to_vec(),
+        commitment.clone(),
+    );
+    let stored = COMMITMENTS
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::ConsumeNonexistCommitment { commitment })?;
+    let commit_time = stored.timestamp;
     let current = env.block.time.seconds();
     if commit_time + config.min_commitment_age > current
         || commit_time + config.max_commitment_age < current
@@ -183,38 +1148,125 @@ pub fn consume_commitment(
         });
     }
 
-    COMMITMENTS.remove(deps.storage, commitment);
-    Ok(())
+    COMMITMENTS.remove(deps.storage, key);
+    release_deposit(deps.storage, stored.deposit)?;
+    Ok(stored.deposit)
+}
+
+// start_price * 2^(-elapsed / half_life), interpolated linearly between 1/16 half lives
+fn decayed_premium(premium: &Premium, elapsed: u64) -> Uint128 {
+    let halvings = elapsed / premium.half_life;
+    if halvings >= 64 {
+        return Uint128::zero();
+    }
+    let remainder = (elapsed % premium.half_life) as u128 * 16;
+    let half_life = premium.half_life as u128;
+    let step = (remainder / half_life) as usize;
+    let fraction = remainder % half_life;
+    let (high, low) = (PREMIUM_DECAY_STEPS[step], PREMIUM_DECAY_STEPS[step + 1]);
+    let factor = high - (high - low) * fraction / half_life;
+    let value = (premium.start_price as u128 * factor) >> halvings;
+    Uint128::from(value / PREMIUM_PRECISION)
+}
+
+// Expiry of `name` in its registrar, 0 if never registered, and the registrar grace period
+fn get_expires(deps: Deps, name: &str) -> StdResult<(u64, u64)> {
+    let (label, tld) = resolve_tld(deps, name)?;
+    let registrar_address = deps.api.addr_humanize(&tld.registrar_address)?;
+    let id = get_token_id_from_label(&get_label_from_name(&label));
+    let expires_response: GetExpiresResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: registrar_address.to_string(),
+            msg: to_binary(&RegistrarQueryMsg::GetExpires { id })?,
+        }))?;
+    let grace_period_response: GetGracePeriodResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: registrar_address.to_string(),
+            msg: to_binary(&RegistrarQueryMsg::GetGracePeriod {})?,
+        }))?;
+    Ok((expires_response.expires, grace_period_response.grace_period))
+}
+
+/// Premium on a name whose previous registration ended recently, zero otherwise.
+pub fn get_premium(deps: Deps, env: &Env, name: &str) -> StdResult<Uint128> {
+    let premium = match PREMIUM.may_load(deps.storage)? {
+        Some(premium) if premium.start_price > 0 => premium,
+        _ => return Ok(Uint128::zero()),
+    };
+    let (expires, grace_period) = get_expires(deps, name)?;
+    // Names never registered before have no premium
+    if expires == 0 {
+        return Ok(Uint128::zero());
+    }
+    let released_at = expires + grace_period;
+    let now = env.block.time.seconds();
+    if now <= released_at || now - released_at >= premium.duration {
+        return Ok(Uint128::zero());
+    }
+    // Shifted down so the premium reaches exactly zero at the end of the window
+    let premium_price = decayed_premium(&premium, now - released_at)
+        .checked_sub(decayed_premium(&premium, premium.duration))?;
+    Ok(premium_price)
+}
+
+pub fn get_premium_config(deps: Deps) -> StdResult<PremiumResponse> {
+    let premium = PREMIUM.may_load(deps.storage)?.unwrap_or(Premium {
+        start_price: 0,
+        duration: 0,
+        half_life: 0,
+    });
+    Ok(PremiumResponse {
+        start_price: premium.start_price,
+        duration: premium.duration,
+        half_life: premium.half_life,
+    })
 }
 
 pub fn get_cost(deps: Deps, name: String, duration: u64) -> Result<Uint128, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    let min_duration = config.min_registration_duration;
-    let name_length = name.graphemes(true).count();
+    let (label, tld) = resolve_tld(deps, &name)?;
+    let min_duration = tld.min_registration_duration;
+    let label = normalize_name(&label).map_err(|_| ContractError::InvalidName {})?;
+    let name_length = label.graphemes(true).count();
     if name_length < 3 {
         return Err(ContractError::NameTooShort {});
     }
     if duration < min_duration {
         return Err(ContractError::DurationTooShort {
             input_duration: duration,
-            min_duration: min_duration,
+            min_duration,
         });
     }
 
-    let base_cost = match name_length {
-        3 => config.tier1_price,
-        4 => config.tier2_price,
-        _ => config.tier3_price,
+    let base_cost = match PRICE_OVERRIDES.may_load(deps.storage, label)? {
+        Some(price_per_year) => price_per_year,
+        None => {
+            let length = name_length as u32;
+            tld.price_tiers
+                .iter()
+                .find(|tier| {
+                    tier.min_len <= length && tier.max_len.map_or(true, |max_len| length <= max_len)
+                })
+                .ok_or(ContractError::NoPriceTier { length })?
+                .price_per_year
+        }
     };
     Ok(Uint128::from(base_cost).multiply_ratio(duration, 31_536_000u64))
 }
 
 pub fn get_price(deps: Deps) -> StdResult<PriceResponse> {
-    let config = CONFIG.load(deps.storage)?;
+    let overrides = PRICE_OVERRIDES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (label, price_per_year) = item?;
+            Ok(PriceOverride {
+                label: String::from_utf8(label)?,
+                price_per_year,
+            })
+        })
+        .collect::<StdResult<Vec<PriceOverride>>>()?;
     Ok(PriceResponse {
-        tier1_price: config.tier1_price,
-        tier2_price: config.tier2_price,
-        tier3_price: config.tier3_price,
+        tiers: PRICE_TIERS.load(deps.storage)?,
+        overrides,
     })
 }
 
@@ -229,13 +1281,10 @@ fn _register(
 ) -> Result<Vec<CosmosMsg>, ContractError> {
     let mut messages: Vec<CosmosMsg> = vec![];
 
-    let config = CONFIG.load(deps.storage)?;
-    let registrar_address = deps
-        .api
-        .addr_humanize(&config.registrar_address)?
-        .to_string();
+    let (label_name, tld) = resolve_tld(deps.as_ref(), &name)?;
+    let registrar_address = deps.api.addr_humanize(&tld.registrar_address)?.to_string();
 
-    let label: Vec<u8> = get_label_from_name(&name);
+    let label: Vec<u8> = get_label_from_name(&label_name);
     let token_id = get_token_id_from_label(&label);
 
     // Register this contract to be temporary owner of the node at registrar
@@ -244,7 +1293,7 @@ fn _register(
         msg: to_binary(&RegistrarExecuteMsg::<Extension>::Register {
             id: token_id.clone(),
             owner: env.contract.address.to_string(),
-            name: name.clone(),
+            name: label_name,
             duration,
         })?,
         funds: vec![],
@@ -259,7 +1308,12 @@ fn _register(
     let registry_address = String::from(get_registry_response.registry);
 
     // Set resolver of the node at registry
-    let nodehash = get_nodehash(deps.as_ref(), label)?;
+    let nodehash = get_nodehash(deps.as_ref(), &tld.registrar_address, label)?;
+    // Renewal credits deposited for an earlier registration stop paying renewals
+    let registrations = REGISTRATIONS
+        .may_load(deps.storage, hex::encode(&nodehash))?
+        .unwrap_or_default();
+    REGISTRATIONS.save(deps.storage, hex::encode(&nodehash), &(registrations + 1))?;
     let registry_set_resolver_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: registry_address.clone(),
         msg: to_binary(&RegistryExecuteMsg::SetResolver {
@@ -276,7 +1330,7 @@ fn _register(
             contract_addr: resolver.unwrap_or(registry_address),
             msg: to_binary(&ResolverExecuteMsg::SetTerraAddress {
                 node: nodehash,
-                address: address,
+                address,
             })?,
             funds: vec![],
         });
@@ -299,7 +1353,7 @@ fn _register(
         contract_addr: registrar_address,
         msg: to_binary(&RegistrarExecuteMsg::<Extension>::TransferNft {
             recipient: owner,
-            token_id: token_id,
+            token_id,
         })?,
         funds: vec![],
     });
@@ -308,68 +1362,178 @@ fn _register(
     Ok(messages)
 }
 
+/// Checks the payment and returns the refund of any excess to the sender
 fn validate_register_fund(
     deps: Deps,
-    _env: Env,
-    info: MessageInfo,
+    env: Env,
+    sender: &Addr,
+    payment: &Payment,
     name: String,
     duration: u64,
-) -> Result<(), ContractError> {
-    let cost: Uint128 = get_cost(deps, name.clone(), duration)?;
+) -> Result<(Asset, Vec<CosmosMsg>), ContractError> {
+    let premium = get_premium(deps, &env, &name)?;
+    let cost: Uint128 = get_cost(deps, name, duration)? + premium;
+    validate_fund(deps, sender, payment, cost)
+}
+
+// Validates a payment of `cost`, returns the payment kept and the refund of the excess to `sender`
+fn validate_fund(
+    deps: Deps,
+    sender: &Addr,
+    payment: &Payment,
+    cost: Uint128,
+) -> Result<(Asset, Vec<CosmosMsg>), ContractError> {
+    match payment {
+        Payment::Native(funds) => validate_native_fund(deps, sender, funds, cost),
+        Payment::Cw20 { token, amount } => {
+            let payment_config = load_payment_config(deps.storage)?;
+            let asset_info = AssetInfo::Token {
+                contract_addr: token.to_string(),
+            };
+            let required = convert_price(deps, &payment_config, cost, &asset_info)?;
+            if *amount < required {
+                return Err(ContractError::InsufficientFund {
+                    amount: *amount,
+                    required,
+                });
+            }
+            let paid = Asset {
+                info: asset_info.clone(),
+                amount: required,
+            };
+            if *amount == required {
+                return Ok((paid, vec![]));
+            }
+            let refund = Asset {
+                info: asset_info,
+                amount: *amount - required,
+            };
+            Ok((paid, vec![refund.into_msg(&deps.querier, sender.clone())?]))
+        }
+    }
+}
+
+// Validates a payment of `cost` in any accepted denom
+fn validate_native_fund(
+    deps: Deps,
+    sender: &Addr,
+    funds: &[Coin],
+    cost: Uint128,
+) -> Result<(Asset, Vec<CosmosMsg>), ContractError> {
+    let payment_config = load_payment_config(deps.storage)?;
+    let denoms = accepted_denoms(&payment_config);
     let base_fund = &Coin {
         denom: String::from(REGISTER_FEE_DENOM),
         amount: Uint128::from(0u128),
     };
-    let fund = info
-        .funds
+    let fund = funds
         .iter()
-        .find(|fund| fund.denom == String::from(REGISTER_FEE_DENOM))
+        .find(|fund| denoms.contains(&fund.denom) && !fund.amount.is_zero())
         .unwrap_or(base_fund);
-    if fund.amount < cost {
+    // Only one denom is used for the payment, anything else would be kept by the contract
+    if let Some(unexpected) = funds
+        .iter()
+        .find(|coin| coin.denom != fund.denom && !coin.amount.is_zero())
+    {
+        return Err(ContractError::UnexpectedFund {
+            denom: unexpected.denom.clone(),
+        });
+    }
+    let required = convert_price(
+        deps,
+        &payment_config,
+        cost,
+        &AssetInfo::NativeToken {
+            denom: fund.denom.clone(),
+        },
+    )?;
+    // Oracle prices may move between the quote and the transaction
+    let minimum = required - required * payment_config.slippage_tolerance;
+    if fund.amount < minimum {
         return Err(ContractError::InsufficientFund {
             amount: fund.amount,
-            required: cost,
+            required,
         });
     }
 
-    Ok(())
+    let asset_info = AssetInfo::NativeToken {
+        denom: fund.denom.clone(),
+    };
+    if fund.amount <= required {
+        let paid = Asset {
+            info: asset_info,
+            amount: fund.amount,
+        };
+        return Ok((paid, vec![]));
+    }
+    let paid = Asset {
+        info: asset_info.clone(),
+        amount: required,
+    };
+    let refund = Asset {
+        info: asset_info,
+        amount: fund.amount - required,
+    };
+    Ok((paid, vec![refund.into_msg(&deps.querier, sender.clone())?]))
 }
 
-fn validate_enable_registration(deps: Deps) -> Result<(), ContractError> {
+// The config flag closes registration for every TLD, including commitments
+fn validate_enable_registration(deps: Deps, name: Option<&str>) -> Result<(), ContractError> {
     let config = CONFIG.load(deps.storage)?;
     if !config.enable_registration {
         return Err(ContractError::RegistrationDisabled {});
     }
+    if let Some(name) = name {
+        if !resolve_tld(deps, name)?.1.enable_registration {
+            return Err(ContractError::RegistrationDisabled {});
+        }
+    }
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn register(
     mut deps: DepsMut,
     env: Env,
-    info: MessageInfo,
+    sender: Addr,
+    payment: Payment,
     name: String,
     owner: String,
     duration: u64,
     secret: String,
     resolver: Option<String>,
     address: Option<String>,
+    referrer: Option<String>,
 ) -> Result<Response, ContractError> {
     validate_name(deps.as_ref(), name.clone())?;
-    validate_enable_registration(deps.as_ref())?;
+    validate_not_blocked(deps.as_ref(), &name)?;
+    validate_not_reserved(deps.as_ref(), &name, sender.as_str())?;
+    validate_enable_registration(deps.as_ref(), Some(&name))?;
 
     let commitment_response = get_commitment(&name, &owner, &secret, &resolver, &address)?;
     let commitment = commitment_response.commitment;
-    consume_commitment(deps.branch(), env.clone(), commitment)?;
+    let deposit = consume_commitment(deps.branch(), env.clone(), sender.as_str(), commitment)?;
 
-    validate_register_fund(
+    let (paid, mut refund_messages) = validate_register_fund(
         deps.as_ref(),
         env.clone(),
-        info,
+        &sender,
+        &payment,
         name.clone(),
-        duration.clone(),
+        duration,
     )?;
+    let referral = add_referral_reward(deps.branch(), &sender, referrer, &paid)?;
+    if !deposit.is_zero() {
+        let deposit = Asset {
+            info: AssetInfo::NativeToken {
+                denom: String::from(REGISTER_FEE_DENOM),
+            },
+            amount: deposit,
+        };
+        refund_messages.push(deposit.into_msg(&deps.querier, sender)?);
+    }
 
-    let messages = _register(
+    let mut messages = _register(
         deps.branch(),
         env.clone(),
         name.clone(),
@@ -378,20 +1542,21 @@ pub fn register(
         resolver,
         address,
     )?;
+    messages.extend(refund_messages);
 
-    let label: Vec<u8> = get_label_from_name(&name);
-    let token_id = get_token_id_from_label(&label);
-    let nodehash = get_nodehash(deps.as_ref(), label.clone())?;
+    let node_info = get_node_info_from_name(deps.as_ref(), &name)?;
 
     Ok(Response::new()
         .add_messages(messages)
         .add_attribute("method", "register")
         .add_attribute("name", name)
-        .add_attribute("label", format!("{:?}", label.clone()))
-        .add_attribute("token_id", token_id)
-        .add_attribute("nodehash", format!("{:?}", nodehash)))
+        .add_attribute("label", format!("{:?}", node_info.label))
+        .add_attribute("token_id", node_info.token_id)
+        .add_attribute("nodehash", format!("{:?}", node_info.node))
+        .add_attributes(referral_attributes(referral)))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn owner_register(
     mut deps: DepsMut,
     env: Env,
@@ -402,7 +1567,8 @@ pub fn owner_register(
     resolver: Option<String>,
     address: Option<String>,
 ) -> Result<Response, ContractError> {
-    only_owner(deps.as_ref(), &info)?;
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
+    validate_not_blocked(deps.as_ref(), &name)?;
 
     if !is_available_name(deps.as_ref(), &name)? {
         return Err(ContractError::UnavailabledName {});
@@ -418,32 +1584,22 @@ pub fn owner_register(
         address,
     )?;
 
-    let label: Vec<u8> = get_label_from_name(&name);
-    let token_id = get_token_id_from_label(&label);
-    let nodehash = get_nodehash(deps.as_ref(), label.clone())?;
+    let node_info = get_node_info_from_name(deps.as_ref(), &name)?;
 
     Ok(Response::new()
         .add_messages(messages)
         .add_attribute("method", "owner_register")
         .add_attribute("name", name)
-        .add_attribute("label", format!("{:?}", label.clone()))
-        .add_attribute("token_id", token_id)
-        .add_attribute("nodehash", format!("{:?}", nodehash)))
+        .add_attribute("label", format!("{:?}", node_info.label))
+        .add_attribute("token_id", node_info.token_id)
+        .add_attribute("nodehash", format!("{:?}", node_info.node)))
 }
 
-fn _renew(
-    deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
-    token_id: String,
-    duration: u64,
-) -> Result<Vec<CosmosMsg>, ContractError> {
+fn _renew(deps: DepsMut, name: &str, duration: u64) -> Result<Vec<CosmosMsg>, ContractError> {
     let mut messages: Vec<CosmosMsg> = vec![];
-    let config = CONFIG.load(deps.storage)?;
-    let registrar_address = deps
-        .api
-        .addr_humanize(&config.registrar_address)?
-        .to_string();
+    let (label, tld) = resolve_tld(deps.as_ref(), name)?;
+    let registrar_address = deps.api.addr_humanize(&tld.registrar_address)?.to_string();
+    let token_id = get_token_id_from_label(&get_label_from_name(&label));
 
     let renew_registrar_message: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: registrar_address.clone(),
@@ -465,51 +1621,451 @@ pub fn owner_renew(
     name: String,
     duration: u64,
 ) -> Result<Response, ContractError> {
-    only_owner(deps.as_ref(), &info)?;
-    let label = get_label_from_name(&name);
-    let token_id = get_token_id_from_label(&label);
-    let nodehash = get_nodehash(deps.as_ref(), label.clone())?;
-    let messages = _renew(deps.branch(), _env, info, token_id.clone(), duration)?;
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
+    let node_info = get_node_info_from_name(deps.as_ref(), &name)?;
+    let messages = _renew(deps.branch(), &name, duration)?;
     Ok(Response::new()
         .add_messages(messages)
         .add_attribute("method", "owner_renew")
         .add_attribute("name", name)
         .add_attribute("duration", duration.to_string())
-        .add_attribute("label", format!("{:?}", label))
-        .add_attribute("token_id", token_id)
-        .add_attribute("nodehash", format!("{:?}", nodehash)))
+        .add_attribute("label", format!("{:?}", node_info.label))
+        .add_attribute("token_id", node_info.token_id)
+        .add_attribute("nodehash", format!("{:?}", node_info.node)))
 }
 
 pub fn renew(
     mut deps: DepsMut,
     env: Env,
-    info: MessageInfo,
+    sender: Addr,
+    payment: Payment,
     name: String,
     duration: u64,
+    referrer: Option<String>,
 ) -> Result<Response, ContractError> {
-    validate_register_fund(
+    let (paid, refund_messages) = validate_register_fund(
         deps.as_ref(),
-        env.clone(),
-        info.clone(),
+        env,
+        &sender,
+        &payment,
         name.clone(),
         duration,
     )?;
-    let label = get_label_from_name(&name);
-    let token_id = get_token_id_from_label(&label);
-    let nodehash = get_nodehash(deps.as_ref(), label.clone())?;
-    let messages = _renew(deps.branch(), env, info, token_id.clone(), duration)?;
+    let referral = add_referral_reward(deps.branch(), &sender, referrer, &paid)?;
+    let node_info = get_node_info_from_name(deps.as_ref(), &name)?;
+    let mut messages = _renew(deps.branch(), &name, duration)?;
+    messages.extend(refund_messages);
     Ok(Response::new()
         .add_messages(messages)
         .add_attribute("method", "renew")
         .add_attribute("name", name)
         .add_attribute("duration", duration.to_string())
-        .add_attribute("label", format!("{:?}", label.clone()))
-        .add_attribute("token_id", token_id)
-        .add_attribute("nodehash", format!("{:?}", nodehash)))
+        .add_attribute("label", format!("{:?}", node_info.label))
+        .add_attribute("token_id", node_info.token_id)
+        .add_attribute("nodehash", format!("{:?}", node_info.node))
+        .add_attributes(referral_attributes(referral)))
+}
+
+pub fn batch_renew(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    names: Vec<(String, u64)>,
+) -> Result<Response, ContractError> {
+    if names.is_empty() {
+        return Err(ContractError::BadRequest {
+            msg: String::from("names must not be empty"),
+        });
+    }
+    let mut price = Uint128::zero();
+    for (name, duration) in names.iter() {
+        price += get_cost(deps.as_ref(), name.clone(), *duration)?
+            + get_premium(deps.as_ref(), &env, name)?;
+    }
+    let (_, refund_messages) = validate_fund(
+        deps.as_ref(),
+        &info.sender,
+        &Payment::Native(info.funds),
+        price,
+    )?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for (name, duration) in names.iter() {
+        messages.extend(_renew(deps.branch(), name, *duration)?);
+    }
+    messages.extend(refund_messages);
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "batch_renew")
+        .add_attribute("count", names.len().to_string())
+        .add_attribute("price", price.to_string()))
+}
+
+fn asset_amount(assets: &[Asset], info: &AssetInfo) -> Uint128 {
+    assets
+        .iter()
+        .find(|asset| &asset.info == info)
+        .map(|asset| asset.amount)
+        .unwrap_or_default()
+}
+
+fn add_asset(assets: &mut Vec<Asset>, asset: &Asset) {
+    match assets.iter_mut().find(|item| item.info == asset.info) {
+        Some(item) => item.amount += asset.amount,
+        None => assets.push(asset.clone()),
+    }
+}
+
+fn sub_asset(assets: &mut Vec<Asset>, asset: &Asset) {
+    for item in assets.iter_mut().filter(|item| item.info == asset.info) {
+        item.amount = item.amount.saturating_sub(asset.amount);
+    }
+    assets.retain(|item| !item.amount.is_zero());
+}
+
+// Earmarks the referral cut of the payment for `referrer`
+fn add_referral_reward(
+    deps: DepsMut,
+    sender: &Addr,
+    referrer: Option<String>,
+    paid: &Asset,
+) -> Result<Option<(Addr, Asset)>, ContractError> {
+    let referrer = match referrer {
+        Some(referrer) => deps.api.addr_validate(&referrer)?,
+        None => return Ok(None),
+    };
+    if &referrer == sender {
+        return Err(ContractError::BadRequest {
+            msg: String::from("referrer must not be the sender"),
+        });
+    }
+    let fee_bps = REFERRAL_FEE_BPS.may_load(deps.storage)?.unwrap_or_default();
+    let reward = Asset {
+        info: paid.info.clone(),
+        amount: paid.amount.multiply_ratio(fee_bps, 10_000u64),
+    };
+    let mut referral = REFERRALS
+        .may_load(deps.storage, referrer.to_string())?
+        .unwrap_or_default();
+    referral.referrals += 1;
+    if !reward.amount.is_zero() {
+        add_asset(&mut referral.pending_rewards, &reward);
+        add_asset(&mut referral.total_rewards, &reward);
+        let mut unclaimed_rewards = UNCLAIMED_REFERRAL_REWARDS
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        add_asset(&mut unclaimed_rewards, &reward);
+        UNCLAIMED_REFERRAL_REWARDS.save(deps.storage, &unclaimed_rewards)?;
+    }
+    REFERRALS.save(deps.storage, referrer.to_string(), &referral)?;
+    Ok(Some((referrer, reward)))
+}
+
+fn referral_attributes(referral: Option<(Addr, Asset)>) -> Vec<(String, String)> {
+    match referral {
+        Some((referrer, reward)) => vec![
+            (String::from("referrer"), referrer.to_string()),
+            (String::from("referral_reward"), reward.to_string()),
+        ],
+        None => vec![],
+    }
+}
+
+pub fn set_referral_fee(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    bps: u64,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::Treasury)?;
+    if bps > 10_000 {
+        return Err(ContractError::BadRequest {
+            msg: String::from("bps must not exceed 10000"),
+        });
+    }
+    REFERRAL_FEE_BPS.save(deps.storage, &bps)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_referral_fee")
+        .add_attribute("bps", bps.to_string()))
+}
+
+pub fn claim_referral_rewards(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut referral = REFERRALS
+        .may_load(deps.storage, info.sender.to_string())?
+        .unwrap_or_default();
+    if referral.pending_rewards.is_empty() {
+        return Err(ContractError::BadRequest {
+            msg: String::from("no referral rewards to claim"),
+        });
+    }
+    let mut unclaimed_rewards = UNCLAIMED_REFERRAL_REWARDS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for reward in referral.pending_rewards.iter() {
+        sub_asset(&mut unclaimed_rewards, reward);
+        messages.push(
+            reward
+                .clone()
+                .into_msg(&deps.querier, info.sender.clone())?,
+        );
+    }
+    let rewards = fees_to_string(&referral.pending_rewards);
+    referral.pending_rewards = vec![];
+    REFERRALS.save(deps.storage, info.sender.to_string(), &referral)?;
+    UNCLAIMED_REFERRAL_REWARDS.save(deps.storage, &unclaimed_rewards)?;
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "claim_referral_rewards")
+        .add_attribute("rewards", rewards))
+}
+
+fn renewal_credit_key(deps: Deps, name: &str) -> StdResult<String> {
+    Ok(hex::encode(get_node_info_from_name(deps, name)?.node))
+}
+
+pub fn set_renewal_window(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    window: u64,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
+    RENEWAL_WINDOW.save(deps.storage, &window)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_renewal_window")
+        .add_attribute("window", window.to_string()))
+}
+
+pub fn deposit_renewal_credit(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let amount = received_fee_denom(&info)?;
+    if amount.is_zero() {
+        return Err(ContractError::BadRequest {
+            msg: format!("renewal credit must be paid in {}", REGISTER_FEE_DENOM),
+        });
+    }
+    if get_expires(deps.as_ref(), &name)?.0 == 0 {
+        return Err(ContractError::BadRequest {
+            msg: format!("{} is not registered", name),
+        });
+    }
+    let key = renewal_credit_key(deps.as_ref(), &name)?;
+    let registration = REGISTRATIONS
+        .may_load(deps.storage, key.clone())?
+        .unwrap_or_default();
+    let depositor = deps.api.addr_canonicalize(info.sender.as_str())?.to_vec();
+    let mut credit = RENEWAL_CREDITS
+        .may_load(deps.storage, (key.clone(), depositor.clone()))?
+        .unwrap_or(RenewalCredit {
+            amount: Uint128::zero(),
+            registration,
+        });
+    if credit.registration != registration {
+        return Err(ContractError::BadRequest {
+            msg: format!(
+                "withdraw the renewal credit of the previous registration of {} first",
+                name
+            ),
+        });
+    }
+    credit.amount += amount;
+    RENEWAL_CREDITS.save(deps.storage, (key, depositor), &credit)?;
+    let total = TOTAL_RENEWAL_CREDITS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    TOTAL_RENEWAL_CREDITS.save(deps.storage, &(total + amount))?;
+    Ok(Response::new()
+        .add_attribute("method", "deposit_renewal_credit")
+        .add_attribute("name", name)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("credit", credit.amount.to_string()))
+}
+
+/// Refunds the whole renewal credit the sender deposited for the name
+pub fn withdraw_renewal_credit(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let key = renewal_credit_key(deps.as_ref(), &name)?;
+    let depositor = deps.api.addr_canonicalize(info.sender.as_str())?.to_vec();
+    let credit = RENEWAL_CREDITS
+        .may_load(deps.storage, (key.clone(), depositor.clone()))?
+        .ok_or_else(|| ContractError::BadRequest {
+            msg: format!("no renewal credit for {}", name),
+        })?;
+    RENEWAL_CREDITS.remove(deps.storage, (key, depositor));
+    let total = TOTAL_RENEWAL_CREDITS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    TOTAL_RENEWAL_CREDITS.save(deps.storage, &total.saturating_sub(credit.amount))?;
+    let refund = Asset {
+        info: AssetInfo::NativeToken {
+            denom: String::from(REGISTER_FEE_DENOM),
+        },
+        amount: credit.amount,
+    };
+    Ok(Response::new()
+        .add_message(refund.into_msg(&deps.querier, info.sender)?)
+        .add_attribute("method", "withdraw_renewal_credit")
+        .add_attribute("name", name)
+        .add_attribute("amount", credit.amount.to_string()))
+}
+
+/// Renews the names expiring within the renewal window for a year, or the minimum registration
+/// duration if longer, paid by the first renewal credit of their current registration covering
+/// it. Other names are skipped.
+pub fn process_renewals(
+    mut deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    names: Vec<String>,
+) -> Result<Response, ContractError> {
+    if names.is_empty() || names.len() > MAX_LIMIT as usize {
+        return Err(ContractError::BadRequest {
+            msg: format!("names must have between 1 and {} entries", MAX_LIMIT),
+        });
+    }
+    let window = RENEWAL_WINDOW
+        .may_load(deps.storage)?
+        .unwrap_or(DEFAULT_RENEWAL_WINDOW);
+    let now = env.block.time.seconds();
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut renewed: Vec<String> = vec![];
+    let mut spent = Uint128::zero();
+    for name in names.iter() {
+        let key = renewal_credit_key(deps.as_ref(), name)?;
+        let registration = REGISTRATIONS
+            .may_load(deps.storage, key.clone())?
+            .unwrap_or_default();
+        let credits = RENEWAL_CREDITS
+            .prefix(key.clone())
+            .range(deps.storage, None, None, Order::Ascending)
+            .filter(|item| {
+                item.as_ref()
+                    .map_or(true, |(_, credit)| credit.registration == registration)
+            })
+            .collect::<StdResult<Vec<(Vec<u8>, RenewalCredit)>>>()?;
+        if credits.is_empty() {
+            continue;
+        }
+        // Released names can not be renewed anymore
+        let (expires, grace_period) = get_expires(deps.as_ref(), name)?;
+        if expires > now + window || expires + grace_period < now {
+            continue;
+        }
+        let duration = resolve_tld(deps.as_ref(), name)?
+            .1
+            .min_registration_duration
+            .max(31_536_000);
+        let cost = get_cost(deps.as_ref(), name.clone(), duration)?;
+        let (depositor, mut credit) = match credits
+            .into_iter()
+            .find(|(_, credit)| credit.amount >= cost)
+        {
+            Some(credit) => credit,
+            None => continue,
+        };
+        credit.amount -= cost;
+        if credit.amount.is_zero() {
+            RENEWAL_CREDITS.remove(deps.storage, (key, depositor));
+        } else {
+            RENEWAL_CREDITS.save(deps.storage, (key, depositor), &credit)?;
+        }
+        spent += cost;
+        messages.extend(_renew(deps.branch(), name, duration)?);
+        renewed.push(name.clone());
+    }
+    let total = TOTAL_RENEWAL_CREDITS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    TOTAL_RENEWAL_CREDITS.save(deps.storage, &total.saturating_sub(spent))?;
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "process_renewals")
+        .add_attribute("renewed", renewed.join(","))
+        .add_attribute("spent", spent.to_string()))
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // The sender of the hook is the token contract
+    let token = info.sender;
+    if !CW20_TOKENS.has(deps.storage, token.to_string()) {
+        return Err(ContractError::UnexpectedFund {
+            denom: token.to_string(),
+        });
+    }
+    let sender = deps.api.addr_validate(&cw20_msg.sender)?;
+    let payment = Payment::Cw20 {
+        token,
+        amount: cw20_msg.amount,
+    };
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Register {
+            name,
+            owner,
+            duration,
+            secret,
+            resolver,
+            address,
+            referrer,
+        } => register(
+            deps, env, sender, payment, name, owner, duration, secret, resolver, address, referrer,
+        ),
+        Cw20HookMsg::Renew {
+            name,
+            duration,
+            referrer,
+        } => renew(deps, env, sender, payment, name, duration, referrer),
+    }
+}
+
+pub fn set_cw20_token(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    token: String,
+    price_multiplier: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    only_role(deps.as_ref(), &info, Role::PricingAdmin)?;
+    let token = deps.api.addr_validate(&token)?.to_string();
+    match price_multiplier {
+        Some(price_multiplier) => {
+            if price_multiplier.is_zero() {
+                return Err(ContractError::BadRequest {
+                    msg: String::from("price_multiplier must not be zero"),
+                });
+            }
+            CW20_TOKENS.save(deps.storage, token.clone(), &price_multiplier)?;
+        }
+        None => CW20_TOKENS.remove(deps.storage, token.clone()),
+    }
+    Ok(Response::new()
+        .add_attribute("method", "set_cw20_token")
+        .add_attribute("token", token)
+        .add_attribute(
+            "price_multiplier",
+            price_multiplier.map(|m| m.to_string()).unwrap_or_default(),
+        ))
 }
 
 pub fn get_commitment(
-    name: &String,
+    name: &str,
     owner: &String,
     secret: &String,
     resolver: &Option<String>,
@@ -520,8 +2076,8 @@ pub fn get_commitment(
     let arr = [
         &label[..],
         owner.as_bytes(),
-        resolver.as_deref().unwrap_or(&String::from("")).as_bytes(),
-        address.as_deref().unwrap_or(&String::from("")).as_bytes(),
+        resolver.as_deref().unwrap_or("").as_bytes(),
+        address.as_deref().unwrap_or("").as_bytes(),
         secret.as_bytes(),
     ]
     .concat();
@@ -532,12 +2088,12 @@ pub fn get_commitment(
     })
 }
 
-pub fn get_nodehash(deps: Deps, label: Vec<u8>) -> StdResult<Vec<u8>> {
-    let config = CONFIG.load(deps.storage)?;
-    let registrar_address = deps
-        .api
-        .addr_humanize(&config.registrar_address)?
-        .to_string();
+pub fn get_nodehash(
+    deps: Deps,
+    registrar_address: &CanonicalAddr,
+    label: Vec<u8>,
+) -> StdResult<Vec<u8>> {
+    let registrar_address = deps.api.addr_humanize(registrar_address)?.to_string();
 
     let get_base_node_response: GetBaseNodeResponse =
         deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
@@ -552,20 +2108,16 @@ pub fn get_nodehash(deps: Deps, label: Vec<u8>) -> StdResult<Vec<u8>> {
     Ok(nodehash)
 }
 
-pub fn is_available_name(deps: Deps, name: &String) -> StdResult<bool> {
-    let label = get_label_from_name(name);
-    let id = get_token_id_from_label(&label);
-    let config = CONFIG.load(deps.storage)?;
-    let registrar_address = deps
-        .api
-        .addr_humanize(&config.registrar_address)?
-        .to_string();
+pub fn is_available_name(deps: Deps, name: &str) -> StdResult<bool> {
+    let (label, tld) = resolve_tld(deps, name)?;
+    let id = get_token_id_from_label(&get_label_from_name(&label));
+    let registrar_address = deps.api.addr_humanize(&tld.registrar_address)?.to_string();
     let is_available_response: IsAvailableResponse =
         deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: registrar_address,
             msg: to_binary(&RegistrarQueryMsg::IsAvailable { id })?,
         }))?;
-    return Ok(is_available_response.available);
+    Ok(is_available_response.available)
 }
 
 pub fn get_max_commitment_age(deps: Deps) -> StdResult<MaxCommitmentAgeResponse> {
@@ -585,7 +2137,14 @@ pub fn get_min_commitment_age(deps: Deps) -> StdResult<MinCommitmentAgeResponse>
 pub fn get_owner(deps: Deps) -> StdResult<OwnerResponse> {
     let config = CONFIG.load(deps.storage)?;
     let owner = deps.api.addr_humanize(&config.owner)?;
-    Ok(OwnerResponse { owner })
+    Ok(OwnerResponse {
+        owner,
+        pending_owner: admin::pending_owner(deps.storage, deps.api)?,
+    })
+}
+
+pub fn get_roles(deps: Deps) -> StdResult<RolesResponse> {
+    admin::get_roles(deps.storage, deps.api, &Role::all())
 }
 
 pub fn get_registrar(deps: Deps) -> StdResult<RegistrarResponse> {
@@ -594,24 +2153,283 @@ pub fn get_registrar(deps: Deps) -> StdResult<RegistrarResponse> {
     Ok(RegistrarResponse { registrar_address })
 }
 
-pub fn get_rent_price(deps: Deps, name: String, duration: u64) -> StdResult<RentPriceResponse> {
+pub fn get_rent_price(
+    deps: Deps,
+    env: Env,
+    name: String,
+    duration: u64,
+) -> StdResult<RentPriceResponse> {
+    let premium = get_premium(deps, &env, &name)?;
     let cost = get_cost(deps, name, duration);
     if let Err(_err) = cost {
         return Err(StdError::generic_err("error"));
     }
     Ok(RentPriceResponse {
-        price: cost.unwrap(),
+        price: cost.unwrap() + premium,
+        premium,
+    })
+}
+
+pub fn get_batch_rent_price(
+    deps: Deps,
+    env: Env,
+    names: Vec<(String, u64)>,
+) -> StdResult<BatchRentPriceResponse> {
+    if names.is_empty() {
+        return Err(StdError::generic_err("names must not be empty"));
+    }
+    let prices = names
+        .into_iter()
+        .map(|(name, duration)| get_rent_price(deps, env.clone(), name, duration))
+        .collect::<StdResult<Vec<RentPriceResponse>>>()?;
+    let price = prices.iter().fold(Uint128::zero(), |total, rent_price| {
+        total + rent_price.price
+    });
+    Ok(BatchRentPriceResponse { price, prices })
+}
+
+pub fn get_rent_prices(
+    deps: Deps,
+    env: Env,
+    name: String,
+    duration: u64,
+) -> StdResult<RentPricesResponse> {
+    let price = get_rent_price(deps, env, name, duration)?.price;
+    let payment_config = load_payment_config(deps.storage)?;
+    let mut asset_infos = payment_config.accepted_assets.clone();
+    for token in CW20_TOKENS.keys(deps.storage, None, None, Order::Ascending) {
+        asset_infos.push(AssetInfo::Token {
+            contract_addr: String::from_utf8(token)?,
+        });
+    }
+    let prices = asset_infos
+        .iter()
+        .map(|asset_info| {
+            Ok(Asset {
+                info: asset_info.clone(),
+                amount: convert_price(deps, &payment_config, price, asset_info)?,
+            })
+        })
+        .collect::<StdResult<Vec<Asset>>>()?;
+    Ok(RentPricesResponse { prices })
+}
+
+pub fn get_payment_config(deps: Deps) -> StdResult<PaymentConfigResponse> {
+    let payment_config = load_payment_config(deps.storage)?;
+    let oracle = match payment_config.oracle {
+        Some(oracle) => Some(deps.api.addr_humanize(&oracle)?),
+        None => None,
+    };
+    Ok(PaymentConfigResponse {
+        oracle,
+        accepted_assets: payment_config.accepted_assets,
+        slippage_tolerance: payment_config.slippage_tolerance,
+    })
+}
+
+pub fn get_tlds(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListTldsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let tlds = TLDS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (tld, config) = item?;
+            Ok(TldResponse {
+                tld: String::from_utf8(tld)?,
+                registrar_address: deps.api.addr_humanize(&config.registrar_address)?,
+                price_tiers: config.price_tiers,
+                min_registration_duration: config.min_registration_duration,
+                enable_registration: config.enable_registration,
+            })
+        })
+        .collect::<StdResult<Vec<TldResponse>>>()?;
+    Ok(ListTldsResponse { tlds })
+}
+
+pub fn get_cw20_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Cw20TokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let tokens = CW20_TOKENS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (token, price_multiplier) = item?;
+            Ok(Cw20Token {
+                token: Addr::unchecked(String::from_utf8(token)?),
+                price_multiplier,
+            })
+        })
+        .collect::<StdResult<Vec<Cw20Token>>>()?;
+    Ok(Cw20TokensResponse { tokens })
+}
+
+pub fn get_renewal_credit(
+    deps: Deps,
+    name: String,
+    depositor: String,
+) -> StdResult<RenewalCreditResponse> {
+    let key = renewal_credit_key(deps, &name)?;
+    let registration = REGISTRATIONS
+        .may_load(deps.storage, key.clone())?
+        .unwrap_or_default();
+    let depositor = deps.api.addr_canonicalize(&depositor)?.to_vec();
+    let credit = RENEWAL_CREDITS.may_load(deps.storage, (key, depositor))?;
+    Ok(RenewalCreditResponse {
+        credit: credit
+            .as_ref()
+            .map(|credit| credit.amount)
+            .unwrap_or_default(),
+        stale: credit.map_or(false, |credit| credit.registration != registration),
+    })
+}
+
+pub fn get_renewal_window(deps: Deps) -> StdResult<RenewalWindowResponse> {
+    Ok(RenewalWindowResponse {
+        window: RENEWAL_WINDOW
+            .may_load(deps.storage)?
+            .unwrap_or(DEFAULT_RENEWAL_WINDOW),
+    })
+}
+
+pub fn get_referral_fee(deps: Deps) -> StdResult<ReferralFeeResponse> {
+    Ok(ReferralFeeResponse {
+        bps: REFERRAL_FEE_BPS.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+pub fn get_referral_stats(deps: Deps, referrer: String) -> StdResult<ReferralStatsResponse> {
+    let referrer = deps.api.addr_validate(&referrer)?;
+    let referral = REFERRALS
+        .may_load(deps.storage, referrer.to_string())?
+        .unwrap_or_default();
+    Ok(ReferralStatsResponse {
+        referrer,
+        referrals: referral.referrals,
+        pending_rewards: referral.pending_rewards,
+        total_rewards: referral.total_rewards,
+    })
+}
+
+pub fn get_fee_shares(deps: Deps) -> StdResult<FeeSharesResponse> {
+    let shares = FEE_SHARES
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|fee_recipient| {
+            Ok(FeeShare {
+                recipient: deps
+                    .api
+                    .addr_humanize(&fee_recipient.recipient)?
+                    .to_string(),
+                share: fee_recipient.share,
+            })
+        })
+        .collect::<StdResult<Vec<FeeShare>>>()?;
+    Ok(FeeSharesResponse { shares })
+}
+
+pub fn get_reserved_names(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ReservedNamesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let names = RESERVED_NAMES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (name, reservation) = item?;
+            let claimant = match reservation.claimant {
+                Some(claimant) => Some(deps.api.addr_humanize(&claimant)?),
+                None => None,
+            };
+            Ok(ReservedName {
+                name: String::from_utf8(name)?,
+                claimant,
+            })
+        })
+        .collect::<StdResult<Vec<ReservedName>>>()?;
+    Ok(ReservedNamesResponse { names })
+}
+
+pub fn get_blocked_names(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BlockedNamesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let names = BLOCKED_NAMES
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|name| String::from_utf8(name).map_err(StdError::invalid_utf8))
+        .collect::<StdResult<Vec<String>>>()?;
+    Ok(BlockedNamesResponse { names })
+}
+
+pub fn get_claims(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ClaimsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let claims = CLAIMS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (name, claim) = item?;
+            Ok(ClaimEntry {
+                name: String::from_utf8(name)?,
+                claimant: deps.api.addr_humanize(&claim.claimant)?.to_string(),
+                duration: claim.duration,
+            })
+        })
+        .collect::<StdResult<Vec<ClaimEntry>>>()?;
+    Ok(ClaimsResponse { claims })
+}
+
+pub fn get_claim_merkle_root(deps: Deps) -> StdResult<ClaimMerkleRootResponse> {
+    Ok(ClaimMerkleRootResponse {
+        merkle_root: CLAIM_MERKLE_ROOT.may_load(deps.storage)?.map(hex::encode),
     })
 }
 
 pub fn get_commitment_timestamp(
     deps: Deps,
+    committer: String,
     commitment: String,
 ) -> StdResult<CommitmentTimestampResponse> {
-    let timestamp = COMMITMENTS.load(deps.storage, commitment)?;
+    let committer = deps.api.addr_canonicalize(committer.as_str())?;
+    let timestamp = COMMITMENTS
+        .load(deps.storage, (committer.to_vec(), commitment))?
+        .timestamp;
     Ok(CommitmentTimestampResponse { timestamp })
 }
 
+pub fn get_commitment_deposit(deps: Deps) -> StdResult<CommitmentDepositResponse> {
+    Ok(CommitmentDepositResponse {
+        amount: COMMITMENT_DEPOSIT
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
+    })
+}
+
 pub fn get_min_registration_duration(deps: Deps) -> StdResult<MinRegistrationDurationResponse> {
     let config = CONFIG.load(deps.storage)?;
     Ok(MinRegistrationDurationResponse {
@@ -619,30 +2437,32 @@ pub fn get_min_registration_duration(deps: Deps) -> StdResult<MinRegistrationDur
     })
 }
 
-pub fn get_is_valid_name(name: &String) -> StdResult<IsValidNameResponse> {
-    let graphemes = name.graphemes(true).collect::<Vec<&str>>();
-    let name_length = graphemes.len();
-    if graphemes[0usize] == "-" {
-        return Ok(IsValidNameResponse {
-            is_valid_name: false,
-        });
-    }
-    let is_valid_name = name_length >= 3
-        && name.chars().all(|c| -> bool {
-            match c {
-                '0'..='9' => true,
-                'a'..='z' => true,
-                '-' => true,
-                _c => false,
-            }
-        });
+// Only canonical names are valid, NormalizeName returns the form to register
+pub fn get_is_valid_name(name: &str) -> StdResult<IsValidNameResponse> {
+    let (label, _) = split_name(name);
+    let is_valid_name =
+        normalize_name(label).ok().as_deref() == Some(label) && label.graphemes(true).count() >= 3;
     Ok(IsValidNameResponse { is_valid_name })
 }
 
-pub fn get_node_info_from_name(deps: Deps, name: &String) -> StdResult<NodeInfoResponse> {
-    let label: Vec<u8> = get_label_from_name(&name);
+pub fn get_normalized_name(name: &str) -> StdResult<NormalizeNameResponse> {
+    Ok(match normalize_name(name) {
+        Ok(normalized) => NormalizeNameResponse {
+            normalized: Some(normalized),
+            error: None,
+        },
+        Err(error) => NormalizeNameResponse {
+            normalized: None,
+            error: Some(error),
+        },
+    })
+}
+
+pub fn get_node_info_from_name(deps: Deps, name: &str) -> StdResult<NodeInfoResponse> {
+    let (label, tld) = resolve_tld(deps, name)?;
+    let label: Vec<u8> = get_label_from_name(&label);
     let token_id = get_token_id_from_label(&label);
-    let node = get_nodehash(deps, label.clone())?;
+    let node = get_nodehash(deps, &tld.registrar_address, label.clone())?;
     Ok(NodeInfoResponse {
         label,
         token_id,
@@ -650,14 +2470,13 @@ pub fn get_node_info_from_name(deps: Deps, name: &String) -> StdResult<NodeInfoR
     })
 }
 
-pub fn get_token_id_from_name(name: &String) -> StdResult<TokenIdResponse> {
-    let label: Vec<u8> = get_label_from_name(&name);
+pub fn get_token_id_from_name(name: &str) -> StdResult<TokenIdResponse> {
+    let label: Vec<u8> = get_label_from_name(split_name(name).0);
     let token_id = get_token_id_from_label(&label);
     Ok(TokenIdResponse { token_id })
 }
 
-pub fn get_nodehash_from_name(deps: Deps, name: &String) -> StdResult<NodehashResponse> {
-    let label: Vec<u8> = get_label_from_name(&name);
-    let node = get_nodehash(deps, label)?;
+pub fn get_nodehash_from_name(deps: Deps, name: &str) -> StdResult<NodehashResponse> {
+    let node = get_node_info_from_name(deps, name)?.node;
     Ok(NodehashResponse { node })
 }
@@ -3,11 +3,21 @@ use cosmwasm_std::{
     from_binary, from_slice, to_binary, Addr, Coin, ContractResult, Decimal, OwnedDeps, Querier,
     QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
+use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg};
 use std::collections::HashMap;
-use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
+use terra_cosmwasm::{
+    ExchangeRateItem, ExchangeRatesResponse, TaxCapResponse, TaxRateResponse, TerraQuery,
+    TerraQueryWrapper, TerraRoute,
+};
+use terraswap::asset::AssetInfo;
+use tns::oracle::{ExchangeRateResponse, QueryMsg as OracleQueryMsg};
 use tns::registrar::{
-    GetBaseNodeResponse, GetRegistryResponse, IsAvailableResponse, QueryMsg as RegistrarQueryMsg,
+    GetBaseNodeResponse, GetExpiresResponse, GetGracePeriodResponse, GetRegistryResponse,
+    IsAvailableResponse, QueryMsg as RegistrarQueryMsg,
 };
+use tns::utils::namehash;
+
+pub const GRACE_PERIOD: u64 = 7_776_000;
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
 /// this uses our CustomQuerier.
 pub fn mock_dependencies(
@@ -26,6 +36,12 @@ pub fn mock_dependencies(
 pub struct WasmMockQuerier {
     base: MockQuerier<TerraQueryWrapper>,
     tax_querier: TaxQuerier,
+    // Registrar expiry of token ids, 0 if never registered
+    expires: HashMap<String, u64>,
+    // Amount of a denom or token worth one uusd
+    exchange_rates: HashMap<String, Decimal>,
+    // CW20 balances of the contract by token address
+    token_balances: HashMap<String, Uint128>,
 }
 
 #[derive(Clone, Default)]
@@ -92,28 +108,93 @@ impl WasmMockQuerier {
                         }
                         _ => panic!("DO NOT ENTER HERE"),
                     }
+                } else if &TerraRoute::Oracle == route {
+                    match query_data {
+                        TerraQuery::ExchangeRates {
+                            base_denom,
+                            quote_denoms,
+                        } => {
+                            let res = ExchangeRatesResponse {
+                                base_denom: base_denom.to_string(),
+                                exchange_rates: quote_denoms
+                                    .iter()
+                                    .filter_map(|denom| {
+                                        self.exchange_rates.get(denom).map(|rate| {
+                                            ExchangeRateItem {
+                                                quote_denom: denom.to_string(),
+                                                exchange_rate: *rate,
+                                            }
+                                        })
+                                    })
+                                    .collect(),
+                            };
+                            SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+                        }
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
                 } else {
                     panic!("DO NOT ENTER HERE")
                 }
             }
-            QueryRequest::Wasm(WasmQuery::Smart {
-                contract_addr: _,
-                msg,
-            }) => match from_binary(msg) {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                if self.token_balances.contains_key(contract_addr) =>
+            {
+                match from_binary(msg).unwrap() {
+                    Cw20QueryMsg::Balance { .. } => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&Cw20BalanceResponse {
+                            balance: self.token_balances[contract_addr],
+                        })
+                        .unwrap(),
+                    )),
+                    _ => panic!("DO NOT ENTER HERE"),
+                }
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                if contract_addr == "oracle_address" =>
+            {
+                let OracleQueryMsg::ExchangeRate { asset } = from_binary(msg).unwrap();
+                let key = match asset {
+                    AssetInfo::NativeToken { denom } => denom,
+                    AssetInfo::Token { contract_addr } => contract_addr,
+                };
+                match self.exchange_rates.get(&key) {
+                    Some(rate) => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&ExchangeRateResponse { rate: *rate }).unwrap(),
+                    )),
+                    None => SystemResult::Ok(ContractResult::Err(String::from("No price"))),
+                }
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => match from_binary(msg) {
                 Ok(RegistrarQueryMsg::GetRegistry {}) => SystemResult::Ok(ContractResult::Ok(
                     to_binary(&GetRegistryResponse {
                         registry: Addr::unchecked("registry_address"),
                     })
                     .unwrap(),
                 )),
-                Ok(RegistrarQueryMsg::GetBaseNode {}) => SystemResult::Ok(ContractResult::Ok(
-                    to_binary(&GetBaseNodeResponse {
-                        base_node: String::from("749f2b479b45e5da8e4cbecd926ee9a6f78db5424fa6993b6ecababa5d736b12"),
+                Ok(RegistrarQueryMsg::GetBaseNode {}) => {
+                    let base_node = if contract_addr == "luna_registrar_address" {
+                        hex::encode(namehash("luna"))
+                    } else {
+                        String::from("749f2b479b45e5da8e4cbecd926ee9a6f78db5424fa6993b6ecababa5d736b12")
+                    };
+                    SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&GetBaseNodeResponse { base_node }).unwrap(),
+                    ))
+                }
+                Ok(RegistrarQueryMsg::IsAvailable { .. }) => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&IsAvailableResponse { available: true }).unwrap(),
+                )),
+                Ok(RegistrarQueryMsg::GetExpires { id }) => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&GetExpiresResponse {
+                        expires: self.expires.get(&id).copied().unwrap_or(0),
                     })
                     .unwrap(),
                 )),
-                Ok(RegistrarQueryMsg::IsAvailable { .. }) => SystemResult::Ok(ContractResult::Ok(
-                    to_binary(&IsAvailableResponse { available: true }).unwrap(),
+                Ok(RegistrarQueryMsg::GetGracePeriod {}) => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&GetGracePeriodResponse {
+                        grace_period: GRACE_PERIOD,
+                    })
+                    .unwrap(),
                 )),
                 _ => {
                     panic!("DO NOT ENTER HERE")
@@ -129,6 +210,27 @@ impl WasmMockQuerier {
         WasmMockQuerier {
             base,
             tax_querier: TaxQuerier::default(),
+            expires: HashMap::new(),
+            exchange_rates: HashMap::new(),
+            token_balances: HashMap::new(),
+        }
+    }
+
+    pub fn with_tax(&mut self, rate: Decimal, caps: &[(&String, &Uint128)]) {
+        self.tax_querier = TaxQuerier::new(rate, caps);
+    }
+
+    pub fn with_exchange_rates(&mut self, rates: &[(&str, Decimal)]) {
+        for (asset, rate) in rates.iter() {
+            self.exchange_rates.insert(asset.to_string(), *rate);
         }
     }
+
+    pub fn with_token_balance(&mut self, token: &str, balance: Uint128) {
+        self.token_balances.insert(token.to_string(), balance);
+    }
+
+    pub fn with_expires(&mut self, id: &str, expires: u64) {
+        self.expires.insert(id.to_string(), expires);
+    }
 }
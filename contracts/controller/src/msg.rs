@@ -1,6 +1,9 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use terraswap::asset::{Asset, AssetInfo};
+use tns::admin::Role;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -8,12 +11,38 @@ pub struct InstantiateMsg {
     pub max_commitment_age: u64,
     pub min_commitment_age: u64,
     pub min_registration_duration: u64,
-    pub tier1_price: u64,
-    pub tier2_price: u64,
-    pub tier3_price: u64,
+    pub price_tiers: Vec<PriceTier>,
     pub enable_registration: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceTier {
+    pub min_len: u32,
+    /// Unbounded if unset
+    pub max_len: Option<u32>,
+    /// Price of one year in uusd
+    pub price_per_year: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceOverride {
+    pub label: String,
+    pub price_per_year: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeShare {
+    pub recipient: String,
+    pub share: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimEntry {
+    pub name: String,
+    pub claimant: String,
+    pub duration: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
@@ -27,6 +56,8 @@ pub enum ExecuteMsg {
         secret: String,
         resolver: Option<String>,
         address: Option<String>,
+        /// Earns the referral cut of the fee
+        referrer: Option<String>,
     },
     OwnerRegister {
         name: String,
@@ -35,21 +66,88 @@ pub enum ExecuteMsg {
         resolver: Option<String>,
         address: Option<String>,
     },
+    /// `registrar_address`, `min_registration_duration` and the price table apply to names
+    /// without TLD, `enable_registration` opens or closes registration for every TLD.
     SetConfig {
         max_commitment_age: u64,
         min_commitment_age: u64,
         min_registration_duration: u64,
-        tier1_price: u64,
-        tier2_price: u64,
-        tier3_price: u64,
         registrar_address: String,
-        owner: String,
         enable_registration: bool,
     },
+    /// Proposes a new owner, who takes over with `AcceptOwnership`.
+    /// Proposing again replaces the pending owner.
+    ProposeOwner {
+        owner: String,
+    },
+    AcceptOwnership {},
+    /// Delegates `role` to `address`, or revokes it if unset
+    SetRole {
+        role: Role,
+        address: Option<String>,
+    },
     Withdraw {},
+    /// Shares of the collected fees sent to each recipient by `Withdraw` and `Distribute`,
+    /// they must add up to 1. An empty list sends everything to the owner again.
+    SetFeeShares {
+        shares: Vec<FeeShare>,
+    },
+    /// Splits the collected fees of every accepted asset by the fee shares
+    Distribute {},
+    /// Cut of registration and renewal fees earned by referrers, in basis points
+    SetReferralFee {
+        bps: u64,
+    },
+    /// Sends the pending referral rewards of the sender
+    ClaimReferralRewards {},
+    /// Prepays renewals of a registered name in uusd, anyone can fund it.
+    /// The credit only pays renewals of the current registration of the name.
+    DepositRenewalCredit {
+        name: String,
+    },
+    /// Refunds the unspent renewal credit the sender deposited for a name
+    WithdrawRenewalCredit {
+        name: String,
+    },
+    /// Renews the given names expiring within the renewal window from their credit
+    ProcessRenewals {
+        names: Vec<String>,
+    },
+    /// Seconds before expiry from which renewal credits apply
+    SetRenewalWindow {
+        window: u64,
+    },
+    /// Accepts `token` for payments priced at the uusd price times `price_multiplier`,
+    /// or stops accepting it if unset. Withdraw the token balance before removing it.
+    SetCw20Token {
+        token: String,
+        price_multiplier: Option<Decimal>,
+    },
+    /// Deposit in uusd required by `Commit`, refunded by `Register`.
+    SetCommitmentDeposit {
+        amount: Uint128,
+    },
+    /// Deletes up to `limit` expired commitments after `start_after`, forfeiting their deposits.
+    /// `start_after` is the `last` attribute of the previous sweep.
+    SweepCommitments {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Deletes up to `limit` commitments stored by older versions, which no committer can reveal
+    SweepLegacyCommitments {
+        limit: Option<u32>,
+    },
     Renew {
         name: String,
         duration: u64,
+        /// Earns the referral cut of the fee
+        referrer: Option<String>,
+    },
+    /// Pays for a `Cw20HookMsg` with a whitelisted CW20 token
+    Receive(Cw20ReceiveMsg),
+    /// Renews every `(name, duration)` pair paid with a single payment
+    BatchRenew {
+        names: Vec<(String, u64)>,
     },
     OwnerRenew {
         name: String,
@@ -58,6 +156,78 @@ pub enum ExecuteMsg {
     SetEnableRegistration {
         enable_registration: bool,
     },
+    /// Routes names ending in `.{tld}` to `registrar_address`, adding or replacing the TLD.
+    SetTld {
+        tld: String,
+        registrar_address: String,
+        price_tiers: Vec<PriceTier>,
+        min_registration_duration: u64,
+        enable_registration: bool,
+    },
+    RemoveTld {
+        tld: String,
+    },
+    /// Replaces the price table, tiers must not overlap.
+    SetPriceTiers {
+        tiers: Vec<PriceTier>,
+    },
+    /// Prices `label` regardless of its length, the override is removed if
+    /// `price_per_year` is unset.
+    SetPriceOverride {
+        label: String,
+        price_per_year: Option<u64>,
+    },
+    /// Reserves `names` for `OwnerRegister`, or for `claimant` to register them as usual.
+    AddReservedNames {
+        names: Vec<String>,
+        claimant: Option<String>,
+    },
+    RemoveReservedNames {
+        names: Vec<String>,
+    },
+    /// Blocked names can not be registered by anyone.
+    AddBlockedNames {
+        names: Vec<String>,
+    },
+    RemoveBlockedNames {
+        names: Vec<String>,
+    },
+    /// Pre-allocates names, each claimant registers its name for free with `Claim`.
+    AddClaims {
+        claims: Vec<ClaimEntry>,
+    },
+    RemoveClaims {
+        names: Vec<String>,
+    },
+    /// Hex encoded root of a keccak256 Merkle tree over "name:claimant:duration" leaves,
+    /// where each pair of nodes is hashed in ascending order.
+    SetClaimMerkleRoot {
+        merkle_root: Option<String>,
+    },
+    /// Registers a pre-allocated name to the sender without commitment or payment.
+    /// `duration` and `proof` are only needed for names allocated through the Merkle root.
+    Claim {
+        name: String,
+        duration: Option<u64>,
+        proof: Option<Vec<String>>,
+        resolver: Option<String>,
+        address: Option<String>,
+    },
+    /// The premium starts at `start_price` when the grace period ends, halves every
+    /// `half_life` seconds and drops to zero after `duration` seconds.
+    SetPremium {
+        start_price: u64,
+        duration: u64,
+        half_life: u64,
+    },
+    /// Prices stay defined in uusd, payments in other accepted assets are converted
+    /// through `oracle`, or the Terra oracle if unset. Only native denoms are taken,
+    /// CW20 tokens are accepted with `SetCw20Token`.
+    SetPaymentConfig {
+        oracle: Option<String>,
+        accepted_assets: Vec<AssetInfo>,
+        slippage_tolerance: Decimal,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -66,6 +236,7 @@ pub enum QueryMsg {
     Owner {},
     Registrar {},
     CommitmentTimestamp {
+        committer: String,
         commitment: String,
     },
     GetCommitment {
@@ -79,12 +250,24 @@ pub enum QueryMsg {
         name: String,
         duration: u64,
     },
+    /// Total rent price of a `BatchRenew`
+    /// Return type: BatchRentPriceResponse
+    BatchRentPrice {
+        names: Vec<(String, u64)>,
+    },
     MaxCommitmentAge {},
+    /// Return type: CommitmentDepositResponse
+    CommitmentDeposit {},
     MinCommitmentAge {},
     MinRegistrationDuration {},
     IsValidName {
         name: String,
     },
+    /// Canonical form of `name` under UTS-46 normalization, or the reason it is rejected.
+    /// Return type: NormalizeNameResponse
+    NormalizeName {
+        name: String,
+    },
     GetTokenId {
         name: String,
     },
@@ -95,6 +278,77 @@ pub enum QueryMsg {
         name: String,
     },
     GetPrice {},
+    GetPremium {},
+    GetPaymentConfig {},
+    /// Rent price converted to every accepted asset
+    RentPrices {
+        name: String,
+        duration: u64,
+    },
+    ReservedNames {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    BlockedNames {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// TLDs served besides the default one of `Registrar {}`.
+    /// Return type: ListTldsResponse
+    ListTlds {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Return type: ClaimsResponse
+    Claims {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Return type: ClaimMerkleRootResponse
+    ClaimMerkleRoot {},
+    /// Return type: RolesResponse
+    Roles {},
+    /// Return type: RenewalCreditResponse
+    RenewalCredit {
+        name: String,
+        depositor: String,
+    },
+    /// Return type: RenewalWindowResponse
+    RenewalWindow {},
+    /// Return type: ReferralFeeResponse
+    ReferralFee {},
+    /// Return type: ReferralStatsResponse
+    ReferralStats {
+        referrer: String,
+    },
+    /// Return type: FeeSharesResponse
+    FeeShares {},
+    /// Return type: Cw20TokensResponse
+    Cw20Tokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    Register {
+        name: String,
+        owner: String,
+        duration: u64,
+        secret: String,
+        resolver: Option<String>,
+        address: Option<String>,
+        /// Earns the referral cut of the fee
+        referrer: Option<String>,
+    },
+    Renew {
+        name: String,
+        duration: u64,
+        /// Earns the referral cut of the fee
+        referrer: Option<String>,
+    },
 }
 
 // We define a custom struct for each query response
@@ -108,9 +362,65 @@ pub struct CommitmentTimestampResponse {
     pub timestamp: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BatchRentPriceResponse {
+    /// Total price including the premiums
+    pub price: Uint128,
+    /// Price of each name in the order of the query
+    pub prices: Vec<RentPriceResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RenewalCreditResponse {
+    pub credit: Uint128,
+    /// Deposited for an earlier registration of the name, it can only be withdrawn
+    pub stale: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RenewalWindowResponse {
+    pub window: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReferralFeeResponse {
+    pub bps: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReferralStatsResponse {
+    pub referrer: Addr,
+    pub referrals: u64,
+    pub pending_rewards: Vec<Asset>,
+    pub total_rewards: Vec<Asset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeSharesResponse {
+    pub shares: Vec<FeeShare>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20Token {
+    pub token: Addr,
+    pub price_multiplier: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20TokensResponse {
+    pub tokens: Vec<Cw20Token>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CommitmentDepositResponse {
+    pub amount: Uint128,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RentPriceResponse {
+    /// Total price including the premium
     pub price: Uint128,
+    pub premium: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -133,6 +443,12 @@ pub struct IsValidNameResponse {
     pub is_valid_name: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NormalizeNameResponse {
+    pub normalized: Option<String>,
+    pub error: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TokenIdResponse {
     pub token_id: String,
@@ -153,6 +469,7 @@ pub struct NodeInfoResponse {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct OwnerResponse {
     pub owner: Addr,
+    pub pending_owner: Option<Addr>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -162,9 +479,67 @@ pub struct RegistrarResponse {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PriceResponse {
-    pub tier1_price: u64,
-    pub tier2_price: u64,
-    pub tier3_price: u64,
+    pub tiers: Vec<PriceTier>,
+    pub overrides: Vec<PriceOverride>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PremiumResponse {
+    pub start_price: u64,
+    pub duration: u64,
+    pub half_life: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PaymentConfigResponse {
+    pub oracle: Option<Addr>,
+    pub accepted_assets: Vec<AssetInfo>,
+    pub slippage_tolerance: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RentPricesResponse {
+    pub prices: Vec<Asset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReservedName {
+    pub name: String,
+    pub claimant: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReservedNamesResponse {
+    pub names: Vec<ReservedName>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlockedNamesResponse {
+    pub names: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TldResponse {
+    pub tld: String,
+    pub registrar_address: Addr,
+    pub price_tiers: Vec<PriceTier>,
+    pub min_registration_duration: u64,
+    pub enable_registration: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListTldsResponse {
+    pub tlds: Vec<TldResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimsResponse {
+    pub claims: Vec<ClaimEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimMerkleRootResponse {
+    pub merkle_root: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
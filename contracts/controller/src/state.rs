@@ -1,21 +1,142 @@
-use cosmwasm_std::CanonicalAddr;
+use crate::msg::PriceTier;
+use cosmwasm_std::{CanonicalAddr, Decimal, Uint128};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use terraswap::asset::{Asset, AssetInfo};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub max_commitment_age: u64,
     pub min_commitment_age: u64,
     pub min_registration_duration: u64,
+    pub enable_registration: bool,
+    pub registrar_address: CanonicalAddr,
+    pub owner: CanonicalAddr,
+}
+
+/// Prices of the config before PRICE_TIERS, by names of 3, 4 and 5+ characters
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyPriceConfig {
     pub tier1_price: u64,
     pub tier2_price: u64,
     pub tier3_price: u64,
-    pub enable_registration: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeRecipient {
+    pub recipient: CanonicalAddr,
+    pub share: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Referral {
+    pub referrals: u64,
+    /// Rewards not claimed yet
+    pub pending_rewards: Vec<Asset>,
+    pub total_rewards: Vec<Asset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Commitment {
+    /// Only the committer can reveal the commitment
+    pub committer: CanonicalAddr,
+    pub timestamp: u64,
+    /// Paid in REGISTER_FEE_DENOM, refunded on registration and forfeited on expiry
+    pub deposit: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RenewalCredit {
+    pub amount: Uint128,
+    /// Registration of the name the credit was deposited for, see REGISTRATIONS
+    pub registration: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Tld {
     pub registrar_address: CanonicalAddr,
-    pub owner: CanonicalAddr,
+    pub price_tiers: Vec<PriceTier>,
+    pub min_registration_duration: u64,
+    pub enable_registration: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Premium {
+    pub start_price: u64,
+    /// Seconds after the grace period until the premium reaches zero
+    pub duration: u64,
+    pub half_life: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PaymentConfig {
+    /// Price feed contract, the Terra oracle is used if unset
+    pub oracle: Option<CanonicalAddr>,
+    pub accepted_assets: Vec<AssetInfo>,
+    /// Fraction of the converted price a payment may fall short by
+    pub slippage_tolerance: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Reservation {
+    /// Address allowed to register the name, only the owner can if unset
+    pub claimant: Option<CanonicalAddr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Claim {
+    pub claimant: CanonicalAddr,
+    pub duration: u64,
 }
 
+// Prices are defined in REGISTER_FEE_DENOM and converted to the paid asset
 pub const REGISTER_FEE_DENOM: &str = "uusd";
 pub const CONFIG: Item<Config> = Item::new("CONFIG");
-pub const COMMITMENTS: Map<String, u64> = Map::new("COMMITMENTS");
+// Price fields left in CONFIG by older versions, moved to PRICE_TIERS on migration
+pub const LEGACY_PRICE_CONFIG: Item<LegacyPriceConfig> = Item::new("CONFIG");
+// TLDs served next to the default one, whose settings live in CONFIG and PRICE_TIERS
+pub const TLDS: Map<String, Tld> = Map::new("TLDS");
+// Yearly price by name length, sorted by min_len and non-overlapping
+pub const PRICE_TIERS: Item<Vec<PriceTier>> = Item::new("PRICE_TIERS");
+// Yearly price of specific labels, takes precedence over PRICE_TIERS
+pub const PRICE_OVERRIDES: Map<String, u64> = Map::new("PRICE_OVERRIDES");
+// Premium on names released by the registrar, no premium is charged if unset
+pub const PREMIUM: Item<Premium> = Item::new("PREMIUM");
+// Only REGISTER_FEE_DENOM is accepted if unset
+pub const PAYMENT_CONFIG: Item<PaymentConfig> = Item::new("PAYMENT_CONFIG");
+// Split of the collected fees, the owner withdraws everything if unset
+pub const FEE_SHARES: Item<Vec<FeeRecipient>> = Item::new("FEE_SHARES");
+// Prepaid renewal credit in REGISTER_FEE_DENOM, keyed by the hex encoded nodehash and the
+// canonical depositor. Credits of an earlier registration of the name only get refunded
+pub const RENEWAL_CREDITS: Map<(String, Vec<u8>), RenewalCredit> =
+    Map::new("DEPOSITOR_RENEWAL_CREDITS");
+// Registrations of a name through this controller, keyed by the hex encoded nodehash
+pub const REGISTRATIONS: Map<String, u64> = Map::new("REGISTRATIONS");
+// Sum of RENEWAL_CREDITS, kept out of the collected fees
+pub const TOTAL_RENEWAL_CREDITS: Item<Uint128> = Item::new("TOTAL_RENEWAL_CREDITS");
+// Renewal credits apply to names expiring within this many seconds
+pub const RENEWAL_WINDOW: Item<u64> = Item::new("RENEWAL_WINDOW");
+// Cut of registration and renewal fees earmarked for the referrer, in basis points
+pub const REFERRAL_FEE_BPS: Item<u64> = Item::new("REFERRAL_FEE_BPS");
+pub const REFERRALS: Map<String, Referral> = Map::new("REFERRALS");
+// Referral rewards of every referrer not claimed yet, kept out of the collected fees
+pub const UNCLAIMED_REFERRAL_REWARDS: Item<Vec<Asset>> = Item::new("UNCLAIMED_REFERRAL_REWARDS");
+// Price multiplier of each accepted CW20 token, keyed by the token address
+pub const CW20_TOKENS: Map<String, Decimal> = Map::new("CW20_TOKENS");
+pub const RESERVED_NAMES: Map<String, Reservation> = Map::new("RESERVED_NAMES");
+pub const BLOCKED_NAMES: Map<String, bool> = Map::new("BLOCKED_NAMES");
+// Names pre-allocated by the owner, removed once claimed
+pub const CLAIMS: Map<String, Claim> = Map::new("CLAIMS");
+// Root of a keccak256 Merkle tree over "name:claimant:duration" leaves
+pub const CLAIM_MERKLE_ROOT: Item<Vec<u8>> = Item::new("CLAIM_MERKLE_ROOT");
+pub const MERKLE_CLAIMED: Map<String, bool> = Map::new("MERKLE_CLAIMED");
+// Keyed by (committer, commitment), a copied commitment does not block its committer
+pub const COMMITMENTS: Map<(Vec<u8>, String), Commitment> = Map::new("COMMITTER_COMMITMENTS");
+// Commitment timestamps of older versions, keyed by the commitment alone. They have no
+// committer to bind them to and can only be swept
+pub const LEGACY_COMMITMENTS: Map<String, u64> = Map::new("COMMITMENTS");
+// Deposit required by Commit in REGISTER_FEE_DENOM, no deposit is required if unset
+pub const COMMITMENT_DEPOSIT: Item<Uint128> = Item::new("COMMITMENT_DEPOSIT");
+// Deposits of commitments not consumed or swept yet, kept out of withdrawals
+pub const PENDING_DEPOSITS: Item<Uint128> = Item::new("PENDING_DEPOSITS");
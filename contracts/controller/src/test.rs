@@ -1,21 +1,55 @@
 mod tests {
-    use crate::contract::{execute, instantiate, query};
+    use crate::contract::{execute, instantiate, migrate, query};
     use crate::error::ContractError;
     use crate::handler::consume_commitment;
-    use crate::mock_querier::mock_dependencies;
+    use crate::mock_querier::{mock_dependencies, GRACE_PERIOD};
     use crate::msg::{
-        ExecuteMsg, GetCommitmentResponse, InstantiateMsg, MaxCommitmentAgeResponse,
-        MinCommitmentAgeResponse, MinRegistrationDurationResponse, NodehashResponse, OwnerResponse,
-        PriceResponse, QueryMsg, RegistrarResponse, RentPriceResponse, TokenIdResponse,
+        BatchRentPriceResponse, BlockedNamesResponse, ClaimEntry, ClaimMerkleRootResponse,
+        ClaimsResponse, CommitmentDepositResponse, CommitmentTimestampResponse, Cw20HookMsg,
+        Cw20Token, Cw20TokensResponse, ExecuteMsg, FeeShare, FeeSharesResponse,
+        GetCommitmentResponse, InstantiateMsg, IsValidNameResponse, ListTldsResponse,
+        MaxCommitmentAgeResponse, MigrateMsg, MinCommitmentAgeResponse,
+        MinRegistrationDurationResponse, NodehashResponse, NormalizeNameResponse, OwnerResponse,
+        PaymentConfigResponse, PremiumResponse, PriceOverride, PriceResponse, PriceTier, QueryMsg,
+        ReferralFeeResponse, ReferralStatsResponse, RegistrarResponse, RenewalCreditResponse,
+        RenewalWindowResponse, RentPriceResponse, RentPricesResponse, ReservedName,
+        ReservedNamesResponse, TldResponse, TokenIdResponse,
     };
-    use crate::state::COMMITMENTS;
+    use crate::state::{COMMITMENTS, CONFIG, LEGACY_COMMITMENTS, LEGACY_PRICE_CONFIG, PRICE_TIERS};
     use cosmwasm_std::testing::{mock_env, mock_info};
     use cosmwasm_std::{
-        coins, from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, Timestamp, Uint128, WasmMsg,
+        coins, from_binary, to_binary, Addr, Api, BankMsg, CanonicalAddr, Coin, CosmosMsg, Decimal,
+        OwnedDeps, SubMsg, Timestamp, Uint128, WasmMsg,
     };
+    use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use cw_storage_plus::Item;
+    use serde::{Deserialize, Serialize};
+    use terraswap::asset::{Asset, AssetInfo};
+    use tns::admin::{AdminError, Role, RoleAddress, RolesResponse};
     use tns::registrar::{ExecuteMsg as RegistrarExecuteMsg, Extension};
     use tns::registry::ExecuteMsg as RegistryExecuteMsg;
     use tns::resolver::ExecuteMsg as ResolverExecuteMsg;
+    use tns::utils::{keccak256, namehash};
+
+    fn default_price_tiers() -> Vec<PriceTier> {
+        vec![
+            PriceTier {
+                min_len: 3,
+                max_len: Some(3),
+                price_per_year: 640_000_000u64,
+            },
+            PriceTier {
+                min_len: 4,
+                max_len: Some(4),
+                price_per_year: 160_000_000u64,
+            },
+            PriceTier {
+                min_len: 5,
+                max_len: None,
+                price_per_year: 5_000_000u64,
+            },
+        ]
+    }
 
     #[test]
     fn proper_initialization() {
@@ -25,9 +59,7 @@ mod tests {
             min_commitment_age: 0,
             min_registration_duration: 0,
             max_commitment_age: 0,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -43,9 +75,7 @@ mod tests {
             min_commitment_age: 0,
             min_registration_duration: 0,
             max_commitment_age: 0,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -59,6 +89,25 @@ mod tests {
             token_id_response.token_id,
             "9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501"
         );
+
+        // Normalized like the labels of the nodehash
+        let name = String::from("Alice");
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetTokenId { name }).unwrap();
+        let token_id_response: TokenIdResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            token_id_response.token_id,
+            "9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501"
+        );
+        assert_eq!(
+            namehash("Alice.ust"),
+            keccak256(
+                &[
+                    namehash("ust"),
+                    hex::decode(token_id_response.token_id).unwrap()
+                ]
+                .concat()
+            )
+        );
     }
 
     #[test]
@@ -69,9 +118,7 @@ mod tests {
             min_commitment_age: 0,
             min_registration_duration: 0,
             max_commitment_age: 0,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -98,9 +145,7 @@ mod tests {
             min_commitment_age: 10,
             min_registration_duration: 10,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -126,9 +171,7 @@ mod tests {
             min_commitment_age: 10,
             min_registration_duration: 10,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -170,9 +213,7 @@ mod tests {
             min_commitment_age: 10,
             min_registration_duration: 10,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -208,9 +249,7 @@ mod tests {
             min_commitment_age: 10,
             min_registration_duration: 10,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -225,19 +264,24 @@ mod tests {
         execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         // Found the commitment
+        let committer = deps.api.addr_canonicalize("creator").unwrap().to_vec();
         let commit_time = COMMITMENTS
-            .load(deps.as_mut().storage, commitment.clone())
-            .unwrap();
+            .load(
+                deps.as_mut().storage,
+                (committer.clone(), commitment.clone()),
+            )
+            .unwrap()
+            .timestamp;
         assert_eq!(commit_time, 1571797419);
 
         //fast forward 50 seconds
         let mut env = mock_env();
         env.block.time = Timestamp::from_nanos(1_571_797_469_879_305_533);
-        consume_commitment(deps.as_mut(), env, commitment.clone()).unwrap();
+        consume_commitment(deps.as_mut(), env, "creator", commitment.clone()).unwrap();
 
         // Should not found the commitment
-        let res = COMMITMENTS.load(deps.as_mut().storage, commitment.clone());
-        assert_eq!(res.is_err(), true);
+        let res = COMMITMENTS.load(deps.as_mut().storage, (committer, commitment.clone()));
+        assert!(res.is_err());
     }
 
     #[test] //Should return error commitment age is out of range
@@ -248,9 +292,7 @@ mod tests {
             min_commitment_age: 10,
             min_registration_duration: 10,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -265,7 +307,8 @@ mod tests {
         execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         // Too early
-        let err = consume_commitment(deps.as_mut(), mock_env(), commitment.clone()).unwrap_err();
+        let err = consume_commitment(deps.as_mut(), mock_env(), "creator", commitment.clone())
+            .unwrap_err();
         assert_eq!(
             err,
             ContractError::CommitmentIsTooEarlyOrExpired {
@@ -280,7 +323,8 @@ mod tests {
         let mut env = mock_env();
         env.block.time = Timestamp::from_nanos(1_571_797_569_879_305_533);
 
-        let err = consume_commitment(deps.as_mut(), env, commitment.clone()).unwrap_err();
+        let err =
+            consume_commitment(deps.as_mut(), env, "creator", commitment.clone()).unwrap_err();
         assert_eq!(
             err,
             ContractError::CommitmentIsTooEarlyOrExpired {
@@ -299,16 +343,19 @@ mod tests {
             min_commitment_age: 10,
             min_registration_duration: 10,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
         instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let err =
-            consume_commitment(deps.as_mut(), mock_env(), String::from("nonexist")).unwrap_err();
+        let err = consume_commitment(
+            deps.as_mut(),
+            mock_env(),
+            "creator",
+            String::from("nonexist"),
+        )
+        .unwrap_err();
         assert_eq!(
             err,
             ContractError::ConsumeNonexistCommitment {
@@ -326,9 +373,7 @@ mod tests {
             min_commitment_age: 0, // For by-pass commitment guard
             min_registration_duration: 24 * 3600 * 365,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -365,7 +410,7 @@ mod tests {
             mock_env(),
             QueryMsg::RentPrice {
                 name: name.clone(),
-                duration: duration.clone(),
+                duration,
             },
         )
         .unwrap();
@@ -374,10 +419,11 @@ mod tests {
         let msg = ExecuteMsg::Register {
             name: name.clone(),
             owner: owner.clone(),
-            duration: duration.clone(),
+            duration,
             secret: secret.clone(),
             resolver: Some(resolver.clone()),
             address: Some(address.clone()),
+            referrer: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -388,7 +434,7 @@ mod tests {
                     "9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501",
                 ), // token_id of alice
                 owner: mock_env().contract.address.to_string(),
-                duration: duration.clone(),
+                duration,
                 name: name.clone(),
             })
             .unwrap(),
@@ -483,7 +529,7 @@ mod tests {
             mock_env(),
             QueryMsg::RentPrice {
                 name: name.clone(),
-                duration: duration.clone(),
+                duration,
             },
         )
         .unwrap();
@@ -492,12 +538,13 @@ mod tests {
         let msg = ExecuteMsg::Register {
             name: name.clone(),
             owner: owner.clone(),
-            duration: duration.clone(),
+            duration,
             secret: secret.clone(),
             resolver: Some(resolver.clone()),
             address: Some(address.clone()),
+            referrer: None,
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_err(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
     }
 
     #[test] // Should return correct messages
@@ -509,9 +556,7 @@ mod tests {
             min_commitment_age: 0, // For by-pass commitment guard
             min_registration_duration: 24 * 3600 * 365,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -525,7 +570,7 @@ mod tests {
         let msg = ExecuteMsg::OwnerRegister {
             name: name.clone(),
             owner: owner.clone(),
-            duration: duration.clone(),
+            duration,
             resolver: Some(resolver.clone()),
             address: Some(address.clone()),
         };
@@ -537,7 +582,7 @@ mod tests {
                     "9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501",
                 ), // token_id of alice
                 owner: mock_env().contract.address.to_string(),
-                duration: duration.clone(),
+                duration,
                 name: name.clone(),
             })
             .unwrap(),
@@ -609,11 +654,11 @@ mod tests {
         let msg = ExecuteMsg::OwnerRegister {
             name: name.clone(),
             owner: owner.clone(),
-            duration: duration.clone(),
+            duration,
             resolver: Some(resolver.clone()),
             address: Some(address.clone()),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
     }
 
     #[test]
@@ -625,9 +670,7 @@ mod tests {
             min_commitment_age: 0, // For by-pass commitment guard
             min_registration_duration: 24 * 3600 * 365,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: false,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -656,7 +699,7 @@ mod tests {
         let msg = ExecuteMsg::Commit {
             commitment: get_commitment_response.commitment,
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_err(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
 
         let duration: u64 = 24 * 3600 * 365;
         let res = query(
@@ -664,7 +707,7 @@ mod tests {
             mock_env(),
             QueryMsg::RentPrice {
                 name: name.clone(),
-                duration: duration.clone(),
+                duration,
             },
         )
         .unwrap();
@@ -673,12 +716,13 @@ mod tests {
         let msg = ExecuteMsg::Register {
             name: name.clone(),
             owner: owner.clone(),
-            duration: duration.clone(),
+            duration,
             secret: secret.clone(),
             resolver: Some(resolver.clone()),
             address: Some(address.clone()),
+            referrer: None,
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_err(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
 
         let msg = ExecuteMsg::SetEnableRegistration {
             enable_registration: true,
@@ -708,18 +752,19 @@ mod tests {
             commitment: get_commitment_response.commitment,
         };
         let info = mock_info("alice", &coins(rent_price_response.price.u128(), "uusd"));
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         let info = mock_info("alice", &coins(rent_price_response.price.u128(), "uusd"));
         let msg = ExecuteMsg::Register {
             name: name.clone(),
             owner: owner.clone(),
-            duration: duration.clone(),
+            duration,
             secret: secret.clone(),
             resolver: Some(resolver.clone()),
             address: Some(address.clone()),
+            referrer: None,
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
     }
 
     #[test] // Should not be able to register with insufficient fund
@@ -731,9 +776,7 @@ mod tests {
             min_commitment_age: 0, // For by-pass commitment guard
             min_registration_duration: 24 * 3600 * 365,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -770,7 +813,7 @@ mod tests {
             mock_env(),
             QueryMsg::RentPrice {
                 name: name.clone(),
-                duration: duration.clone(),
+                duration,
             },
         )
         .unwrap();
@@ -784,10 +827,11 @@ mod tests {
         let msg = ExecuteMsg::Register {
             name: name.clone(),
             owner: owner.clone(),
-            duration: duration.clone(),
+            duration,
             secret: secret.clone(),
             resolver: Some(resolver.clone()),
             address: Some(address.clone()),
+            referrer: None,
         };
 
         let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
@@ -809,9 +853,7 @@ mod tests {
             min_commitment_age: 0, // For by-pass commitment guard
             min_registration_duration: 24 * 3600 * 365,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -829,7 +871,7 @@ mod tests {
             mock_env(),
             QueryMsg::RentPrice {
                 name: name.clone(),
-                duration: duration.clone(),
+                duration,
             },
         )
         .unwrap();
@@ -839,10 +881,11 @@ mod tests {
         let msg = ExecuteMsg::Register {
             name: name.clone(),
             owner: owner.clone(),
-            duration: duration.clone(),
+            duration,
             secret: secret.clone(),
             resolver: Some(resolver.clone()),
             address: Some(address.clone()),
+            referrer: None,
         };
 
         let res = query(
@@ -878,9 +921,7 @@ mod tests {
             min_commitment_age: 0, // For by-pass commitment guard
             min_registration_duration: 24 * 3600 * 365,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -917,7 +958,7 @@ mod tests {
             mock_env(),
             QueryMsg::RentPrice {
                 name: name.clone(),
-                duration: duration.clone(),
+                duration,
             },
         )
         .unwrap();
@@ -927,10 +968,11 @@ mod tests {
         let msg = ExecuteMsg::Register {
             name: name.clone(),
             owner: owner.clone(),
-            duration: duration.clone(),
+            duration,
             secret: secret.clone(),
             resolver: Some(resolver.clone()),
             address: Some(address.clone()),
+            referrer: None,
         };
 
         execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -938,7 +980,8 @@ mod tests {
         let info = mock_info("alice", &coins(rent_price_response.price.u128(), "uusd"));
         let msg = ExecuteMsg::Renew {
             name: name.clone(),
-            duration: duration.clone(),
+            duration,
+            referrer: None,
         };
 
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -949,7 +992,7 @@ mod tests {
                 id: String::from(
                     "9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501",
                 ), // token_id of alice,,
-                duration: duration.clone(),
+                duration,
             })
             .unwrap(),
             funds: vec![],
@@ -968,9 +1011,7 @@ mod tests {
             min_commitment_age: 0, // For by-pass commitment guard
             min_registration_duration: 24 * 3600 * 365,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -983,7 +1024,7 @@ mod tests {
             mock_env(),
             QueryMsg::RentPrice {
                 name: name.clone(),
-                duration: duration.clone(),
+                duration,
             },
         )
         .unwrap();
@@ -994,7 +1035,8 @@ mod tests {
         let info = mock_info("alice", &coins(half, "uusd"));
         let msg = ExecuteMsg::Renew {
             name: name.clone(),
-            duration: duration.clone(),
+            duration,
+            referrer: None,
         };
 
         let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
@@ -1016,9 +1058,7 @@ mod tests {
             min_commitment_age: 0, // For by-pass commitment guard
             min_registration_duration: 24 * 3600 * 365,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -1027,19 +1067,49 @@ mod tests {
         let info = mock_info("creator", &coins(0, "uusd"));
         let msg = ExecuteMsg::Withdraw {};
 
-        // Zero balance
-        let bank_send_message: CosmosMsg = CosmosMsg::Bank(BankMsg::Send {
-            to_address: "creator".to_string(),
-            amount: vec![Coin {
-                denom: "uusd".to_string(),
-                amount: Uint128::from(0 as u32),
-            }],
-        });
-
+        // Zero balance, nothing to send
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(res.messages.is_empty());
 
-        assert_eq!(res.messages.len(), 1); // Renew
-        assert_eq!(res.messages[0].msg, bank_send_message);
+        // Only the accepted denoms with a balance are sent
+        let mut deps = mock_dependencies(&coins(1_000_000, "uusd"));
+        let msg = InstantiateMsg {
+            registrar_address,
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        deps.querier
+            .with_exchange_rates(&[("ukrw", Decimal::from_ratio(1300u128, 1u128))]);
+        let msg = ExecuteMsg::SetPaymentConfig {
+            oracle: None,
+            accepted_assets: vec![
+                AssetInfo::NativeToken {
+                    denom: String::from("uusd"),
+                },
+                AssetInfo::NativeToken {
+                    denom: String::from("ukrw"),
+                },
+            ],
+            slippage_tolerance: Decimal::percent(1),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Withdraw {}).unwrap();
+        assert_eq!(
+            res.messages[..],
+            [SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(1_000_000u128),
+                }],
+            }))]
+        );
+        assert_eq!(res.attributes[1].value, "1000000uusd");
     }
 
     #[test] // Should return error if withdraw with non-owner
@@ -1051,9 +1121,7 @@ mod tests {
             min_commitment_age: 0, // For by-pass commitment guard
             min_registration_duration: 24 * 3600 * 365,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -1065,10 +1133,10 @@ mod tests {
         let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
         assert_eq!(
             err,
-            ContractError::NotOwner {
+            ContractError::Admin(AdminError::MissingRole {
                 sender: String::from("alice"),
-                owner: String::from("creator")
-            }
+                role: String::from("treasury")
+            })
         );
     }
 
@@ -1081,9 +1149,7 @@ mod tests {
             min_commitment_age: 0, // For by-pass commitment guard
             min_registration_duration: 24 * 3600 * 365,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -1093,12 +1159,8 @@ mod tests {
             max_commitment_age: 120,
             min_commitment_age: 20,
             min_registration_duration: 24 * 3600 * 365 * 2,
-            tier1_price: 6_000_000u64,
-            tier2_price: 5_000_000u64,
-            tier3_price: 4_000_000u64,
             enable_registration: true,
             registrar_address: String::from("new_registrar_address"),
-            owner: String::from("new_owner"),
         };
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
@@ -1108,7 +1170,8 @@ mod tests {
         assert_eq!(
             res,
             OwnerResponse {
-                owner: Addr::unchecked(String::from("new_owner"))
+                owner: Addr::unchecked(String::from("creator")),
+                pending_owner: None,
             }
         );
 
@@ -1148,9 +1211,8 @@ mod tests {
         assert_eq!(
             res,
             PriceResponse {
-                tier1_price: 6_000_000u64,
-                tier2_price: 5_000_000u64,
-                tier3_price: 4_000_000u64,
+                tiers: default_price_tiers(),
+                overrides: vec![],
             }
         );
     }
@@ -1164,9 +1226,7 @@ mod tests {
             min_commitment_age: 0, // For by-pass commitment guard
             min_registration_duration: 24 * 3600 * 365,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
@@ -1176,12 +1236,8 @@ mod tests {
             max_commitment_age: 120,
             min_commitment_age: 20,
             min_registration_duration: 24 * 3600 * 365 * 2,
-            tier1_price: 6_000_000u64,
-            tier2_price: 5_000_000u64,
-            tier3_price: 4_000_000u64,
             enable_registration: true,
             registrar_address: String::from("new_registrar_address"),
-            owner: String::from("new_owner"),
         };
         let info = mock_info("alice", &coins(0, "uusd"));
         let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
@@ -1196,67 +1252,2359 @@ mod tests {
     }
 
     #[test]
-    fn test_set_config_transfer_owner() {
+    fn test_transfer_ownership() {
         let mut deps = mock_dependencies(&[]);
-        let registrar_address = String::from("registrar_address");
         let msg = InstantiateMsg {
-            registrar_address: registrar_address.clone(),
-            min_commitment_age: 0, // For by-pass commitment guard
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
             min_registration_duration: 24 * 3600 * 365,
             max_commitment_age: 100,
-            tier1_price: 640_000_000u64,
-            tier2_price: 160_000_000u64,
-            tier3_price: 5_000_000u64,
+            price_tiers: default_price_tiers(),
             enable_registration: true,
         };
         let info = mock_info("creator", &coins(0, "uusd"));
         instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let msg = ExecuteMsg::SetConfig {
-            max_commitment_age: 120,
-            min_commitment_age: 20,
-            min_registration_duration: 24 * 3600 * 365 * 2,
-            tier1_price: 6_000_000u64,
-            tier2_price: 5_000_000u64,
-            tier3_price: 4_000_000u64,
-            enable_registration: true,
-            registrar_address: String::from("new_registrar_address"),
+        // Only the owner can propose
+        let msg = ExecuteMsg::ProposeOwner {
             owner: String::from("new_owner"),
         };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let msg = QueryMsg::Owner {};
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        // Proposing does not hand over control yet
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap();
         let res: OwnerResponse = from_binary(&res).unwrap();
         assert_eq!(
             res,
             OwnerResponse {
-                owner: Addr::unchecked(String::from("new_owner"))
+                owner: Addr::unchecked("creator"),
+                pending_owner: Some(Addr::unchecked("new_owner")),
             }
         );
 
-        let msg = ExecuteMsg::SetConfig {
-            max_commitment_age: 120,
-            min_commitment_age: 20,
-            min_registration_duration: 24 * 3600 * 365 * 2,
-            tier1_price: 6_000_000u64,
-            tier2_price: 5_000_000u64,
-            tier3_price: 4_000_000u64,
-            enable_registration: true,
-            registrar_address: String::from("new_registrar_address"),
-            owner: String::from("creator"),
-        };
-        let info = mock_info("new_owner", &coins(0, "uusd"));
-        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::AcceptOwnership {};
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Admin(AdminError::NotPendingOwner {
+                sender: String::from("alice")
+            })
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("new_owner", &[]),
+            msg.clone(),
+        )
+        .unwrap();
 
-        let msg = QueryMsg::Owner {};
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Owner {}).unwrap();
         let res: OwnerResponse = from_binary(&res).unwrap();
         assert_eq!(
             res,
             OwnerResponse {
-                owner: Addr::unchecked(String::from("creator"))
+                owner: Addr::unchecked("new_owner"),
+                pending_owner: None,
+            }
+        );
+
+        // The proposal is consumed and the previous owner lost control
+        let err = execute(deps.as_mut(), mock_env(), mock_info("new_owner", &[]), msg).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::NotPendingOwner { .. })
+        ));
+        let msg = ExecuteMsg::SetEnableRegistration {
+            enable_registration: false,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
+    }
+
+    #[test]
+    fn test_roles() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::SetRole {
+            role: Role::PricingAdmin,
+            address: Some(String::from("pricing")),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetRole {
+            role: Role::Treasury,
+            address: Some(String::from("treasury")),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Roles {}).unwrap();
+        let res: RolesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.roles,
+            vec![
+                RoleAddress {
+                    role: Role::PricingAdmin,
+                    address: Addr::unchecked("pricing"),
+                },
+                RoleAddress {
+                    role: Role::Treasury,
+                    address: Addr::unchecked("treasury"),
+                },
+            ]
+        );
+
+        // A role only unlocks its own operations
+        let price_msg = ExecuteMsg::SetPriceTiers {
+            tiers: default_price_tiers(),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("pricing", &[]),
+            price_msg.clone(),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("treasury", &[]),
+            price_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Admin(AdminError::MissingRole {
+                sender: String::from("treasury"),
+                role: String::from("pricing_admin")
+            })
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("treasury", &[]),
+            ExecuteMsg::Withdraw {},
+        )
+        .unwrap();
+        let msg = ExecuteMsg::SetEnableRegistration {
+            enable_registration: false,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("pricing", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
+
+        // The owner keeps every role, and roles cannot touch owner-only settings
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::ProposeOwner {
+            owner: String::from("pricing"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("pricing", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+
+        // Revoking
+        let msg = ExecuteMsg::SetRole {
+            role: Role::PricingAdmin,
+            address: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("pricing", &[]),
+            price_msg,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
+    }
+
+    #[test]
+    fn test_premium() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetPremium {
+            start_price: 100_000_000,
+            duration: 21 * 24 * 3600,
+            half_life: 24 * 3600,
+        };
+        let info = mock_info("alice", &coins(0, "uusd"));
+        assert!(execute(deps.as_mut(), mock_env(), info, msg.clone()).is_err());
+        let info = mock_info("creator", &coins(0, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPremium {}).unwrap();
+        let res: PremiumResponse = from_binary(&res).unwrap();
+        assert_eq!(100_000_000, res.start_price);
+
+        // alice.ust was released from the grace period one day ago
+        let now = mock_env().block.time.seconds();
+        deps.querier.with_expires(
+            "9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501",
+            now - GRACE_PERIOD - 24 * 3600,
+        );
+        let rent_price = |deps: &OwnedDeps<_, _, _>, seconds: u64| {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(seconds);
+            let msg = QueryMsg::RentPrice {
+                name: String::from("alice"),
+                duration: 24 * 3600 * 365,
+            };
+            let res = query(deps.as_ref(), env, msg).unwrap();
+            from_binary::<RentPriceResponse>(&res).unwrap()
+        };
+        // Half of the start price, minus the remainder at the end of the window
+        let res = rent_price(&deps, 0);
+        assert_eq!(Uint128::from(49_999_953u128), res.premium);
+        assert_eq!(Uint128::from(54_999_953u128), res.price);
+        let res = rent_price(&deps, 12 * 3600);
+        assert_eq!(Uint128::from(35_355_292u128), res.premium);
+        let res = rent_price(&deps, 20 * 24 * 3600);
+        assert_eq!(Uint128::zero(), res.premium);
+        assert_eq!(Uint128::from(5_000_000u128), res.price);
+
+        // Names never registered have no premium
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RentPrice {
+                name: String::from("bob"),
+                duration: 24 * 3600 * 365,
+            },
+        )
+        .unwrap();
+        let res: RentPriceResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::zero(), res.premium);
+
+        // Registration has to pay the premium
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetCommitment {
+                name: String::from("alice"),
+                owner: String::from("alice"),
+                secret: String::from("tns_secret"),
+                resolver: None,
+                address: None,
+            },
+        )
+        .unwrap();
+        let get_commitment_response: GetCommitmentResponse = from_binary(&res).unwrap();
+        let msg = ExecuteMsg::Commit {
+            commitment: get_commitment_response.commitment,
+        };
+        let info = mock_info("alice", &coins(0, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let msg = ExecuteMsg::Register {
+            name: String::from("alice"),
+            owner: String::from("alice"),
+            duration: 24 * 3600 * 365,
+            secret: String::from("tns_secret"),
+            resolver: None,
+            address: None,
+            referrer: None,
+        };
+        let info = mock_info("alice", &coins(5_000_000, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientFund {
+                amount: Uint128::from(5_000_000u128),
+                required: Uint128::from(54_999_953u128),
+            }
+        );
+    }
+
+    #[test]
+    fn test_payment_config() {
+        let mut deps = mock_dependencies(&[]);
+        deps.querier.with_exchange_rates(&[
+            ("ukrw", Decimal::from_ratio(1300u128, 1u128)),
+            ("cw20_address", Decimal::from_ratio(2u128, 1u128)),
+        ]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let uusd = AssetInfo::NativeToken {
+            denom: String::from("uusd"),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: String::from("ukrw"),
+        };
+        let msg = ExecuteMsg::SetPaymentConfig {
+            oracle: None,
+            accepted_assets: vec![uusd.clone(), ukrw.clone()],
+            slippage_tolerance: Decimal::percent(1),
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let rent_prices = |deps: &OwnedDeps<_, _, _>| {
+            let msg = QueryMsg::RentPrices {
+                name: String::from("alice"),
+                duration: 24 * 3600 * 365,
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<RentPricesResponse>(&res).unwrap().prices
+        };
+        assert_eq!(
+            rent_prices(&deps),
+            vec![
+                Asset {
+                    info: uusd.clone(),
+                    amount: Uint128::from(5_000_000u128),
+                },
+                Asset {
+                    info: ukrw.clone(),
+                    amount: Uint128::from(6_500_000_000u128),
+                },
+            ]
+        );
+
+        // Payments within the slippage tolerance are accepted
+        let msg = ExecuteMsg::Renew {
+            name: String::from("alice"),
+            duration: 24 * 3600 * 365,
+            referrer: None,
+        };
+        let info = mock_info("alice", &coins(6_435_000_000, "ukrw"));
+        execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        let info = mock_info("alice", &coins(6_434_999_999, "ukrw"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientFund {
+                amount: Uint128::from(6_434_999_999u128),
+                required: Uint128::from(6_500_000_000u128),
+            }
+        );
+        // Denoms which are not accepted do not count
+        let info = mock_info("alice", &coins(6_500_000_000, "ueur"));
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
+
+        // Tokens are only accepted through the CW20 whitelist
+        let msg = ExecuteMsg::SetPaymentConfig {
+            oracle: Some(String::from("oracle_address")),
+            accepted_assets: vec![
+                uusd.clone(),
+                AssetInfo::Token {
+                    contract_addr: String::from("cw20_address"),
+                },
+            ],
+            slippage_tolerance: Decimal::zero(),
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BadRequest {
+                msg: String::from("accepted_assets only takes native denoms, use SetCw20Token"),
             }
         );
+
+        // Denoms are priced through the oracle contract if set
+        let msg = ExecuteMsg::SetPaymentConfig {
+            oracle: Some(String::from("oracle_address")),
+            accepted_assets: vec![uusd.clone(), ukrw.clone()],
+            slippage_tolerance: Decimal::zero(),
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            rent_prices(&deps),
+            vec![
+                Asset {
+                    info: uusd,
+                    amount: Uint128::from(5_000_000u128),
+                },
+                Asset {
+                    info: ukrw,
+                    amount: Uint128::from(6_500_000_000u128),
+                },
+            ]
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPaymentConfig {}).unwrap();
+        let res: PaymentConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(Some(Addr::unchecked("oracle_address")), res.oracle);
+    }
+
+    #[test]
+    fn test_refund_overpayment() {
+        let mut deps = mock_dependencies(&[]);
+        deps.querier.with_tax(
+            Decimal::permille(5),
+            &[(&String::from("uusd"), &Uint128::from(1_000_000u128))],
+        );
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::Renew {
+            name: String::from("alice"),
+            duration: 24 * 3600 * 365,
+            referrer: None,
+        };
+        let info = mock_info("alice", &coins(6_000_000, "uusd"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        // The excess is sent back minus the tax
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("alice"),
+                amount: coins(995_024, "uusd"),
+            })
+        );
+
+        // Exact payments have nothing to refund
+        let info = mock_info("alice", &coins(5_000_000, "uusd"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        let info = mock_info(
+            "alice",
+            &[
+                Coin {
+                    denom: String::from("uusd"),
+                    amount: Uint128::from(5_000_000u128),
+                },
+                Coin {
+                    denom: String::from("uluna"),
+                    amount: Uint128::from(1u128),
+                },
+            ],
+        );
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnexpectedFund {
+                denom: String::from("uluna"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_renewal_credit() {
+        let mut deps = mock_dependencies(&coins(10_000_000, "uusd"));
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // alice expires in 60 days
+        let alice_token_id = "9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501";
+        let now = mock_env().block.time.seconds();
+        deps.querier
+            .with_expires(alice_token_id, now + 60 * 24 * 3600);
+
+        let msg = ExecuteMsg::DepositRenewalCredit {
+            name: String::from("bobby"),
+        };
+        let info = mock_info("friend", &coins(6_000_000, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+
+        let msg = ExecuteMsg::DepositRenewalCredit {
+            name: String::from("alice"),
+        };
+        let info = mock_info("friend", &coins(6_000_000, "uluna"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnexpectedFund {
+                denom: String::from("uluna"),
+            }
+        );
+        let info = mock_info("friend", &coins(6_000_000, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let credit_query = QueryMsg::RenewalCredit {
+            name: String::from("alice"),
+            depositor: String::from("friend"),
+        };
+        let res = query(deps.as_ref(), mock_env(), credit_query.clone()).unwrap();
+        let renewal_credit: RenewalCreditResponse = from_binary(&res).unwrap();
+        assert_eq!(renewal_credit.credit, Uint128::from(6_000_000u128));
+        assert!(!renewal_credit.stale);
+
+        // Not within the default window of 30 days yet
+        let msg = ExecuteMsg::ProcessRenewals {
+            names: vec![String::from("alice"), String::from("bobby")],
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        let window_msg = ExecuteMsg::SetRenewalWindow {
+            window: 90 * 24 * 3600,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            window_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            window_msg,
+        )
+        .unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::RenewalWindow {}).unwrap();
+        let renewal_window: RenewalWindowResponse = from_binary(&res).unwrap();
+        assert_eq!(renewal_window.window, 90 * 24 * 3600);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|m| m.msg)
+                .collect::<Vec<CosmosMsg>>(),
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("registrar_address"),
+                msg: to_binary(&RegistrarExecuteMsg::<Extension>::Renew {
+                    id: String::from(alice_token_id),
+                    duration: 24 * 3600 * 365,
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+        let res = query(deps.as_ref(), mock_env(), credit_query).unwrap();
+        let renewal_credit: RenewalCreditResponse = from_binary(&res).unwrap();
+        assert_eq!(renewal_credit.credit, Uint128::from(1_000_000u128));
+
+        // The remaining credit does not cover another year
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        // Unspent credit is kept out of withdrawals
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Withdraw {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("creator"),
+                amount: coins(9_000_000, "uusd"),
+            })
+        );
+
+        let msg = ExecuteMsg::ProcessRenewals { names: vec![] };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+    }
+
+    #[test]
+    fn test_renewal_credit_reregistration() {
+        let mut deps = mock_dependencies(&coins(10_000_000, "uusd"));
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let window_msg = ExecuteMsg::SetRenewalWindow {
+            window: 90 * 24 * 3600,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            window_msg,
+        )
+        .unwrap();
+
+        let alice_token_id = "9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501";
+        let now = mock_env().block.time.seconds();
+        deps.querier
+            .with_expires(alice_token_id, now + 60 * 24 * 3600);
+        let msg = ExecuteMsg::DepositRenewalCredit {
+            name: String::from("alice"),
+        };
+        let info = mock_info("friend", &coins(6_000_000, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+
+        // alice gets released and registered again by someone else
+        let register_msg = ExecuteMsg::OwnerRegister {
+            name: String::from("alice"),
+            owner: String::from("bob"),
+            duration: 24 * 3600 * 365,
+            resolver: None,
+            address: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            register_msg,
+        )
+        .unwrap();
+
+        let credit_query = QueryMsg::RenewalCredit {
+            name: String::from("alice"),
+            depositor: String::from("friend"),
+        };
+        let res = query(deps.as_ref(), mock_env(), credit_query.clone()).unwrap();
+        let renewal_credit: RenewalCreditResponse = from_binary(&res).unwrap();
+        assert_eq!(renewal_credit.credit, Uint128::from(6_000_000u128));
+        assert!(renewal_credit.stale);
+
+        // The credit of the previous registration does not pay for the new owner
+        let renew_msg = ExecuteMsg::ProcessRenewals {
+            names: vec![String::from("alice")],
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            renew_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 0);
+        let info = mock_info("friend", &coins(6_000_000, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+
+        // Only the depositor gets the credit back
+        let withdraw_msg = ExecuteMsg::WithdrawRenewalCredit {
+            name: String::from("alice"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            withdraw_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("friend", &[]),
+            withdraw_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("friend"),
+                amount: coins(6_000_000, "uusd"),
+            })
+        );
+        let res = query(deps.as_ref(), mock_env(), credit_query).unwrap();
+        let renewal_credit: RenewalCreditResponse = from_binary(&res).unwrap();
+        assert_eq!(renewal_credit.credit, Uint128::zero());
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("friend", &[]),
+            withdraw_msg,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+
+        // A credit for the new registration pays its renewals
+        let info = mock_info("bob", &coins(6_000_000, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            renew_msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RenewalCredit {
+                name: String::from("alice"),
+                depositor: String::from("bob"),
+            },
+        )
+        .unwrap();
+        let renewal_credit: RenewalCreditResponse = from_binary(&res).unwrap();
+        assert_eq!(renewal_credit.credit, Uint128::from(1_000_000u128));
+        assert!(!renewal_credit.stale);
+    }
+
+    #[test]
+    fn test_referral_rewards() {
+        let mut deps = mock_dependencies(&coins(10_000_000, "uusd"));
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetReferralFee { bps: 10_001 };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+        let msg = ExecuteMsg::SetReferralFee { bps: 1_000 };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ReferralFee {}).unwrap();
+        let referral_fee: ReferralFeeResponse = from_binary(&res).unwrap();
+        assert_eq!(referral_fee.bps, 1_000);
+
+        // Referring yourself earns nothing
+        let msg = ExecuteMsg::Renew {
+            name: String::from("alice"),
+            duration: 24 * 3600 * 365,
+            referrer: Some(String::from("alice")),
+        };
+        let info = mock_info("alice", &coins(5_000_000, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+
+        let msg = ExecuteMsg::Renew {
+            name: String::from("alice"),
+            duration: 24 * 3600 * 365,
+            referrer: Some(String::from("partner")),
+        };
+        // Only the fee kept by the contract is shared, not the refund of the excess
+        let info = mock_info("alice", &coins(6_000_000, "uusd"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let reward = res
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key == "referral_reward")
+            .unwrap();
+        assert_eq!(reward.value, "500000uusd");
+
+        let uusd_reward = Asset {
+            info: AssetInfo::NativeToken {
+                denom: String::from("uusd"),
+            },
+            amount: Uint128::from(500_000u128),
+        };
+        let query_msg = QueryMsg::ReferralStats {
+            referrer: String::from("partner"),
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
+        let referral_stats: ReferralStatsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            referral_stats,
+            ReferralStatsResponse {
+                referrer: Addr::unchecked("partner"),
+                referrals: 1,
+                pending_rewards: vec![uusd_reward.clone()],
+                total_rewards: vec![uusd_reward.clone()],
+            }
+        );
+
+        // Unclaimed rewards are kept out of withdrawals
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Withdraw {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("creator"),
+                amount: coins(9_500_000, "uusd"),
+            })
+        );
+
+        let msg = ExecuteMsg::ClaimReferralRewards {};
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("partner", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("partner"),
+                amount: coins(500_000, "uusd"),
+            })
+        );
+        let err = execute(deps.as_mut(), mock_env(), mock_info("partner", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let referral_stats: ReferralStatsResponse = from_binary(&res).unwrap();
+        assert!(referral_stats.pending_rewards.is_empty());
+        assert_eq!(referral_stats.total_rewards, vec![uusd_reward]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Withdraw {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("creator"),
+                amount: coins(10_000_000, "uusd"),
+            })
+        );
+    }
+
+    #[test]
+    fn test_fee_shares() {
+        let mut deps = mock_dependencies(&coins(10_000_001, "uusd"));
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Distribute {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+
+        let shares = vec![
+            FeeShare {
+                recipient: String::from("treasury"),
+                share: Decimal::percent(50),
+            },
+            FeeShare {
+                recipient: String::from("staking"),
+                share: Decimal::percent(30),
+            },
+            FeeShare {
+                recipient: String::from("referral_pool"),
+                share: Decimal::percent(20),
+            },
+        ];
+        let msg = ExecuteMsg::SetFeeShares {
+            shares: shares.clone(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::SetFeeShares {
+                shares: shares[..2].to_vec(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::FeeShares {}).unwrap();
+        let fee_shares: FeeSharesResponse = from_binary(&res).unwrap();
+        assert_eq!(fee_shares.shares, shares);
+
+        // The last recipient gets the rounding dust
+        let expected = vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("treasury"),
+                amount: coins(5_000_000, "uusd"),
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("staking"),
+                amount: coins(3_000_000, "uusd"),
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("referral_pool"),
+                amount: coins(2_000_001, "uusd"),
+            }),
+        ];
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Distribute {},
+        )
+        .unwrap();
+        let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(messages, expected);
+
+        // Withdraw follows the fee shares as well
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Withdraw {},
+        )
+        .unwrap();
+        let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(messages, expected);
+
+        let msg = ExecuteMsg::SetFeeShares { shares: vec![] };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Withdraw {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("creator"),
+                amount: coins(10_000_001, "uusd"),
+            })
+        );
+    }
+
+    #[test]
+    fn test_cw20_payment() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetCw20Token {
+            token: String::from("token_address"),
+            price_multiplier: Some(Decimal::from_ratio(2u128, 1u128)),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Cw20Tokens {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let cw20_tokens: Cw20TokensResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            cw20_tokens.tokens,
+            vec![Cw20Token {
+                token: Addr::unchecked("token_address"),
+                price_multiplier: Decimal::from_ratio(2u128, 1u128),
+            }]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RentPrices {
+                name: String::from("alice"),
+                duration: 24 * 3600 * 365,
+            },
+        )
+        .unwrap();
+        let rent_prices: RentPricesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            rent_prices.prices[1],
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: String::from("token_address"),
+                },
+                amount: Uint128::from(10_000_000u128),
+            }
+        );
+
+        let renew_msg = to_binary(&Cw20HookMsg::Renew {
+            name: String::from("alice"),
+            duration: 24 * 3600 * 365,
+            referrer: None,
+        })
+        .unwrap();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("alice"),
+            amount: Uint128::from(12_000_000u128),
+            msg: renew_msg.clone(),
+        });
+
+        // Only whitelisted tokens are accepted
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other_token", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnexpectedFund {
+                denom: String::from("other_token"),
+            }
+        );
+
+        // The excess is sent back in tokens
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("token_address", &[]),
+            msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("token_address"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("alice"),
+                    amount: Uint128::from(2_000_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("alice"),
+            amount: Uint128::from(9_999_999u128),
+            msg: renew_msg,
+        });
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("token_address", &[]),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientFund {
+                amount: Uint128::from(9_999_999u128),
+                required: Uint128::from(10_000_000u128),
+            }
+        );
+
+        // Registrations reveal the commitment of the token sender
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetCommitment {
+                name: String::from("alice"),
+                owner: String::from("alice"),
+                secret: String::from("tns_secret"),
+                resolver: None,
+                address: None,
+            },
+        )
+        .unwrap();
+        let commitment = from_binary::<GetCommitmentResponse>(&res)
+            .unwrap()
+            .commitment;
+        let msg = ExecuteMsg::Commit { commitment };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("alice"),
+            amount: Uint128::from(10_000_000u128),
+            msg: to_binary(&Cw20HookMsg::Register {
+                name: String::from("alice"),
+                owner: String::from("alice"),
+                duration: 24 * 3600 * 365,
+                secret: String::from("tns_secret"),
+                resolver: None,
+                address: None,
+                referrer: None,
+            })
+            .unwrap(),
+        });
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("token_address", &[]),
+            msg,
+        )
+        .unwrap();
+        assert_eq!(res.attributes[0].value, "register");
+
+        // Withdraw sweeps the token balances as well
+        deps.querier
+            .with_token_balance("token_address", Uint128::from(20_000_000u128));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Withdraw {},
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("token_address"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("creator"),
+                    amount: Uint128::from(20_000_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        let msg = ExecuteMsg::SetCw20Token {
+            token: String::from("token_address"),
+            price_multiplier: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Cw20Tokens {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let cw20_tokens: Cw20TokensResponse = from_binary(&res).unwrap();
+        assert!(cw20_tokens.tokens.is_empty());
+    }
+
+    #[test]
+    fn test_batch_renew() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let names = vec![
+            (String::from("alice"), 24 * 3600 * 365),
+            (String::from("bob"), 2 * 24 * 3600 * 365),
+        ];
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BatchRentPrice {
+                names: names.clone(),
+            },
+        )
+        .unwrap();
+        let batch_rent_price: BatchRentPriceResponse = from_binary(&res).unwrap();
+        // 5 UST for alice and 2 years of 640 UST for bob
+        assert_eq!(batch_rent_price.price, Uint128::from(1_285_000_000u128));
+        assert_eq!(batch_rent_price.prices.len(), 2);
+        assert_eq!(
+            batch_rent_price.prices[1].price,
+            Uint128::from(1_280_000_000u128)
+        );
+
+        let msg = ExecuteMsg::BatchRenew {
+            names: names.clone(),
+        };
+        let info = mock_info("alice", &coins(1_284_999_999, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientFund {
+                amount: Uint128::from(1_284_999_999u128),
+                required: Uint128::from(1_285_000_000u128),
+            }
+        );
+
+        let info = mock_info("alice", &coins(1_285_000_000, "uusd"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("registrar_address"),
+                msg: to_binary(&RegistrarExecuteMsg::<Extension>::Renew {
+                    id: String::from(
+                        "9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501"
+                    ),
+                    duration: 24 * 3600 * 365,
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // Every name must be renewable
+        let msg = ExecuteMsg::BatchRenew {
+            names: vec![
+                (String::from("alice"), 24 * 3600 * 365),
+                (String::from("bob"), 24 * 3600),
+            ],
+        };
+        let info = mock_info("alice", &coins(1_285_000_000, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DurationTooShort { .. }));
+
+        let msg = ExecuteMsg::BatchRenew { names: vec![] };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+    }
+
+    #[test]
+    fn test_commitment_deposit() {
+        let mut deps = mock_dependencies(&coins(10_000_000, "uusd"));
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetCommitmentDeposit {
+            amount: Uint128::from(1_000_000u128),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::CommitmentDeposit {}).unwrap();
+        let deposit_response: CommitmentDepositResponse = from_binary(&res).unwrap();
+        assert_eq!(deposit_response.amount, Uint128::from(1_000_000u128));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetCommitment {
+                name: String::from("alice"),
+                owner: String::from("alice"),
+                secret: String::from("tns_secret"),
+                resolver: None,
+                address: None,
+            },
+        )
+        .unwrap();
+        let commitment = from_binary::<GetCommitmentResponse>(&res)
+            .unwrap()
+            .commitment;
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::Commit {
+                commitment: String::from("not_a_hash"),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+
+        let msg = ExecuteMsg::Commit {
+            commitment: commitment.clone(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &coins(999_999, "uusd")),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientFund {
+                amount: Uint128::from(999_999u128),
+                required: Uint128::from(1_000_000u128),
+            }
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &coins(1_000_000, "uusd")),
+            msg,
+        )
+        .unwrap();
+
+        // Pending deposits are kept out of withdrawals
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Withdraw {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("creator"),
+                amount: coins(9_000_000, "uusd"),
+            })
+        );
+
+        // Only the committer can reveal
+        let msg = ExecuteMsg::Register {
+            name: String::from("alice"),
+            owner: String::from("alice"),
+            duration: 24 * 3600 * 365,
+            secret: String::from("tns_secret"),
+            resolver: None,
+            address: None,
+            referrer: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &coins(5_000_000, "uusd")),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ConsumeNonexistCommitment {
+                commitment: commitment.clone(),
+            }
+        );
+
+        // The deposit is refunded on registration
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &coins(5_000_000, "uusd")),
+            msg,
+        )
+        .unwrap();
+        assert!(res.messages.iter().any(|message| message.msg
+            == CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("alice"),
+                amount: coins(1_000_000, "uusd"),
+            })));
+        let alice = deps.api.addr_canonicalize("alice").unwrap().to_vec();
+        assert!(COMMITMENTS
+            .may_load(deps.as_ref().storage, (alice, commitment))
+            .unwrap()
+            .is_none());
+
+        // Expired commitments are swept and their deposits forfeited
+        let commitment =
+            String::from("9232a542ecd323875f2ebac7db9f86ab606badb823af8628b7615ad78227e349");
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &coins(1_000_000, "uusd")),
+            ExecuteMsg::Commit {
+                commitment: commitment.clone(),
+            },
+        )
+        .unwrap();
+        let msg = ExecuteMsg::SweepCommitments {
+            start_after: None,
+            limit: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[1].value, "0");
+        let bob = deps.api.addr_canonicalize("bob").unwrap().to_vec();
+        assert!(COMMITMENTS.has(deps.as_ref().storage, (bob.clone(), commitment.clone())));
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(101);
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(res.attributes[1].value, "1");
+        assert_eq!(res.attributes[2].value, "1000000");
+        assert!(!COMMITMENTS.has(deps.as_ref().storage, (bob, commitment)));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Withdraw {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("creator"),
+                amount: coins(10_000_000, "uusd"),
+            })
+        );
+    }
+
+    #[test]
+    fn test_commitment_front_running() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetCommitment {
+                name: String::from("alice"),
+                owner: String::from("alice"),
+                secret: String::from("tns_secret"),
+                resolver: None,
+                address: None,
+            },
+        )
+        .unwrap();
+        let commitment = from_binary::<GetCommitmentResponse>(&res)
+            .unwrap()
+            .commitment;
+
+        // A copy of a pending commitment does not block its committer
+        let msg = ExecuteMsg::Commit {
+            commitment: commitment.clone(),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(10);
+        execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::CommitmentTimestamp {
+                committer: String::from("alice"),
+                commitment: commitment.clone(),
+            },
+        )
+        .unwrap();
+        let value: CommitmentTimestampResponse = from_binary(&res).unwrap();
+        assert_eq!(value.timestamp, env.block.time.seconds());
+
+        let msg = ExecuteMsg::Register {
+            name: String::from("alice"),
+            owner: String::from("alice"),
+            duration: 24 * 3600 * 365,
+            secret: String::from("tns_secret"),
+            resolver: None,
+            address: None,
+            referrer: None,
+        };
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("alice", &coins(5_000_000, "uusd")),
+            msg,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_sweep_legacy_commitments() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Commitment timestamps stored before commitments had a committer
+        let commitments = [
+            String::from("9232a542ecd323875f2ebac7db9f86ab606badb823af8628b7615ad78227e349"),
+            String::from("e1a4e3ae8a2ac0e0fe3b5a3a1e1bca8c3a4f7b2bd1da8d7c4ce5bd3ddd9a0c5b"),
+        ];
+        for commitment in commitments.iter() {
+            LEGACY_COMMITMENTS
+                .save(deps.as_mut().storage, commitment.clone(), &1571797419)
+                .unwrap();
+        }
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::SweepLegacyCommitments { limit: Some(1) };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(res.attributes[1].value, "1");
+        assert!(!LEGACY_COMMITMENTS.has(deps.as_ref().storage, commitments[0].clone()));
+        assert!(LEGACY_COMMITMENTS.has(deps.as_ref().storage, commitments[1].clone()));
+        let msg = ExecuteMsg::SweepLegacyCommitments { limit: None };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "1");
+        assert!(!LEGACY_COMMITMENTS.has(deps.as_ref().storage, commitments[1].clone()));
+    }
+
+    #[test]
+    fn test_migrate_price_tiers() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Config stored before the price tiers
+        #[derive(Serialize, Deserialize)]
+        struct BaselineConfig {
+            max_commitment_age: u64,
+            min_commitment_age: u64,
+            min_registration_duration: u64,
+            tier1_price: u64,
+            tier2_price: u64,
+            tier3_price: u64,
+            enable_registration: bool,
+            registrar_address: CanonicalAddr,
+            owner: CanonicalAddr,
+        }
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        Item::new("CONFIG")
+            .save(
+                deps.as_mut().storage,
+                &BaselineConfig {
+                    max_commitment_age: config.max_commitment_age,
+                    min_commitment_age: config.min_commitment_age,
+                    min_registration_duration: config.min_registration_duration,
+                    tier1_price: 640_000_000u64,
+                    tier2_price: 160_000_000u64,
+                    tier3_price: 5_000_000u64,
+                    enable_registration: config.enable_registration,
+                    registrar_address: config.registrar_address.clone(),
+                    owner: config.owner.clone(),
+                },
+            )
+            .unwrap();
+        PRICE_TIERS.remove(deps.as_mut().storage);
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPrice {}).unwrap();
+        let res: PriceResponse = from_binary(&res).unwrap();
+        assert_eq!(res.tiers, default_price_tiers());
+        assert_eq!(CONFIG.load(deps.as_ref().storage).unwrap(), config);
+        assert!(LEGACY_PRICE_CONFIG.load(deps.as_ref().storage).is_err());
+
+        // Tiers set since are kept
+        let msg = ExecuteMsg::SetPriceTiers {
+            tiers: default_price_tiers()[2..].to_vec(),
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPrice {}).unwrap();
+        let res: PriceResponse = from_binary(&res).unwrap();
+        assert_eq!(res.tiers, default_price_tiers()[2..].to_vec());
+    }
+
+    #[test]
+    fn test_price_tiers() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // Tiers may leave gaps but must not overlap
+        let msg = ExecuteMsg::SetPriceTiers {
+            tiers: vec![
+                PriceTier {
+                    min_len: 6,
+                    max_len: None,
+                    price_per_year: 1_000_000u64,
+                },
+                PriceTier {
+                    min_len: 3,
+                    max_len: Some(6),
+                    price_per_year: 20_000_000u64,
+                },
+            ],
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::BadRequest { .. }));
+
+        let tiers = vec![
+            PriceTier {
+                min_len: 6,
+                max_len: None,
+                price_per_year: 1_000_000u64,
+            },
+            PriceTier {
+                min_len: 4,
+                max_len: Some(5),
+                price_per_year: 20_000_000u64,
+            },
+        ];
+        let msg = ExecuteMsg::SetPriceTiers {
+            tiers: tiers.clone(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::SetPriceOverride {
+            label: String::from("terra"),
+            price_per_year: Some(500_000_000u64),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let rent_price = |deps: &OwnedDeps<_, _, _>, name: &str| {
+            let msg = QueryMsg::RentPrice {
+                name: String::from(name),
+                duration: 24 * 3600 * 365,
+            };
+            query(deps.as_ref(), mock_env(), msg)
+                .map(|res| from_binary::<RentPriceResponse>(&res).unwrap().price)
+        };
+        assert_eq!(
+            Uint128::from(1_000_000u128),
+            rent_price(&deps, "alice1").unwrap()
+        );
+        assert_eq!(
+            Uint128::from(20_000_000u128),
+            rent_price(&deps, "alice").unwrap()
+        );
+        assert_eq!(
+            Uint128::from(500_000_000u128),
+            rent_price(&deps, "terra").unwrap()
+        );
+        // No tier covers three characters anymore
+        assert!(rent_price(&deps, "bob").is_err());
+
+        let msg = ExecuteMsg::Renew {
+            name: String::from("bob"),
+            duration: 24 * 3600 * 365,
+            referrer: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::NoPriceTier { length: 3 });
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPrice {}).unwrap();
+        let res: PriceResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            PriceResponse {
+                tiers: vec![tiers[1].clone(), tiers[0].clone()],
+                overrides: vec![PriceOverride {
+                    label: String::from("terra"),
+                    price_per_year: 500_000_000u64,
+                }],
+            }
+        );
+
+        let msg = ExecuteMsg::SetPriceOverride {
+            label: String::from("terra"),
+            price_per_year: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            Uint128::from(20_000_000u128),
+            rent_price(&deps, "terra").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_reserved_and_blocked_names() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::AddBlockedNames {
+            names: vec![String::from("badword")],
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::AddReservedNames {
+            names: vec![String::from("alice")],
+            claimant: Some(String::from("alice")),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::AddReservedNames {
+            names: vec![String::from("bobby")],
+            claimant: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let register = |deps: &mut OwnedDeps<_, _, _>, sender: &str, name: &str, secret: &str| {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetCommitment {
+                    name: String::from(name),
+                    owner: String::from(sender),
+                    secret: String::from(secret),
+                    resolver: None,
+                    address: None,
+                },
+            )
+            .unwrap();
+            let commitment = from_binary::<GetCommitmentResponse>(&res)
+                .unwrap()
+                .commitment;
+            let msg = ExecuteMsg::Commit { commitment };
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::Register {
+                name: String::from(name),
+                owner: String::from(sender),
+                duration: 24 * 3600 * 365,
+                secret: String::from(secret),
+                resolver: None,
+                address: None,
+                referrer: None,
+            };
+            let info = mock_info(sender, &coins(5_000_000, "uusd"));
+            execute(deps.as_mut(), mock_env(), info, msg)
+        };
+
+        let err = register(&mut deps, "carol", "alice", "secret").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ReservedName {
+                name: String::from("alice"),
+            }
+        );
+        register(&mut deps, "alice", "alice", "secret").unwrap();
+        let err = register(&mut deps, "alice", "bobby", "secret").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ReservedName {
+                name: String::from("bobby"),
+            }
+        );
+        let err = register(&mut deps, "alice", "badword", "secret").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BlockedName {
+                name: String::from("badword"),
+            }
+        );
+
+        let owner_register = |deps: &mut OwnedDeps<_, _, _>, name: &str| {
+            let msg = ExecuteMsg::OwnerRegister {
+                name: String::from(name),
+                owner: String::from("bob"),
+                duration: 24 * 3600 * 365,
+                resolver: None,
+                address: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg)
+        };
+        owner_register(&mut deps, "bobby").unwrap();
+        let err = owner_register(&mut deps, "badword").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BlockedName {
+                name: String::from("badword"),
+            }
+        );
+
+        let msg = QueryMsg::ReservedNames {
+            start_after: None,
+            limit: Some(1),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res: ReservedNamesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.names,
+            vec![ReservedName {
+                name: String::from("alice"),
+                claimant: Some(Addr::unchecked("alice")),
+            }]
+        );
+        let msg = QueryMsg::ReservedNames {
+            start_after: Some(String::from("alice")),
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res: ReservedNamesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.names,
+            vec![ReservedName {
+                name: String::from("bobby"),
+                claimant: None,
+            }]
+        );
+
+        let msg = QueryMsg::BlockedNames {
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res: BlockedNamesResponse = from_binary(&res).unwrap();
+        assert_eq!(res.names, vec![String::from("badword")]);
+
+        let msg = ExecuteMsg::RemoveBlockedNames {
+            names: vec![String::from("badword")],
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        register(&mut deps, "alice", "badword", "other_secret").unwrap();
+    }
+
+    #[test]
+    fn test_claim() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let duration: u64 = 24 * 3600 * 365 * 2;
+        let entry = ClaimEntry {
+            name: String::from("alice"),
+            claimant: String::from("alice"),
+            duration,
+        };
+        let msg = ExecuteMsg::AddClaims {
+            claims: vec![entry.clone()],
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = QueryMsg::Claims {
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg.clone()).unwrap();
+        let res: ClaimsResponse = from_binary(&res).unwrap();
+        assert_eq!(res.claims, vec![entry]);
+
+        let claim = |deps: &mut OwnedDeps<_, _, _>,
+                     sender: &str,
+                     name: &str,
+                     duration: Option<u64>,
+                     proof: Option<Vec<String>>| {
+            let msg = ExecuteMsg::Claim {
+                name: String::from(name),
+                duration,
+                proof,
+                resolver: None,
+                address: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg)
+        };
+        let err = claim(&mut deps, "bob", "alice", None, None).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+        // Claims go through the registration flow for free
+        let res = claim(&mut deps, "alice", "alice", None, None).unwrap();
+        assert_eq!(res.messages.len(), 4);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("registrar_address"),
+                msg: to_binary(&RegistrarExecuteMsg::<Extension>::Register {
+                    id: String::from(
+                        "9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501",
+                    ), // token_id of alice
+                    owner: mock_env().contract.address.to_string(),
+                    name: String::from("alice"),
+                    duration,
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res: ClaimsResponse = from_binary(&res).unwrap();
+        assert_eq!(res.claims, vec![]);
+        let err = claim(&mut deps, "alice", "alice", None, None).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ClaimNotFound {
+                name: String::from("alice"),
+            }
+        );
+
+        let bobby_leaf = keccak256(format!("bobby:bob:{}", duration).as_bytes());
+        let carol_leaf = keccak256(format!("carol:carol:{}", duration).as_bytes());
+        let root = if bobby_leaf <= carol_leaf {
+            keccak256(&[bobby_leaf.clone(), carol_leaf.clone()].concat())
+        } else {
+            keccak256(&[carol_leaf.clone(), bobby_leaf.clone()].concat())
+        };
+        let msg = ExecuteMsg::SetClaimMerkleRoot {
+            merkle_root: Some(hex::encode(&root)),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ClaimMerkleRoot {}).unwrap();
+        let res: ClaimMerkleRootResponse = from_binary(&res).unwrap();
+        assert_eq!(res.merkle_root, Some(hex::encode(&root)));
+
+        let proof = Some(vec![hex::encode(&carol_leaf)]);
+        let err = claim(&mut deps, "bob", "bobby", Some(duration * 2), proof.clone()).unwrap_err();
+        assert_eq!(err, ContractError::InvalidProof {});
+        let err = claim(&mut deps, "carol", "bobby", Some(duration), proof.clone()).unwrap_err();
+        assert_eq!(err, ContractError::InvalidProof {});
+        claim(&mut deps, "bob", "bobby", Some(duration), proof.clone()).unwrap();
+        let err = claim(&mut deps, "bob", "bobby", Some(duration), proof).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AlreadyClaimed {
+                name: String::from("bobby"),
+            }
+        );
+
+        let proof = Some(vec![hex::encode(&bobby_leaf)]);
+        claim(&mut deps, "carol", "carol", Some(duration), proof).unwrap();
+    }
+
+    #[test]
+    fn test_normalize_name() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let normalize = |deps: &OwnedDeps<_, _, _>, name: &str| {
+            let msg = QueryMsg::NormalizeName {
+                name: String::from(name),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<NormalizeNameResponse>(&res).unwrap()
+        };
+        let is_valid_name = |deps: &OwnedDeps<_, _, _>, name: &str| {
+            let msg = QueryMsg::IsValidName {
+                name: String::from(name),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<IsValidNameResponse>(&res)
+                .unwrap()
+                .is_valid_name
+        };
+
+        for (name, normalized) in [
+            ("Alice", "alice"),
+            ("ＡＬＩＣＥ", "alice"),
+            ("한국어", "한국어"),
+            ("東京タワー", "東京タワー"),
+            ("😀😀😀", "😀😀😀"),
+            ("xn--3e0b707e", "한국"),
+        ] {
+            assert_eq!(
+                normalize(&deps, name),
+                NormalizeNameResponse {
+                    normalized: Some(String::from(normalized)),
+                    error: None,
+                }
+            );
+            assert_eq!(is_valid_name(&deps, name), name == normalized);
+        }
+
+        // Underscore, multiple labels, leading hyphen, Latin mixed with Cyrillic,
+        // and a Cyrillic name spelling a Latin one
+        for name in [
+            "ab_c",
+            "ali.ce.ust",
+            "-alice",
+            "p\u{430}ypal",
+            "\u{440}\u{43e}\u{435}",
+        ] {
+            let res = normalize(&deps, name);
+            assert_eq!(res.normalized, None);
+            assert!(res.error.is_some());
+            assert!(!is_valid_name(&deps, name));
+        }
+
+        // Prices follow the grapheme length of the canonical name
+        let msg = QueryMsg::RentPrice {
+            name: String::from("한국어"),
+            duration: 24 * 3600 * 365,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res: RentPriceResponse = from_binary(&res).unwrap();
+        assert_eq!(res.price, Uint128::from(640_000_000u128));
+        let msg = QueryMsg::RentPrice {
+            name: String::from("Alice"),
+            duration: 24 * 3600 * 365,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res: RentPriceResponse = from_binary(&res).unwrap();
+        assert_eq!(res.price, Uint128::from(5_000_000u128));
+    }
+
+    #[test]
+    fn test_tlds() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registrar_address: String::from("registrar_address"),
+            min_commitment_age: 0,
+            min_registration_duration: 24 * 3600 * 365,
+            max_commitment_age: 100,
+            price_tiers: default_price_tiers(),
+            enable_registration: true,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let price_tiers = vec![PriceTier {
+            min_len: 3,
+            max_len: None,
+            price_per_year: 1_000_000u64,
+        }];
+        let set_tld = |deps: &mut OwnedDeps<_, _, _>, sender: &str, enable_registration: bool| {
+            let msg = ExecuteMsg::SetTld {
+                tld: String::from("luna"),
+                registrar_address: String::from("luna_registrar_address"),
+                price_tiers: price_tiers.clone(),
+                min_registration_duration: 24 * 3600 * 30,
+                enable_registration,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg)
+        };
+        let err = set_tld(&mut deps, "alice", true).unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+        set_tld(&mut deps, "creator", true).unwrap();
+
+        let msg = QueryMsg::ListTlds {
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res: ListTldsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.tlds,
+            vec![TldResponse {
+                tld: String::from("luna"),
+                registrar_address: Addr::unchecked("luna_registrar_address"),
+                price_tiers: price_tiers.clone(),
+                min_registration_duration: 24 * 3600 * 30,
+                enable_registration: true,
+            }]
+        );
+
+        // Each TLD has its own price table and minimum duration
+        let rent_price = |deps: &OwnedDeps<_, _, _>, name: &str, duration: u64| {
+            let msg = QueryMsg::RentPrice {
+                name: String::from(name),
+                duration,
+            };
+            query(deps.as_ref(), mock_env(), msg)
+                .map(|res| from_binary::<RentPriceResponse>(&res).unwrap().price)
+        };
+        let year = 24 * 3600 * 365;
+        assert_eq!(
+            rent_price(&deps, "alice", year).unwrap(),
+            Uint128::from(5_000_000u128)
+        );
+        assert_eq!(
+            rent_price(&deps, "alice.luna", year).unwrap(),
+            Uint128::from(1_000_000u128)
+        );
+        assert!(rent_price(&deps, "alice.luna", 24 * 3600 * 30).is_ok());
+        assert!(rent_price(&deps, "alice", 24 * 3600 * 30).is_err());
+        assert!(rent_price(&deps, "alice.sol", year).is_err());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetNodehash {
+                name: String::from("alice.luna"),
+            },
+        )
+        .unwrap();
+        let res: NodehashResponse = from_binary(&res).unwrap();
+        assert_eq!(res.node, namehash("alice.luna"));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetCommitment {
+                name: String::from("alice.luna"),
+                owner: String::from("alice"),
+                secret: String::from("secret"),
+                resolver: None,
+                address: None,
+            },
+        )
+        .unwrap();
+        let commitment = from_binary::<GetCommitmentResponse>(&res)
+            .unwrap()
+            .commitment;
+        let msg = ExecuteMsg::Commit { commitment };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::Register {
+            name: String::from("alice.luna"),
+            owner: String::from("alice"),
+            duration: year,
+            secret: String::from("secret"),
+            resolver: None,
+            address: None,
+            referrer: None,
+        };
+        let info = mock_info("alice", &coins(1_000_000, "uusd"));
+        set_tld(&mut deps, "creator", false).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::RegistrationDisabled {});
+        set_tld(&mut deps, "creator", true).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        // The label is registered at the registrar of the TLD
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("luna_registrar_address"),
+                msg: to_binary(&RegistrarExecuteMsg::<Extension>::Register {
+                    id: String::from(
+                        "9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501",
+                    ), // token_id of alice
+                    owner: mock_env().contract.address.to_string(),
+                    name: String::from("alice"),
+                    duration: year,
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("registry_address"),
+                msg: to_binary(&RegistryExecuteMsg::SetResolver {
+                    node: namehash("alice.luna"),
+                    resolver: None,
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        let msg = ExecuteMsg::Renew {
+            name: String::from("alice.luna"),
+            duration: year,
+            referrer: None,
+        };
+        let info = mock_info("bob", &coins(1_000_000, "uusd"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("luna_registrar_address"),
+                msg: to_binary(&RegistrarExecuteMsg::<Extension>::Renew {
+                    id: String::from(
+                        "9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501",
+                    ),
+                    duration: year,
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        let msg = ExecuteMsg::RemoveTld {
+            tld: String::from("luna"),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert!(rent_price(&deps, "alice.luna", year).is_err());
     }
 }
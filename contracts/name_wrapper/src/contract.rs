@@ -0,0 +1,191 @@
+use crate::error::ContractError;
+use crate::handler::{
+    accept_ownership, execute_cw721, get_config, propose_owner, query_data, receive_nft,
+    set_child_fuses, set_config, set_fuses, set_resolver, set_subnode_owner, set_ttl, sync_expiry,
+    unwrap, wrap,
+};
+use crate::state::{tokens, Config, CONFIG};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw721::ContractInfoResponse;
+use cw721_base::{ExecuteMsg as Cw721ExecuteMsg, QueryMsg as Cw721QueryMsg};
+use tns::name_wrapper::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let registry_address = deps.api.addr_canonicalize(msg.registry_address.as_str())?;
+    let registrar_address = deps.api.addr_canonicalize(msg.registrar_address.as_str())?;
+    let owner = deps.api.addr_canonicalize(info.sender.as_str())?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            registry_address,
+            registrar_address,
+            owner,
+        },
+    )?;
+
+    let contract = tokens();
+    contract.contract_info.save(
+        deps.storage,
+        &ContractInfoResponse {
+            name: msg.name,
+            symbol: msg.symbol,
+        },
+    )?;
+    // Tokens are only minted by wrapping
+    contract.minter.save(deps.storage, &env.contract.address)?;
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("owner", info.sender))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Wrap {
+            parent_node,
+            label,
+            owner,
+            resolver,
+        } => wrap(deps, env, info, parent_node, label, owner, resolver),
+        ExecuteMsg::ReceiveNft(msg) => receive_nft(deps, env, info, msg),
+        ExecuteMsg::Unwrap { node, new_owner } => unwrap(deps, env, info, node, new_owner),
+        ExecuteMsg::SetFuses { node, fuses } => set_fuses(deps, env, info, node, fuses),
+        ExecuteMsg::SetChildFuses {
+            parent_node,
+            label,
+            fuses,
+            expiry,
+        } => set_child_fuses(deps, env, info, parent_node, label, fuses, expiry),
+        ExecuteMsg::SetSubnodeOwner {
+            parent_node,
+            label,
+            owner,
+            fuses,
+            expiry,
+        } => set_subnode_owner(deps, env, info, parent_node, label, owner, fuses, expiry),
+        ExecuteMsg::SyncExpiry { node } => sync_expiry(deps, env, info, node),
+        ExecuteMsg::SetResolver { node, resolver } => set_resolver(deps, env, info, node, resolver),
+        ExecuteMsg::SetTTL { node, ttl } => set_ttl(deps, env, info, node, ttl),
+        ExecuteMsg::SetConfig {
+            registry_address,
+            registrar_address,
+        } => set_config(deps, env, info, registry_address, registrar_address),
+        ExecuteMsg::ProposeOwner { owner } => propose_owner(deps, env, info, owner),
+        ExecuteMsg::AcceptOwnership {} => accept_ownership(deps, env, info),
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => execute_cw721(
+            deps,
+            env,
+            info,
+            Cw721ExecuteMsg::TransferNft {
+                recipient,
+                token_id,
+            },
+        ),
+        ExecuteMsg::SendNft {
+            contract,
+            token_id,
+            msg,
+        } => execute_cw721(
+            deps,
+            env,
+            info,
+            Cw721ExecuteMsg::SendNft {
+                contract,
+                token_id,
+                msg,
+            },
+        ),
+        ExecuteMsg::Approve {
+            spender,
+            token_id,
+            expires,
+        } => execute_cw721(
+            deps,
+            env,
+            info,
+            Cw721ExecuteMsg::Approve {
+                spender,
+                token_id,
+                expires,
+            },
+        ),
+        ExecuteMsg::Revoke { spender, token_id } => execute_cw721(
+            deps,
+            env,
+            info,
+            Cw721ExecuteMsg::Revoke { spender, token_id },
+        ),
+        ExecuteMsg::ApproveAll { operator, expires } => execute_cw721(
+            deps,
+            env,
+            info,
+            Cw721ExecuteMsg::ApproveAll { operator, expires },
+        ),
+        ExecuteMsg::RevokeAll { operator } => {
+            execute_cw721(deps, env, info, Cw721ExecuteMsg::RevokeAll { operator })
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let cw721_msg = match msg {
+        QueryMsg::GetData { node } => return to_binary(&query_data(deps, env, node)?),
+        QueryMsg::GetConfig {} => return to_binary(&get_config(deps)?),
+        QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        } => Cw721QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        },
+        QueryMsg::ApprovedForAll {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => Cw721QueryMsg::ApprovedForAll {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        },
+        QueryMsg::NumTokens {} => Cw721QueryMsg::NumTokens {},
+        QueryMsg::ContractInfo {} => Cw721QueryMsg::ContractInfo {},
+        QueryMsg::NftInfo { token_id } => Cw721QueryMsg::NftInfo { token_id },
+        QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        } => Cw721QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        },
+        QueryMsg::AllTokens { start_after, limit } => {
+            Cw721QueryMsg::AllTokens { start_after, limit }
+        }
+    };
+    tokens().query(deps, env, cw721_msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    Ok(Response::default())
+}
@@ -0,0 +1,39 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+use tns::admin::AdminError;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Cw721(#[from] cw721_base::ContractError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("NotOwner: Sender is {sender}, but owner is {owner}.")]
+    NotOwner { sender: String, owner: String },
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("NotNodeOwner: Sender {sender} is not node owner of {node}.")]
+    NotNodeOwner { sender: String, node: String },
+
+    #[error("AlreadyWrapped: Node {node} is already wrapped.")]
+    AlreadyWrapped { node: String },
+
+    #[error("NotWrapped: Node {node} is not wrapped.")]
+    NotWrapped { node: String },
+
+    #[error("OperationProhibited: A fuse of node {node} prohibits this operation.")]
+    OperationProhibited { node: String },
+
+    #[error("RegistrarName: Node {node} has to be wrapped through the registrar token.")]
+    RegistrarName { node: String },
+
+    #[error("InvalidFuses: Fuses {fuses} cannot be burned.")]
+    InvalidFuses { fuses: u32 },
+}
@@ -0,0 +1,694 @@
+use crate::error::ContractError;
+use crate::state::{tokens, Extension, WrappedNode, CONFIG, WRAPPED_NODES};
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, QueryRequest,
+    Response, StdError, StdResult, Storage, WasmMsg, WasmQuery,
+};
+use cw721::{Cw721ReceiveMsg, OwnerOfResponse};
+use cw721_base::state::TokenInfo;
+use cw721_base::ExecuteMsg as Cw721ExecuteMsg;
+use tns::admin;
+use tns::name_wrapper::{
+    ConfigResponse, ReceiveMsg, WrappedDataResponse, CANNOT_BURN_FUSES, CANNOT_CREATE_SUBDOMAIN,
+    CANNOT_SET_RESOLVER, CANNOT_SET_TTL, CANNOT_TRANSFER, CANNOT_UNWRAP, OWNER_CONTROLLED_FUSES,
+    PARENT_CANNOT_CONTROL,
+};
+use tns::registrar::{
+    ConfigResponse as RegistrarConfigResponse, ExecuteMsg as RegistrarExecuteMsg,
+    Extension as RegistrarExtension, GetExpiresResponse, QueryMsg as RegistrarQueryMsg,
+};
+use tns::registry::{
+    ExecuteMsg as RegistryExecuteMsg, QueryMsg as RegistryQueryMsg, RecordResponse,
+};
+use tns::utils::keccak256;
+
+fn only_owner(deps: Deps, info: &MessageInfo) -> Result<bool, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if sender != config.owner {
+        return Err(ContractError::NotOwner {
+            sender: info.sender.to_string(),
+            owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        });
+    }
+    Ok(true)
+}
+
+/// The token owner, an approved spender or an operator of the owner can manage the node
+fn only_token_owner(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    node: &[u8],
+) -> Result<TokenInfo<Extension>, ContractError> {
+    let contract = tokens();
+    let token = contract
+        .tokens
+        .may_load(deps.storage, &hex::encode(node))?
+        .ok_or_else(|| ContractError::NotWrapped {
+            node: hex::encode(node),
+        })?;
+    if token.owner == info.sender {
+        return Ok(token);
+    }
+    if token
+        .approvals
+        .iter()
+        .any(|apr| apr.spender == info.sender && !apr.is_expired(&env.block))
+    {
+        return Ok(token);
+    }
+    let operator = contract
+        .operators
+        .may_load(deps.storage, (&token.owner, &info.sender))?;
+    match operator {
+        Some(expires) if !expires.is_expired(&env.block) => Ok(token),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+fn load_wrapped(storage: &dyn Storage, node: &[u8]) -> Result<WrappedNode, ContractError> {
+    WRAPPED_NODES
+        .may_load(storage, node.to_vec())?
+        .ok_or_else(|| ContractError::NotWrapped {
+            node: hex::encode(node),
+        })
+}
+
+fn check_fuse(
+    env: &Env,
+    node: &[u8],
+    wrapped: &WrappedNode,
+    fuse: u32,
+) -> Result<(), ContractError> {
+    if wrapped.active_fuses(env.block.time.seconds()) & fuse != 0 {
+        return Err(ContractError::OperationProhibited {
+            node: hex::encode(node),
+        });
+    }
+    Ok(())
+}
+
+/// Fuses and expiry of a child after the parent burned `fuses` on it
+fn child_fuses(
+    env: &Env,
+    parent_node: &[u8],
+    parent: &WrappedNode,
+    current: Option<&WrappedNode>,
+    fuses: u32,
+    expiry: u64,
+) -> Result<(u32, u64), ContractError> {
+    let now = env.block.time.seconds();
+    // Giving up control of a child is only meaningful if the parent cannot be unwrapped
+    if fuses & PARENT_CANNOT_CONTROL != 0 && parent.active_fuses(now) & CANNOT_UNWRAP == 0 {
+        return Err(ContractError::OperationProhibited {
+            node: hex::encode(parent_node),
+        });
+    }
+    let (current_fuses, current_expiry) = current
+        .map(|child| (child.active_fuses(now), child.expiry))
+        .unwrap_or((0, 0));
+    let fuses = current_fuses | fuses;
+    // Owner controlled fuses need both PARENT_CANNOT_CONTROL and CANNOT_UNWRAP
+    if fuses & OWNER_CONTROLLED_FUSES != 0
+        && (fuses & PARENT_CANNOT_CONTROL == 0 || fuses & CANNOT_UNWRAP == 0)
+    {
+        return Err(ContractError::InvalidFuses { fuses });
+    }
+    // Expiry cannot be reduced nor exceed the parent's
+    let expiry = expiry.max(current_expiry).min(parent.expiry);
+    Ok((fuses, expiry))
+}
+
+fn mint(deps: DepsMut, node: &[u8], owner: &str) -> Result<(), ContractError> {
+    let contract = tokens();
+    let token_id = hex::encode(node);
+    let token = TokenInfo {
+        owner: deps.api.addr_validate(owner)?,
+        approvals: vec![],
+        name: token_id.clone(),
+        description: String::new(),
+        image: None,
+        extension: None,
+    };
+    let previous = contract.tokens.may_load(deps.storage, &token_id)?;
+    if previous.is_some() {
+        // Replaced tokens drop their approvals
+        contract.tokens.remove(deps.storage, &token_id)?;
+    } else {
+        contract.increment_tokens(deps.storage)?;
+    }
+    contract.tokens.save(deps.storage, &token_id, &token)?;
+    Ok(())
+}
+
+fn burn(storage: &mut dyn Storage, node: &[u8]) -> Result<(), ContractError> {
+    let contract = tokens();
+    contract.tokens.remove(storage, &hex::encode(node))?;
+    let count = contract.token_count(storage)?;
+    contract.token_count.save(storage, &(count - 1))?;
+    Ok(())
+}
+
+fn registry_msg(deps: Deps, msg: &RegistryExecuteMsg) -> Result<CosmosMsg, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let registry_address = deps.api.addr_humanize(&config.registry_address)?;
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: registry_address.to_string(),
+        msg: to_binary(msg)?,
+        funds: vec![],
+    }))
+}
+
+fn registrar_msg(
+    deps: Deps,
+    msg: &RegistrarExecuteMsg<RegistrarExtension>,
+) -> StdResult<CosmosMsg> {
+    let config = CONFIG.load(deps.storage)?;
+    let registrar_address = deps.api.addr_humanize(&config.registrar_address)?;
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: registrar_address.to_string(),
+        msg: to_binary(msg)?,
+        funds: vec![],
+    }))
+}
+
+fn query_registrar<T: serde::de::DeserializeOwned>(
+    deps: Deps,
+    msg: &RegistrarQueryMsg,
+) -> StdResult<T> {
+    let config = CONFIG.load(deps.storage)?;
+    let registrar_address = deps.api.addr_humanize(&config.registrar_address)?;
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: registrar_address.to_string(),
+        msg: to_binary(msg)?,
+    }))
+}
+
+fn registrar_base_node(deps: Deps) -> StdResult<Vec<u8>> {
+    let config: RegistrarConfigResponse = query_registrar(deps, &RegistrarQueryMsg::GetConfig {})?;
+    Ok(config.base_node)
+}
+
+fn registrar_expires(deps: Deps, token_id: &str) -> StdResult<u64> {
+    let res: GetExpiresResponse = query_registrar(
+        deps,
+        &RegistrarQueryMsg::GetExpires {
+            id: token_id.to_string(),
+        },
+    )?;
+    Ok(res.expires)
+}
+
+pub fn wrap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    parent_node: Vec<u8>,
+    label: Vec<u8>,
+    owner: String,
+    resolver: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let registry_address = deps.api.addr_humanize(&config.registry_address)?;
+    let node = keccak256(&[parent_node.clone(), label.clone()].concat());
+    if WRAPPED_NODES
+        .may_load(deps.storage, node.clone())?
+        .is_some()
+    {
+        return Err(ContractError::AlreadyWrapped {
+            node: hex::encode(&node),
+        });
+    }
+    // The registrar token holder could take the node back with Reclaim
+    if parent_node == registrar_base_node(deps.as_ref())? {
+        return Err(ContractError::RegistrarName {
+            node: hex::encode(&node),
+        });
+    }
+
+    let is_node_owner: bool = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: registry_address.to_string(),
+        msg: to_binary(&RegistryQueryMsg::GetIsNodeOwner {
+            node: node.clone(),
+            address: info.sender.to_string(),
+        })?,
+    }))?;
+    if !is_node_owner {
+        return Err(ContractError::NotNodeOwner {
+            sender: info.sender.to_string(),
+            node: format!("{:?}", node),
+        });
+    }
+
+    // The parent owner can still take the node back in the registry, so no fuse can be burned
+    let (fuses, expiry) = (0, 0);
+    WRAPPED_NODES.save(
+        deps.storage,
+        node.clone(),
+        &WrappedNode {
+            parent_node,
+            label,
+            fuses,
+            expiry,
+        },
+    )?;
+
+    let mut messages = vec![registry_msg(
+        deps.as_ref(),
+        &RegistryExecuteMsg::SetOwner {
+            node: node.clone(),
+            owner: env.contract.address.to_string(),
+        },
+    )?];
+    if let Some(resolver) = resolver {
+        messages.push(registry_msg(
+            deps.as_ref(),
+            &RegistryExecuteMsg::SetResolver {
+                node: node.clone(),
+                resolver: Some(resolver),
+            },
+        )?);
+    }
+    mint(deps, &node, &owner)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "wrap")
+        .add_attribute("node", hex::encode(node))
+        .add_attribute("owner", owner)
+        .add_attribute("fuses", fuses.to_string())
+        .add_attribute("expiry", expiry.to_string()))
+}
+
+/// Wraps a registrar name sent by its owner, the wrapper keeps the registrar token
+/// so nobody can Reclaim the node while its fuses are burned
+pub fn receive_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let registrar_address = deps.api.addr_humanize(&config.registrar_address)?;
+    if info.sender != registrar_address {
+        return Err(ContractError::Unauthorized {});
+    }
+    let ReceiveMsg::Wrap { owner, resolver } = from_binary(&msg.msg)?;
+    let label = hex::decode(&msg.token_id)
+        .map_err(|_| StdError::generic_err(format!("Invalid token id {}", msg.token_id)))?;
+    let parent_node = registrar_base_node(deps.as_ref())?;
+    let node = keccak256(&[parent_node.clone(), label.clone()].concat());
+
+    // Fuses cannot outlive the registration, a stale wrap of an expired name is replaced
+    let expiry = registrar_expires(deps.as_ref(), &msg.token_id)?;
+    let fuses = PARENT_CANNOT_CONTROL;
+    WRAPPED_NODES.save(
+        deps.storage,
+        node.clone(),
+        &WrappedNode {
+            parent_node,
+            label,
+            fuses,
+            expiry,
+        },
+    )?;
+
+    let mut messages = vec![registrar_msg(
+        deps.as_ref(),
+        &RegistrarExecuteMsg::Reclaim {
+            id: msg.token_id,
+            owner: env.contract.address.to_string(),
+        },
+    )?];
+    if let Some(resolver) = resolver {
+        messages.push(registry_msg(
+            deps.as_ref(),
+            &RegistryExecuteMsg::SetResolver {
+                node: node.clone(),
+                resolver: Some(resolver),
+            },
+        )?);
+    }
+    mint(deps, &node, &owner)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "wrap")
+        .add_attribute("node", hex::encode(node))
+        .add_attribute("owner", owner)
+        .add_attribute("fuses", fuses.to_string())
+        .add_attribute("expiry", expiry.to_string()))
+}
+
+pub fn unwrap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    node: Vec<u8>,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    only_token_owner(deps.as_ref(), &env, &info, &node)?;
+    let wrapped = load_wrapped(deps.storage, &node)?;
+    check_fuse(&env, &node, &wrapped, CANNOT_UNWRAP)?;
+
+    WRAPPED_NODES.remove(deps.storage, node.clone());
+    burn(deps.storage, &node)?;
+    let messages = if wrapped.parent_node == registrar_base_node(deps.as_ref())? {
+        let token_id = hex::encode(&wrapped.label);
+        vec![
+            registrar_msg(
+                deps.as_ref(),
+                &RegistrarExecuteMsg::Reclaim {
+                    id: token_id.clone(),
+                    owner: new_owner.clone(),
+                },
+            )?,
+            registrar_msg(
+                deps.as_ref(),
+                &RegistrarExecuteMsg::TransferNft {
+                    recipient: new_owner.clone(),
+                    token_id,
+                },
+            )?,
+        ]
+    } else {
+        vec![registry_msg(
+            deps.as_ref(),
+            &RegistryExecuteMsg::SetOwner {
+                node: node.clone(),
+                owner: new_owner.clone(),
+            },
+        )?]
+    };
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "unwrap")
+        .add_attribute("node", hex::encode(node))
+        .add_attribute("owner", new_owner))
+}
+
+pub fn set_fuses(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    node: Vec<u8>,
+    fuses: u32,
+) -> Result<Response, ContractError> {
+    only_token_owner(deps.as_ref(), &env, &info, &node)?;
+    if fuses & !OWNER_CONTROLLED_FUSES != 0 {
+        return Err(ContractError::InvalidFuses { fuses });
+    }
+    let mut wrapped = load_wrapped(deps.storage, &node)?;
+    check_fuse(&env, &node, &wrapped, CANNOT_BURN_FUSES)?;
+
+    // The owner can only burn fuses once the parent gave up control
+    let active_fuses = wrapped.active_fuses(env.block.time.seconds());
+    if active_fuses & PARENT_CANNOT_CONTROL == 0 {
+        return Err(ContractError::OperationProhibited {
+            node: hex::encode(&node),
+        });
+    }
+    let new_fuses = active_fuses | fuses;
+    if new_fuses & CANNOT_UNWRAP == 0 {
+        return Err(ContractError::InvalidFuses { fuses });
+    }
+    wrapped.fuses = new_fuses;
+    WRAPPED_NODES.save(deps.storage, node.clone(), &wrapped)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_fuses")
+        .add_attribute("node", hex::encode(node))
+        .add_attribute("fuses", new_fuses.to_string()))
+}
+
+pub fn set_child_fuses(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    parent_node: Vec<u8>,
+    label: Vec<u8>,
+    fuses: u32,
+    expiry: u64,
+) -> Result<Response, ContractError> {
+    only_token_owner(deps.as_ref(), &env, &info, &parent_node)?;
+    let parent = load_wrapped(deps.storage, &parent_node)?;
+    let node = keccak256(&[parent_node.clone(), label].concat());
+    let mut child = load_wrapped(deps.storage, &node)?;
+    check_fuse(&env, &node, &child, PARENT_CANNOT_CONTROL)?;
+
+    let (fuses, expiry) = child_fuses(&env, &parent_node, &parent, Some(&child), fuses, expiry)?;
+    child.fuses = fuses;
+    child.expiry = expiry;
+    WRAPPED_NODES.save(deps.storage, node.clone(), &child)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_child_fuses")
+        .add_attribute("node", hex::encode(node))
+        .add_attribute("fuses", fuses.to_string())
+        .add_attribute("expiry", expiry.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn set_subnode_owner(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    parent_node: Vec<u8>,
+    label: Vec<u8>,
+    owner: String,
+    fuses: u32,
+    expiry: u64,
+) -> Result<Response, ContractError> {
+    only_token_owner(deps.as_ref(), &env, &info, &parent_node)?;
+    let parent = load_wrapped(deps.storage, &parent_node)?;
+    let node = keccak256(&[parent_node.clone(), label.clone()].concat());
+
+    let current = WRAPPED_NODES.may_load(deps.storage, node.clone())?;
+    match &current {
+        Some(child) => check_fuse(&env, &node, child, PARENT_CANNOT_CONTROL)?,
+        None => {
+            if parent.active_fuses(env.block.time.seconds()) & CANNOT_CREATE_SUBDOMAIN != 0 {
+                let config = CONFIG.load(deps.storage)?;
+                let registry_address = deps.api.addr_humanize(&config.registry_address)?;
+                let record: StdResult<RecordResponse> =
+                    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                        contract_addr: registry_address.to_string(),
+                        msg: to_binary(&RegistryQueryMsg::GetRecordByNode {
+                            node: node.clone(),
+                            include_expired: Some(true),
+                        })?,
+                    }));
+                if record.is_err() {
+                    return Err(ContractError::OperationProhibited {
+                        node: hex::encode(&parent_node),
+                    });
+                }
+            }
+        }
+    }
+
+    // Fuses burned by the parent before are dropped when the child is issued again
+    let (fuses, expiry) = child_fuses(&env, &parent_node, &parent, None, fuses, expiry)?;
+    WRAPPED_NODES.save(
+        deps.storage,
+        node.clone(),
+        &WrappedNode {
+            parent_node: parent_node.clone(),
+            label: label.clone(),
+            fuses,
+            expiry,
+        },
+    )?;
+    let message = registry_msg(
+        deps.as_ref(),
+        &RegistryExecuteMsg::SetSubnodeOwner {
+            node: parent_node,
+            label,
+            owner: env.contract.address.to_string(),
+        },
+    )?;
+    mint(deps, &node, &owner)?;
+
+    Ok(Response::new()
+        .add_message(message)
+        .add_attribute("method", "set_subnode_owner")
+        .add_attribute("node", hex::encode(node))
+        .add_attribute("owner", owner)
+        .add_attribute("fuses", fuses.to_string())
+        .add_attribute("expiry", expiry.to_string()))
+}
+
+pub fn sync_expiry(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    node: Vec<u8>,
+) -> Result<Response, ContractError> {
+    let mut wrapped = load_wrapped(deps.storage, &node)?;
+    if wrapped.parent_node != registrar_base_node(deps.as_ref())? {
+        return Err(ContractError::Unauthorized {});
+    }
+    // A name registered again by someone else is no longer held by the wrapper
+    let token_id = hex::encode(&wrapped.label);
+    let owner: OwnerOfResponse = query_registrar(
+        deps.as_ref(),
+        &RegistrarQueryMsg::OwnerOf {
+            token_id: token_id.clone(),
+            include_expired: None,
+        },
+    )?;
+    if owner.owner != env.contract.address.as_str() {
+        return Err(ContractError::NotWrapped {
+            node: hex::encode(&node),
+        });
+    }
+    wrapped.expiry = wrapped
+        .expiry
+        .max(registrar_expires(deps.as_ref(), &token_id)?);
+    WRAPPED_NODES.save(deps.storage, node.clone(), &wrapped)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "sync_expiry")
+        .add_attribute("node", hex::encode(node))
+        .add_attribute("expiry", wrapped.expiry.to_string()))
+}
+
+pub fn set_resolver(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    node: Vec<u8>,
+    resolver: Option<String>,
+) -> Result<Response, ContractError> {
+    only_token_owner(deps.as_ref(), &env, &info, &node)?;
+    let wrapped = load_wrapped(deps.storage, &node)?;
+    check_fuse(&env, &node, &wrapped, CANNOT_SET_RESOLVER)?;
+    let message = registry_msg(
+        deps.as_ref(),
+        &RegistryExecuteMsg::SetResolver {
+            node: node.clone(),
+            resolver,
+        },
+    )?;
+    Ok(Response::new()
+        .add_message(message)
+        .add_attribute("method", "set_resolver")
+        .add_attribute("node", hex::encode(node)))
+}
+
+pub fn set_ttl(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    node: Vec<u8>,
+    ttl: u64,
+) -> Result<Response, ContractError> {
+    only_token_owner(deps.as_ref(), &env, &info, &node)?;
+    let wrapped = load_wrapped(deps.storage, &node)?;
+    check_fuse(&env, &node, &wrapped, CANNOT_SET_TTL)?;
+    let message = registry_msg(
+        deps.as_ref(),
+        &RegistryExecuteMsg::SetTTL {
+            node: node.clone(),
+            ttl,
+        },
+    )?;
+    Ok(Response::new()
+        .add_message(message)
+        .add_attribute("method", "set_ttl")
+        .add_attribute("node", hex::encode(node))
+        .add_attribute("ttl", ttl.to_string()))
+}
+
+/// Runs a cw721 message, transfers are refused while CANNOT_TRANSFER is burned
+pub fn execute_cw721(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw721ExecuteMsg<Extension>,
+) -> Result<Response, ContractError> {
+    match &msg {
+        Cw721ExecuteMsg::TransferNft { token_id, .. }
+        | Cw721ExecuteMsg::SendNft { token_id, .. } => {
+            let node = hex::decode(token_id).map_err(|_| ContractError::NotWrapped {
+                node: token_id.clone(),
+            })?;
+            let wrapped = load_wrapped(deps.storage, &node)?;
+            check_fuse(&env, &node, &wrapped, CANNOT_TRANSFER)?;
+        }
+        Cw721ExecuteMsg::Mint(_) => return Err(ContractError::Unauthorized {}),
+        _ => {}
+    }
+    Ok(tokens().execute(deps, env, info, msg)?)
+}
+
+pub fn set_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    registry_address: String,
+    registrar_address: String,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.registry_address = deps.api.addr_canonicalize(registry_address.as_str())?;
+    config.registrar_address = deps.api.addr_canonicalize(registrar_address.as_str())?;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_config")
+        .add_attribute("registry_address", registry_address)
+        .add_attribute("registrar_address", registrar_address))
+}
+
+pub fn propose_owner(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    admin::propose_owner(deps.storage, deps.api, &owner)?;
+    Ok(Response::new()
+        .add_attribute("method", "propose_owner")
+        .add_attribute("pending_owner", owner))
+}
+
+pub fn accept_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let owner = admin::accept_ownership(deps.storage, deps.api, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.owner = owner;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("method", "accept_ownership")
+        .add_attribute("owner", info.sender))
+}
+
+pub fn query_data(deps: Deps, env: Env, node: Vec<u8>) -> StdResult<WrappedDataResponse> {
+    let wrapped = WRAPPED_NODES.load(deps.storage, node.clone())?;
+    let token = tokens().tokens.load(deps.storage, &hex::encode(node))?;
+    Ok(WrappedDataResponse {
+        owner: token.owner,
+        fuses: wrapped.active_fuses(env.block.time.seconds()),
+        expiry: wrapped.expiry,
+    })
+}
+
+pub fn get_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let registry_address: Addr = deps.api.addr_humanize(&config.registry_address)?;
+    let registrar_address = deps.api.addr_humanize(&config.registrar_address)?;
+    let owner = deps.api.addr_humanize(&config.owner)?;
+    let pending_owner = admin::pending_owner(deps.storage, deps.api)?;
+    Ok(ConfigResponse {
+        registry_address,
+        registrar_address,
+        owner,
+        pending_owner,
+    })
+}
@@ -0,0 +1,120 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Addr, Binary, Coin, ContractResult, Empty, OwnedDeps,
+    Querier, QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+};
+use cw721::OwnerOfResponse;
+use tns::registrar::{
+    ConfigResponse as RegistrarConfigResponse, GetExpiresResponse, QueryMsg as RegistrarQueryMsg,
+};
+use tns::registry::QueryMsg as RegistryQueryMsg;
+use tns::utils::namehash;
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+/// this uses our CustomQuerier.
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: custom_querier,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    expires: u64,
+    token_owner: String,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                if contract_addr == "registrar_address" =>
+            {
+                self.handle_registrar_query(msg)
+            }
+            QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: _,
+                msg,
+            }) => match from_binary(msg) {
+                Ok(RegistryQueryMsg::GetIsNodeOwner { node: _, address }) => {
+                    if address == "owner_address" {
+                        SystemResult::Ok(ContractResult::Ok(to_binary(&true).unwrap()))
+                    } else {
+                        SystemResult::Ok(ContractResult::Ok(to_binary(&false).unwrap()))
+                    }
+                }
+                // No record exists in the mocked registry
+                Ok(RegistryQueryMsg::GetRecordByNode { .. }) => {
+                    SystemResult::Ok(ContractResult::Err(String::from("Record not found")))
+                }
+                _ => panic!("DO NOT ENTER HERE"),
+            },
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    // The mocked registrar manages .ust and holds every token in the wrapper
+    fn handle_registrar_query(&self, msg: &Binary) -> QuerierResult {
+        let res = match from_binary(msg) {
+            Ok(RegistrarQueryMsg::GetConfig {}) => to_binary(&RegistrarConfigResponse {
+                grace_period: 0,
+                registry_address: Addr::unchecked("registry_address"),
+                owner: Addr::unchecked("registrar_owner"),
+                pending_owner: None,
+                base_node: namehash("ust"),
+                base_name: String::from("ust"),
+            }),
+            Ok(RegistrarQueryMsg::GetExpires { .. }) => to_binary(&GetExpiresResponse {
+                expires: self.expires,
+            }),
+            Ok(RegistrarQueryMsg::OwnerOf { .. }) => to_binary(&OwnerOfResponse {
+                owner: self.token_owner.clone(),
+                approvals: vec![],
+            }),
+            _ => panic!("DO NOT ENTER HERE"),
+        };
+        SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            expires: 0,
+            token_owner: String::from(MOCK_CONTRACT_ADDR),
+        }
+    }
+
+    pub fn with_expires(&mut self, expires: u64) {
+        self.expires = expires;
+    }
+
+    pub fn with_token_owner(&mut self, owner: &str) {
+        self.token_owner = owner.to_string();
+    }
+}
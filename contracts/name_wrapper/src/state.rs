@@ -0,0 +1,42 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Empty};
+use cw721_base::Cw721Contract;
+use cw_storage_plus::{Item, Map};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub registry_address: CanonicalAddr,
+    pub registrar_address: CanonicalAddr,
+    pub owner: CanonicalAddr,
+}
+
+pub const CONFIG: Item<Config> = Item::new("CONFIG");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WrappedNode {
+    pub parent_node: Vec<u8>,
+    pub label: Vec<u8>,
+    pub fuses: u32,
+    pub expiry: u64,
+}
+
+impl WrappedNode {
+    /// Fuses stop applying once the node is expired
+    pub fn active_fuses(&self, now: u64) -> u32 {
+        if self.expiry < now {
+            return 0;
+        }
+        self.fuses
+    }
+}
+
+pub const WRAPPED_NODES: Map<Vec<u8>, WrappedNode> = Map::new("WRAPPED_NODES");
+
+pub type Extension = Option<Empty>;
+
+// Wrapped tokens use the cw721-base storage, the token id is the hex encoded node
+pub fn tokens() -> Cw721Contract<'static, Extension, Empty> {
+    Cw721Contract::default()
+}
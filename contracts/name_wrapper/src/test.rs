@@ -0,0 +1,726 @@
+#[cfg(test)]
+mod tests {
+    use crate::contract::{execute, instantiate, query};
+    use crate::error::ContractError;
+    use crate::mock_querier::mock_dependencies;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{coins, from_binary, to_binary, Addr, CosmosMsg, OwnedDeps, WasmMsg};
+    use cw0::Expiration;
+    use cw721::{Cw721ReceiveMsg, NumTokensResponse, OwnerOfResponse};
+    use tns::admin::AdminError;
+    use tns::name_wrapper::{
+        ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg, WrappedDataResponse,
+        CANNOT_CREATE_SUBDOMAIN, CANNOT_SET_RESOLVER, CANNOT_TRANSFER, CANNOT_UNWRAP,
+        PARENT_CANNOT_CONTROL,
+    };
+    use tns::registrar::{ExecuteMsg as RegistrarExecuteMsg, Extension};
+    use tns::registry::ExecuteMsg as RegistryExecuteMsg;
+    use tns::utils::{get_label_from_name, namehash};
+
+    use crate::mock_querier::WasmMockQuerier;
+
+    // Registration of ourapp.ust ends a year after mock_env
+    const REGISTRATION_TIME: u64 = 31536000;
+
+    fn instantiate_contract() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        deps.querier
+            .with_expires(mock_env().block.time.seconds() + REGISTRATION_TIME);
+        let msg = InstantiateMsg {
+            registry_address: String::from("registry_address"),
+            registrar_address: String::from("registrar_address"),
+            name: String::from("Wrapped TNS"),
+            symbol: String::from("WTNS"),
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        deps
+    }
+
+    fn receive_nft_msg(label: &str, owner: &str, resolver: Option<String>) -> ExecuteMsg {
+        ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: String::from(owner),
+            token_id: hex::encode(get_label_from_name(&String::from(label))),
+            msg: to_binary(&ReceiveMsg::Wrap {
+                owner: String::from(owner),
+                resolver,
+            })
+            .unwrap(),
+        })
+    }
+
+    fn setup_contract() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+        let mut deps = instantiate_contract();
+
+        // Wrap ourapp.ust for app_owner by sending its registrar token
+        let msg = receive_nft_msg("ourapp", "app_owner", None);
+        let info = mock_info("registrar_address", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        deps
+    }
+
+    fn query_data(
+        deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+        name: &str,
+        seconds: u64,
+    ) -> WrappedDataResponse {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(seconds);
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::GetData {
+                node: namehash(name),
+            },
+        )
+        .unwrap();
+        from_binary(&res).unwrap()
+    }
+
+    #[test]
+    fn test_wrap_and_unwrap() {
+        let mut deps = instantiate_contract();
+
+        // Registrar names have to be wrapped with their token
+        let msg = ExecuteMsg::Wrap {
+            parent_node: namehash("ust"),
+            label: get_label_from_name(&String::from("ourapp")),
+            owner: String::from("app_owner"),
+            resolver: None,
+        };
+        let info = mock_info("owner_address", &coins(0, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::RegistrarName {
+                node: hex::encode(namehash("ourapp.ust"))
+            }
+        );
+
+        // Only the registry owner can wrap
+        let msg = ExecuteMsg::Wrap {
+            parent_node: namehash("ourapp.ust"),
+            label: get_label_from_name(&String::from("app")),
+            owner: String::from("app_owner"),
+            resolver: Some(String::from("resolver_address")),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &coins(0, "uusd")),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotNodeOwner {
+                sender: String::from("anyone"),
+                node: format!("{:?}", namehash("app.ourapp.ust"))
+            }
+        );
+
+        let info = mock_info("owner_address", &coins(0, "uusd"));
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|message| message.msg)
+                .collect::<Vec<CosmosMsg>>(),
+            vec![
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: String::from("registry_address"),
+                    msg: to_binary(&RegistryExecuteMsg::SetOwner {
+                        node: namehash("app.ourapp.ust"),
+                        owner: String::from(MOCK_CONTRACT_ADDR),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: String::from("registry_address"),
+                    msg: to_binary(&RegistryExecuteMsg::SetResolver {
+                        node: namehash("app.ourapp.ust"),
+                        resolver: Some(String::from("resolver_address")),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ]
+        );
+
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AlreadyWrapped {
+                node: hex::encode(namehash("app.ourapp.ust"))
+            }
+        );
+
+        // The unwrapped parent can still take the node back, so it gets no fuse
+        assert_eq!(
+            query_data(&deps, "app.ourapp.ust", 0),
+            WrappedDataResponse {
+                owner: Addr::unchecked("app_owner"),
+                fuses: 0,
+                expiry: 0,
+            }
+        );
+        let msg = ExecuteMsg::SetFuses {
+            node: namehash("app.ourapp.ust"),
+            fuses: CANNOT_UNWRAP,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("app_owner", &coins(0, "uusd")),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OperationProhibited {
+                node: hex::encode(namehash("app.ourapp.ust"))
+            }
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::OwnerOf {
+                token_id: hex::encode(namehash("app.ourapp.ust")),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+        let res: OwnerOfResponse = from_binary(&res).unwrap();
+        assert_eq!(res.owner, "app_owner");
+
+        // Unwrap
+        let msg = ExecuteMsg::Unwrap {
+            node: namehash("app.ourapp.ust"),
+            new_owner: String::from("app_owner"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &coins(0, "uusd")),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("app_owner", &coins(0, "uusd")),
+            msg,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("registry_address"),
+                msg: to_binary(&RegistryExecuteMsg::SetOwner {
+                    node: namehash("app.ourapp.ust"),
+                    owner: String::from("app_owner"),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {}).unwrap();
+        let res: NumTokensResponse = from_binary(&res).unwrap();
+        assert_eq!(res.count, 0);
+    }
+
+    #[test]
+    fn test_wrap_registrar_name() {
+        let mut deps = instantiate_contract();
+        let expires = mock_env().block.time.seconds() + REGISTRATION_TIME;
+        let token_id = hex::encode(get_label_from_name(&String::from("ourapp")));
+
+        // Only the registrar can hand over its tokens
+        let msg = receive_nft_msg(
+            "ourapp",
+            "app_owner",
+            Some(String::from("resolver_address")),
+        );
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // The wrapper takes the registry node, the registrar token stays with it
+        let info = mock_info("registrar_address", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|message| message.msg)
+                .collect::<Vec<CosmosMsg>>(),
+            vec![
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: String::from("registrar_address"),
+                    msg: to_binary(&RegistrarExecuteMsg::<Extension>::Reclaim {
+                        id: token_id.clone(),
+                        owner: String::from(MOCK_CONTRACT_ADDR),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: String::from("registry_address"),
+                    msg: to_binary(&RegistryExecuteMsg::SetResolver {
+                        node: namehash("ourapp.ust"),
+                        resolver: Some(String::from("resolver_address")),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ]
+        );
+
+        // Fuses last until the registration expires
+        assert_eq!(
+            query_data(&deps, "ourapp.ust", 0),
+            WrappedDataResponse {
+                owner: Addr::unchecked("app_owner"),
+                fuses: PARENT_CANNOT_CONTROL,
+                expiry: expires,
+            }
+        );
+        let app_info = mock_info("app_owner", &[]);
+        let msg = ExecuteMsg::SetFuses {
+            node: namehash("ourapp.ust"),
+            fuses: CANNOT_UNWRAP | CANNOT_SET_RESOLVER,
+        };
+        execute(deps.as_mut(), mock_env(), app_info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetResolver {
+            node: namehash("ourapp.ust"),
+            resolver: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), app_info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OperationProhibited {
+                node: hex::encode(namehash("ourapp.ust"))
+            }
+        );
+
+        // The registrar token cannot be taken back to Reclaim the node while fuses apply
+        let msg = ExecuteMsg::Unwrap {
+            node: namehash("ourapp.ust"),
+            new_owner: String::from("app_owner"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), app_info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OperationProhibited {
+                node: hex::encode(namehash("ourapp.ust"))
+            }
+        );
+
+        // Once the registration expired, unwrapping returns the registrar token
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(REGISTRATION_TIME + 1);
+        assert_eq!(
+            query_data(&deps, "ourapp.ust", REGISTRATION_TIME + 1).fuses,
+            0
+        );
+        let res = execute(deps.as_mut(), env, app_info, msg).unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|message| message.msg)
+                .collect::<Vec<CosmosMsg>>(),
+            vec![
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: String::from("registrar_address"),
+                    msg: to_binary(&RegistrarExecuteMsg::<Extension>::Reclaim {
+                        id: token_id.clone(),
+                        owner: String::from("app_owner"),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: String::from("registrar_address"),
+                    msg: to_binary(&RegistrarExecuteMsg::<Extension>::TransferNft {
+                        recipient: String::from("app_owner"),
+                        token_id,
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sync_expiry() {
+        let mut deps = setup_contract();
+        let app_info = mock_info("app_owner", &[]);
+        let expires = mock_env().block.time.seconds() + REGISTRATION_TIME;
+
+        // Child fuses cannot outlive the parent
+        let msg = ExecuteMsg::SetFuses {
+            node: namehash("ourapp.ust"),
+            fuses: CANNOT_UNWRAP,
+        };
+        execute(deps.as_mut(), mock_env(), app_info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetSubnodeOwner {
+            parent_node: namehash("ourapp.ust"),
+            label: get_label_from_name(&String::from("alice")),
+            owner: String::from("alice_owner"),
+            fuses: PARENT_CANNOT_CONTROL,
+            expiry: u64::MAX,
+        };
+        execute(deps.as_mut(), mock_env(), app_info, msg).unwrap();
+        assert_eq!(query_data(&deps, "alice.ourapp.ust", 0).expiry, expires);
+
+        // Only registrar names follow the registration
+        let msg = ExecuteMsg::SyncExpiry {
+            node: namehash("alice.ourapp.ust"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // Anyone can sync a renewed name
+        deps.querier.with_expires(expires + REGISTRATION_TIME);
+        let msg = ExecuteMsg::SyncExpiry {
+            node: namehash("ourapp.ust"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            query_data(&deps, "ourapp.ust", 0).expiry,
+            expires + REGISTRATION_TIME
+        );
+
+        // A name registered again by someone else is not synced
+        deps.querier.with_token_owner("new_owner");
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotWrapped {
+                node: hex::encode(namehash("ourapp.ust"))
+            }
+        );
+    }
+
+    #[test]
+    fn test_unwrap_permissions() {
+        let mut deps = setup_contract();
+        let app_info = mock_info("app_owner", &[]);
+        let token_id = hex::encode(namehash("ourapp.ust"));
+        let msg = ExecuteMsg::Unwrap {
+            node: namehash("ourapp.ust"),
+            new_owner: String::from("spender"),
+        };
+
+        // Expired approvals do not count
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(10);
+        let approve_msg = ExecuteMsg::Approve {
+            spender: String::from("spender"),
+            token_id: token_id.clone(),
+            expires: Some(Expiration::AtTime(mock_env().block.time.plus_seconds(5))),
+        };
+        execute(deps.as_mut(), mock_env(), app_info.clone(), approve_msg).unwrap();
+        let err = execute(deps.as_mut(), env, mock_info("spender", &[]), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // Operators of the owner can unwrap
+        let approve_msg = ExecuteMsg::ApproveAll {
+            operator: String::from("operator"),
+            expires: None,
+        };
+        execute(deps.as_mut(), mock_env(), app_info.clone(), approve_msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), msg).unwrap();
+
+        // Approved spenders can unwrap
+        let msg = receive_nft_msg("ourapp", "app_owner", None);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("registrar_address", &[]),
+            msg,
+        )
+        .unwrap();
+        let approve_msg = ExecuteMsg::Approve {
+            spender: String::from("spender"),
+            token_id,
+            expires: None,
+        };
+        execute(deps.as_mut(), mock_env(), app_info, approve_msg).unwrap();
+        let msg = ExecuteMsg::Unwrap {
+            node: namehash("ourapp.ust"),
+            new_owner: String::from("spender"),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("spender", &[]), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {}).unwrap();
+        let res: NumTokensResponse = from_binary(&res).unwrap();
+        assert_eq!(res.count, 0);
+    }
+
+    #[test]
+    fn test_fuses() {
+        let mut deps = setup_contract();
+        let app_info = mock_info("app_owner", &coins(0, "uusd"));
+        let expiry = mock_env().block.time.seconds() + 1000;
+        let child_fuses = PARENT_CANNOT_CONTROL | CANNOT_UNWRAP | CANNOT_TRANSFER;
+
+        // Parent has to burn CANNOT_UNWRAP before giving up control of children
+        let msg = ExecuteMsg::SetSubnodeOwner {
+            parent_node: namehash("ourapp.ust"),
+            label: get_label_from_name(&String::from("alice")),
+            owner: String::from("alice_owner"),
+            fuses: child_fuses,
+            expiry,
+        };
+        let err = execute(deps.as_mut(), mock_env(), app_info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OperationProhibited {
+                node: hex::encode(namehash("ourapp.ust"))
+            }
+        );
+
+        // CANNOT_UNWRAP has to come first
+        let set_fuses_msg = ExecuteMsg::SetFuses {
+            node: namehash("ourapp.ust"),
+            fuses: CANNOT_TRANSFER,
+        };
+        let err = execute(deps.as_mut(), mock_env(), app_info.clone(), set_fuses_msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidFuses {
+                fuses: CANNOT_TRANSFER
+            }
+        );
+        let set_fuses_msg = ExecuteMsg::SetFuses {
+            node: namehash("ourapp.ust"),
+            fuses: CANNOT_UNWRAP,
+        };
+        execute(deps.as_mut(), mock_env(), app_info.clone(), set_fuses_msg).unwrap();
+
+        let res = execute(deps.as_mut(), mock_env(), app_info.clone(), msg.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("registry_address"),
+                msg: to_binary(&RegistryExecuteMsg::SetSubnodeOwner {
+                    node: namehash("ourapp.ust"),
+                    label: get_label_from_name(&String::from("alice")),
+                    owner: String::from(MOCK_CONTRACT_ADDR),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert_eq!(
+            query_data(&deps, "alice.ourapp.ust", 0),
+            WrappedDataResponse {
+                owner: Addr::unchecked("alice_owner"),
+                fuses: child_fuses,
+                expiry,
+            }
+        );
+
+        // Parent cannot take the name back nor change its fuses
+        let err = execute(deps.as_mut(), mock_env(), app_info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OperationProhibited {
+                node: hex::encode(namehash("alice.ourapp.ust"))
+            }
+        );
+        let msg = ExecuteMsg::SetChildFuses {
+            parent_node: namehash("ourapp.ust"),
+            label: get_label_from_name(&String::from("alice")),
+            fuses: 0,
+            expiry: expiry + 1000,
+        };
+        let err = execute(deps.as_mut(), mock_env(), app_info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OperationProhibited {
+                node: hex::encode(namehash("alice.ourapp.ust"))
+            }
+        );
+
+        // Burned fuses bind the child owner too
+        let alice_info = mock_info("alice_owner", &coins(0, "uusd"));
+        let msg = ExecuteMsg::TransferNft {
+            recipient: String::from("bob"),
+            token_id: hex::encode(namehash("alice.ourapp.ust")),
+        };
+        let err = execute(deps.as_mut(), mock_env(), alice_info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OperationProhibited {
+                node: hex::encode(namehash("alice.ourapp.ust"))
+            }
+        );
+        let msg = ExecuteMsg::Unwrap {
+            node: namehash("alice.ourapp.ust"),
+            new_owner: String::from("alice_owner"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), alice_info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OperationProhibited {
+                node: hex::encode(namehash("alice.ourapp.ust"))
+            }
+        );
+
+        // Fuses stop applying after expiry and the parent gets control back
+        assert_eq!(query_data(&deps, "alice.ourapp.ust", 1001).fuses, 0);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1001);
+        let msg = ExecuteMsg::SetSubnodeOwner {
+            parent_node: namehash("ourapp.ust"),
+            label: get_label_from_name(&String::from("alice")),
+            owner: String::from("new_owner"),
+            fuses: 0,
+            expiry: 0,
+        };
+        execute(deps.as_mut(), env, app_info, msg).unwrap();
+        assert_eq!(
+            query_data(&deps, "alice.ourapp.ust", 1001).owner,
+            Addr::unchecked("new_owner")
+        );
+    }
+
+    #[test]
+    fn test_cannot_create_subdomain() {
+        let mut deps = setup_contract();
+        let app_info = mock_info("app_owner", &coins(0, "uusd"));
+
+        // Subdomains can be created until the fuse is burned
+        let msg = ExecuteMsg::SetSubnodeOwner {
+            parent_node: namehash("ourapp.ust"),
+            label: get_label_from_name(&String::from("alice")),
+            owner: String::from("alice_owner"),
+            fuses: 0,
+            expiry: 0,
+        };
+        execute(deps.as_mut(), mock_env(), app_info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::SetFuses {
+            node: namehash("ourapp.ust"),
+            fuses: CANNOT_UNWRAP | CANNOT_CREATE_SUBDOMAIN,
+        };
+        execute(deps.as_mut(), mock_env(), app_info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::SetSubnodeOwner {
+            parent_node: namehash("ourapp.ust"),
+            label: get_label_from_name(&String::from("bob")),
+            owner: String::from("bob_owner"),
+            fuses: 0,
+            expiry: 0,
+        };
+        let err = execute(deps.as_mut(), mock_env(), app_info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::OperationProhibited {
+                node: hex::encode(namehash("ourapp.ust"))
+            }
+        );
+
+        // Existing subdomains can still be reissued
+        let msg = ExecuteMsg::SetSubnodeOwner {
+            parent_node: namehash("ourapp.ust"),
+            label: get_label_from_name(&String::from("alice")),
+            owner: String::from("bob_owner"),
+            fuses: 0,
+            expiry: 0,
+        };
+        execute(deps.as_mut(), mock_env(), app_info, msg).unwrap();
+        assert_eq!(
+            query_data(&deps, "alice.ourapp.ust", 0).owner,
+            Addr::unchecked("bob_owner")
+        );
+    }
+
+    #[test]
+    fn test_transfer_ownership() {
+        let mut deps = instantiate_contract();
+
+        let msg = ExecuteMsg::ProposeOwner {
+            owner: String::from("new_owner"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("not_owner", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // Nothing changes until the proposed owner accepts
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            ConfigResponse {
+                registry_address: Addr::unchecked("registry_address"),
+                registrar_address: Addr::unchecked("registrar_address"),
+                owner: Addr::unchecked("creator"),
+                pending_owner: Some(Addr::unchecked("new_owner")),
+            }
+        );
+
+        let msg = ExecuteMsg::AcceptOwnership {};
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("not_owner", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Admin(AdminError::NotPendingOwner {
+                sender: String::from("not_owner")
+            })
+        );
+        execute(deps.as_mut(), mock_env(), mock_info("new_owner", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::SetConfig {
+            registry_address: String::from("new_registry_address"),
+            registrar_address: String::from("registrar_address"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+        execute(deps.as_mut(), mock_env(), mock_info("new_owner", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            ConfigResponse {
+                registry_address: Addr::unchecked("new_registry_address"),
+                registrar_address: Addr::unchecked("registrar_address"),
+                owner: Addr::unchecked("new_owner"),
+                pending_owner: None,
+            }
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use cosmwasm_std::StdError;
 use thiserror::Error;
+use tns::admin::AdminError;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -15,6 +16,9 @@ pub enum ContractError {
         owner: String
     },
 
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
     #[error("NotController: Sender {sender} is not controller.")]
     NotController {
         sender: String,
@@ -75,9 +75,16 @@ where
             ExecuteMsg::RemoveController { address } => {
                 self.remove_controller(deps, env, info, address)
             }
-            ExecuteMsg::SetConfig { grace_period, registry_address, owner } => {
-                self.set_config(deps, env, info, grace_period, registry_address, owner)
+            ExecuteMsg::SetConfig { grace_period, registry_address } => {
+                self.set_config(deps, env, info, grace_period, registry_address)
             }
+            ExecuteMsg::ProposeOwner { owner } => self.propose_owner(deps, env, info, owner),
+            ExecuteMsg::SetRole { role, address } => {
+                self.set_role(deps, env, info, role, address)
+            }
+
+            // Only pending owner
+            ExecuteMsg::AcceptOwnership {} => self.accept_ownership(deps, env, info),
 
             // Only controller
             ExecuteMsg::Register {
@@ -90,6 +97,7 @@ where
 
             // User
             ExecuteMsg::Reclaim { id, owner } => self.reclaim(deps, env, info, id, owner),
+            ExecuteMsg::SyncExpiries { ids } => self.sync_expiries(deps, env, info, ids),
 
             // Base CW721
             ExecuteMsg::Mint(msg) => self.mint(deps, env, info, msg),
@@ -124,6 +132,7 @@ where
     T: Serialize + DeserializeOwned + Clone,
     C: CustomMsg,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn _mint(
         &self,
         deps: DepsMut,
@@ -139,10 +148,10 @@ where
         let token = TokenInfo::<T> {
             owner: deps.api.addr_validate(&owner)?,
             approvals: vec![],
-            name: name,
+            name,
             description: description.unwrap_or_default(),
-            image: image,
-            extension: extension,
+            image,
+            extension,
         };
         self.tokens
             .update(deps.storage, &token_id, |old| match old {
@@ -173,7 +182,7 @@ where
             });
         }
 
-        return self._mint(
+        self._mint(
             deps,
             env,
             info,
@@ -183,7 +192,7 @@ where
             msg.image,
             msg.extension,
             msg.token_id,
-        );
+        )
     }
 }
 
@@ -330,13 +339,13 @@ where
         recipient: &str,
         token_id: &str,
     ) -> Result<TokenInfo<T>, ContractError> {
-        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        let mut token = self.tokens.load(deps.storage, token_id)?;
         // ensure we have permissions
         self.check_can_send(deps.as_ref(), env, info, &token)?;
         // set owner and remove existing approvals
         token.owner = deps.api.addr_validate(recipient)?;
         token.approvals = vec![];
-        self.tokens.save(deps.storage, &token_id, &token)?;
+        self.tokens.save(deps.storage, token_id, &token)?;
         Ok(token)
     }
 
@@ -352,17 +361,13 @@ where
         add: bool,
         expires: Option<Expiration>,
     ) -> Result<TokenInfo<T>, ContractError> {
-        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        let mut token = self.tokens.load(deps.storage, token_id)?;
         // ensure we have permissions
         self.check_can_approve(deps.as_ref(), env, info, &token)?;
 
         // update the approval list (remove any for the same spender before adding)
         let spender_addr = deps.api.addr_validate(spender)?;
-        token.approvals = token
-            .approvals
-            .into_iter()
-            .filter(|apr| apr.spender != spender_addr)
-            .collect();
+        token.approvals.retain(|apr| apr.spender != spender_addr);
 
         // only difference between approve and revoke
         if add {
@@ -378,7 +383,7 @@ where
             token.approvals.push(approval);
         }
 
-        self.tokens.save(deps.storage, &token_id, &token)?;
+        self.tokens.save(deps.storage, token_id, &token)?;
 
         Ok(token)
     }
@@ -1,12 +1,21 @@
 use crate::error::ContractError;
 use crate::state::{Cw721Contract, CONFIG, CONTROLLERS, EXPIRIES};
 use crate::utils::decode_node_string_to_bytes;
-use cosmwasm_std::{to_binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, WasmMsg};
+use cosmwasm_std::{
+    to_binary, ContractResult, CosmosMsg, Deps, DepsMut, Env, MessageInfo, QueryRequest, Reply,
+    Response, StdError, SubMsg, WasmMsg, WasmQuery,
+};
 use cw721::CustomMsg;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use tns::registry::ExecuteMsg as RegistryExecuteMsg;
-use tns::utils::{generate_image, get_label_from_name, get_token_id_from_label};
+use tns::admin::{self, Role};
+use tns::registry::{
+    ExecuteMsg as RegistryExecuteMsg, QueryMsg as RegistryQueryMsg, RecordResponse,
+};
+use tns::resolver::ExecuteMsg as ResolverExecuteMsg;
+use tns::utils::{generate_image, get_label_from_name, get_token_id_from_label, keccak256};
+
+pub const CLEAR_RECORDS_REPLY_ID: u64 = 1;
 
 fn only_owner(deps: Deps, info: MessageInfo) -> Result<bool, ContractError> {
     let config = CONFIG.load(deps.storage)?;
@@ -20,6 +29,12 @@ fn only_owner(deps: Deps, info: MessageInfo) -> Result<bool, ContractError> {
     Ok(true)
 }
 
+fn only_role(deps: Deps, info: &MessageInfo, role: Role) -> Result<bool, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    admin::only_role(deps.storage, deps.api, &config.owner, &info.sender, role)?;
+    Ok(true)
+}
+
 fn only_controller(deps: Deps, info: &MessageInfo) -> Result<bool, ContractError> {
     let is_controller = CONTROLLERS
         .may_load(deps.storage, info.sender.clone())?
@@ -46,6 +61,7 @@ where
     T: Serialize + DeserializeOwned + Clone + Default,
     C: CustomMsg,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn register(
         &self,
         deps: DepsMut,
@@ -56,7 +72,7 @@ where
         name: String,
         duration: u64,
     ) -> Result<Response<C>, ContractError> {
-        let mut messages: Vec<CosmosMsg<C>> = vec![];
+        let mut messages: Vec<SubMsg<C>> = vec![];
         only_controller(deps.as_ref(), &info)?;
         validate_id(id.clone(), name.clone())?;
         if !self
@@ -71,11 +87,63 @@ where
 
         let expire = env.block.time.seconds() + duration;
         EXPIRIES.save(deps.storage, id.clone(), &expire)?;
+        let label = decode_node_string_to_bytes(id.clone()).unwrap();
+        // The registry stops reporting the owner once the name expires
+        let set_subnode_expiry_msg = SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: registry_address.to_string(),
+            msg: to_binary(&RegistryExecuteMsg::SetSubnodeExpiry {
+                node: config.base_node.clone(),
+                label: label.clone(),
+                expires: expire,
+            })?,
+            funds: vec![],
+        }));
         let token = self.tokens.may_load(deps.storage, &id)?;
+        let mut previous_record: Option<RecordResponse> = None;
+        let subnode = keccak256(&[config.base_node.clone(), label.clone()].concat());
         if let Some(_token) = token {
             let token_id = id.clone();
             self.decrease_tokens(deps.storage)?;
             self.tokens.remove(deps.storage, &token_id)?;
+
+            // Re-registration of an expired name, clear the previous owner's records
+            previous_record = deps
+                .querier
+                .query(&QueryRequest::Wasm(WasmQuery::Smart {
+                    contract_addr: registry_address.to_string(),
+                    msg: to_binary(&RegistryQueryMsg::GetRecordByNode {
+                        node: subnode.clone(),
+                        include_expired: Some(true),
+                    })?,
+                }))
+                .ok();
+        }
+        match previous_record {
+            Some(record) => {
+                // Take the node back while it is still expired, the registry then leaves the
+                // subnodes of the previous owner expired. The resolver only lets the node owner
+                // clear, so the expiry is set before clearing.
+                messages.push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: registry_address.to_string(),
+                    msg: to_binary(&RegistryExecuteMsg::SetSubnodeOwner {
+                        node: config.base_node.clone(),
+                        label: label.clone(),
+                        owner: env.contract.address.to_string(),
+                    })?,
+                    funds: vec![],
+                })));
+                messages.push(set_subnode_expiry_msg);
+                // The previous owner picked the resolver, a failing one must not block the name
+                messages.push(SubMsg::reply_on_error(
+                    CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: record.resolver.to_string(),
+                        msg: to_binary(&ResolverExecuteMsg::ClearRecords { node: subnode })?,
+                        funds: vec![],
+                    }),
+                    CLEAR_RECORDS_REPLY_ID,
+                ));
+            }
+            None => messages.push(set_subnode_expiry_msg),
         }
 
         let mint_response = self._mint(
@@ -93,7 +161,6 @@ where
             id.clone(),
         )?;
 
-        let label = decode_node_string_to_bytes(id.clone()).unwrap();
         let set_subnode_owner_registry_msg: CosmosMsg<C> = CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: registry_address.to_string(),
             msg: to_binary(&RegistryExecuteMsg::SetSubnodeOwner {
@@ -103,16 +170,35 @@ where
             })?,
             funds: vec![],
         });
-        messages.push(set_subnode_owner_registry_msg);
+        messages.push(SubMsg::new(set_subnode_owner_registry_msg));
         Ok(Response::<C>::new()
             .add_attributes(mint_response.attributes)
-            .add_messages(messages)
+            .add_submessages(messages)
             .add_attribute("method", "register")
             .add_attribute("id", id)
             .add_attribute("owner", owner)
             .add_attribute("duration", duration.to_string()))
     }
 
+    /// Only failed `ClearRecords` reply, the registration goes through without clearing
+    pub fn reply(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        msg: Reply,
+    ) -> Result<Response<C>, ContractError> {
+        if msg.id != CLEAR_RECORDS_REPLY_ID {
+            return Err(ContractError::Std(StdError::generic_err("unknown reply id")));
+        }
+        let error = match msg.result {
+            ContractResult::Err(error) => error,
+            ContractResult::Ok(_) => String::new(),
+        };
+        Ok(Response::new()
+            .add_attribute("method", "clear_records_failed")
+            .add_attribute("error", error))
+    }
+
     pub fn renew(
         &self,
         deps: DepsMut,
@@ -129,12 +215,64 @@ where
         }
         let new_expiry = expiry + duration;
         EXPIRIES.save(deps.storage, id.clone(), &new_expiry)?;
+        let registry_address = deps.api.addr_humanize(&config.registry_address)?;
+        let set_subnode_expiry_registry_msg: CosmosMsg<C> = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: registry_address.to_string(),
+            msg: to_binary(&RegistryExecuteMsg::SetSubnodeExpiry {
+                node: config.base_node,
+                label: decode_node_string_to_bytes(id.clone()).unwrap(),
+                expires: new_expiry,
+            })?,
+            funds: vec![],
+        });
         Ok(Response::new()
+            .add_message(set_subnode_expiry_registry_msg)
             .add_attribute("method", "renew")
             .add_attribute("id", id)
             .add_attribute("duration", duration.to_string()))
     }
 
+    // Anyone can call it, the registry only gets the expiry kept by the registrar
+    pub fn sync_expiries(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        ids: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let registry_address = deps.api.addr_humanize(&config.registry_address)?;
+        let mut messages: Vec<CosmosMsg<C>> = vec![];
+        // Unknown ids are reported instead of failing the names synced with them
+        let mut skipped: Vec<String> = vec![];
+        for id in ids {
+            let expires = EXPIRIES.may_load(deps.storage, id.clone())?;
+            let label = decode_node_string_to_bytes(id.clone()).ok();
+            let (expires, label) = match (expires, label) {
+                (Some(expires), Some(label)) => (expires, label),
+                _ => {
+                    skipped.push(id);
+                    continue;
+                }
+            };
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: registry_address.to_string(),
+                msg: to_binary(&RegistryExecuteMsg::SetSubnodeExpiry {
+                    node: config.base_node.clone(),
+                    label,
+                    expires,
+                })?,
+                funds: vec![],
+            }));
+        }
+        let count = messages.len();
+        Ok(Response::new()
+            .add_messages(messages)
+            .add_attribute("method", "sync_expiries")
+            .add_attribute("count", count.to_string())
+            .add_attribute("skipped", skipped.join(",")))
+    }
+
     pub fn add_controller(
         &self,
         deps: DepsMut,
@@ -142,7 +280,7 @@ where
         info: MessageInfo,
         address: String,
     ) -> Result<Response<C>, ContractError> {
-        only_owner(deps.as_ref(), info)?;
+        only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
         let controller_addr = deps.api.addr_validate(address.as_str())?;
         CONTROLLERS.save(deps.storage, controller_addr, &true)?;
         Ok(Response::new()
@@ -157,7 +295,7 @@ where
         info: MessageInfo,
         address: String,
     ) -> Result<Response<C>, ContractError> {
-        only_owner(deps.as_ref(), info)?;
+        only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
         let controller_addr = deps.api.addr_validate(address.as_str())?;
         CONTROLLERS.save(deps.storage, controller_addr, &false)?;
         Ok(Response::new()
@@ -172,24 +310,75 @@ where
         info: MessageInfo,
         grace_period: u64,
         registry_address: String,
-        owner: String,
     ) -> Result<Response<C>, ContractError> {
-        only_owner(deps.as_ref(), info)?;
+        only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
         let mut config = CONFIG.load(deps.storage)?;
 
         let registry_address = deps.api.addr_canonicalize(registry_address.as_str())?;
-        let owner = deps.api.addr_canonicalize(owner.as_str())?;
+        // Names are recorded in the registry, only the owner can repoint it
+        if registry_address != config.registry_address {
+            only_owner(deps.as_ref(), info)?;
+        }
 
         config.grace_period = grace_period;
         config.registry_address = registry_address.clone();
-        config.owner = owner.clone();
 
         CONFIG.save(deps.storage, &config)?;
         Ok(Response::new()
             .add_attribute("method", "set_config")
             .add_attribute("grace_period", grace_period.to_string())
-            .add_attribute("registry_address", registry_address.clone().to_string())
-            .add_attribute("owner", owner.clone().to_string()))
+            .add_attribute("registry_address", registry_address.clone().to_string()))
+    }
+
+    pub fn propose_owner(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        owner: String,
+    ) -> Result<Response<C>, ContractError> {
+        only_owner(deps.as_ref(), info)?;
+        admin::propose_owner(deps.storage, deps.api, &owner)?;
+        Ok(Response::new()
+            .add_attribute("method", "propose_owner")
+            .add_attribute("pending_owner", owner))
+    }
+
+    pub fn accept_ownership(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<C>, ContractError> {
+        let owner = admin::accept_ownership(deps.storage, deps.api, &info.sender)?;
+        let mut config = CONFIG.load(deps.storage)?;
+        config.owner = owner;
+        CONFIG.save(deps.storage, &config)?;
+        Ok(Response::new()
+            .add_attribute("method", "accept_ownership")
+            .add_attribute("owner", info.sender))
+    }
+
+    pub fn set_role(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        role: Role,
+        address: Option<String>,
+    ) -> Result<Response<C>, ContractError> {
+        only_owner(deps.as_ref(), info)?;
+        admin::set_role(
+            deps.storage,
+            deps.api,
+            &[Role::RegistrationAdmin],
+            &role,
+            address.as_deref(),
+        )?;
+        Ok(Response::new()
+            .add_attribute("method", "set_role")
+            .add_attribute("role", role.to_string())
+            .add_attribute("address", address.unwrap_or_default()))
     }
 
     pub fn reclaim(
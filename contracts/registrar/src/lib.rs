@@ -12,12 +12,15 @@ pub use tns::registrar::{
 };
 mod test;
 
+#[cfg(test)]
+mod mock_querier;
+
 #[cfg(not(feature = "library"))]
 pub mod entry {
     use super::*;
 
     use cosmwasm_std::entry_point;
-    use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+    use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult};
 
     // This makes a conscious choice on the various generics used by the contract
     #[entry_point]
@@ -42,6 +45,12 @@ pub mod entry {
         tract.execute(deps, env, info, msg)
     }
 
+    #[entry_point]
+    pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+        let tract = Cw721Contract::<Extension, Empty>::default();
+        tract.reply(deps, env, msg)
+    }
+
     #[entry_point]
     pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         let tract = Cw721Contract::<Extension, Empty>::default();
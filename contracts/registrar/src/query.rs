@@ -14,6 +14,7 @@ use tns::registrar::{
     GetBaseNodeResponse, GetExpiresResponse, GetRegistryResponse, IsAvailableResponse,
     GetGracePeriodResponse, ConfigResponse
 };
+use tns::admin::{self, Role, RolesResponse};
 use tns::registrar::{MinterResponse, QueryMsg};
 
 const DEFAULT_LIMIT: u32 = 10;
@@ -116,7 +117,7 @@ where
         limit: Option<u32>,
     ) -> StdResult<TokensResponse> {
         let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-        let start = start_after.map(|s| Bound::exclusive(s));
+        let start = start_after.map(Bound::exclusive);
 
         let tokens: StdResult<Vec<String>> = self
             .tokens
@@ -200,15 +201,21 @@ where
         let config = CONFIG.load(deps.storage)?;
         let owner = deps.api.addr_humanize(&config.owner)?;
         let registry_address = deps.api.addr_humanize(&config.registry_address)?;
+        let pending_owner = admin::pending_owner(deps.storage, deps.api)?;
         Ok(ConfigResponse {
             grace_period: config.grace_period,
-            registry_address: registry_address,
-            owner: owner,
+            registry_address,
+            owner,
+            pending_owner,
             base_node: config.base_node,
             base_name: config.base_name,
         })
     }
 
+    pub fn roles(&self, deps: Deps) -> StdResult<RolesResponse> {
+        admin::get_roles(deps.storage, deps.api, &[Role::RegistrationAdmin])
+    }
+
     pub fn query(&self, deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         match msg {
             QueryMsg::IsAvailable { id } => to_binary(&self.is_available(deps, &env, id)?),
@@ -217,6 +224,7 @@ where
             QueryMsg::GetRegistry {} => to_binary(&self.get_registry(deps)?),
             QueryMsg::GetGracePeriod {} => to_binary(&self.get_grace_period(deps)?),
             QueryMsg::GetConfig {} => to_binary(&self.get_config(deps)?),
+            QueryMsg::Roles {} => to_binary(&self.roles(deps)?),
 
             QueryMsg::Minter {} => to_binary(&self.minter(deps)?),
             QueryMsg::ContractInfo {} => to_binary(&self.contract_info(deps)?),
@@ -1,21 +1,26 @@
 #![cfg(test)]
 use crate::entry;
 use crate::error::ContractError;
+use crate::handler::CLEAR_RECORDS_REPLY_ID;
+use crate::mock_querier::mock_dependencies;
 use crate::state::Cw721Contract;
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::testing::{mock_env, mock_info};
 use cosmwasm_std::{
-    coins, from_binary, to_binary, Addr, CosmosMsg, DepsMut, Empty, Response, WasmMsg,
+    attr, coins, from_binary, to_binary, Addr, ContractResult, CosmosMsg, DepsMut, Empty, Reply,
+    ReplyOn, Response, SubMsg, WasmMsg,
 };
 use cw0::Expiration;
-use cw721;
 use cw721::{
     Approval, ApprovedForAllResponse, ContractInfoResponse, Cw721Query, Cw721ReceiveMsg,
     NftInfoResponse, OwnerOfResponse,
 };
+use tns::admin::{AdminError, Role, RoleAddress, RolesResponse};
 use tns::registrar::{
     ConfigResponse, ExecuteMsg, Extension, InstantiateMsg, IsAvailableResponse, MintMsg, QueryMsg,
 };
 use tns::registry::ExecuteMsg as RegistryExecuteMsg;
+use tns::resolver::ExecuteMsg as ResolverExecuteMsg;
+use tns::utils::keccak256;
 
 const CONTRACT_NAME: &str = "Magic Power";
 const SYMBOL: &str = "MGK";
@@ -737,7 +742,7 @@ fn test_is_available() {
     let res = entry::query(deps.as_ref(), mock_env(), QueryMsg::IsAvailable { id }).unwrap();
 
     let value: IsAvailableResponse = from_binary(&res).unwrap();
-    assert_eq!(value.available, true);
+    assert!(value.available);
 }
 
 #[test]
@@ -773,10 +778,7 @@ fn test_register() {
         duration: 100,
         name: "alice".to_string(),
     };
-    assert_eq!(
-        entry::execute(deps.as_mut(), mock_env(), info, msg).is_err(),
-        true
-    );
+    assert!(entry::execute(deps.as_mut(), mock_env(), info, msg).is_err());
 
     let info = mock_info("controller_address", &coins(0, "uusd"));
     let msg = ExecuteMsg::Register {
@@ -810,7 +812,19 @@ fn test_register() {
     let nft_info_response: NftInfoResponse<Extension> = from_binary(&nft_info_query).unwrap();
     assert_eq!(nft_info_response.name, "alice.ust");
 
-    assert_eq!(res.messages.len(), 1); // set subnode owner
+    assert_eq!(res.messages.len(), 2); // set subnode expiry, set subnode owner
+
+    let set_subnode_expiry_registry_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: registry_address.clone(),
+        msg: to_binary(&RegistryExecuteMsg::SetSubnodeExpiry {
+            node: hex::decode(UST_BASE_NODE).unwrap(),
+            label: hex::decode(id.clone()).unwrap(),
+            expires: mock_env().block.time.seconds() + 100,
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    assert_eq!(res.messages[0].msg, set_subnode_expiry_registry_msg);
 
     let set_subnode_owner_registry_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: registry_address.clone(),
@@ -822,7 +836,243 @@ fn test_register() {
         .unwrap(),
         funds: vec![],
     });
-    assert_eq!(res.messages[0].msg, set_subnode_owner_registry_msg);
+    assert_eq!(res.messages[1].msg, set_subnode_owner_registry_msg);
+
+    // Renewals extend the expiry in the registry as well
+    let info = mock_info("controller_address", &coins(0, "uusd"));
+    let msg = ExecuteMsg::Renew {
+        id: id.clone(),
+        duration: 50,
+    };
+    let res = entry::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: registry_address.clone(),
+            msg: to_binary(&RegistryExecuteMsg::SetSubnodeExpiry {
+                node: hex::decode(UST_BASE_NODE).unwrap(),
+                label: hex::decode(id.clone()).unwrap(),
+                expires: mock_env().block.time.seconds() + 150,
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    // Expiries of names registered before the registry tracked them can be copied by anyone
+    let info = mock_info("anyone", &coins(0, "uusd"));
+    let msg = ExecuteMsg::SyncExpiries {
+        ids: vec![id.clone()],
+    };
+    let res = entry::execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: registry_address.clone(),
+            msg: to_binary(&RegistryExecuteMsg::SetSubnodeExpiry {
+                node: hex::decode(UST_BASE_NODE).unwrap(),
+                label: hex::decode(id.clone()).unwrap(),
+                expires: mock_env().block.time.seconds() + 150,
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    // Unknown ids are skipped, the known ones are still synced
+    let msg = ExecuteMsg::SyncExpiries {
+        ids: vec![String::from("00"), id.clone(), String::from("zz")],
+    };
+    let res = entry::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("method", "sync_expiries"),
+            attr("count", "1"),
+            attr("skipped", "00,zz"),
+        ]
+    );
+}
+
+#[test]
+fn test_register_expired_clears_records() {
+    let registry_address = String::from("registry_address");
+
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        base_node: UST_BASE_NODE.to_string(),
+        base_name: BASE_NAME.to_string(),
+        registry_address: registry_address.clone(),
+        grace_period: Some(10),
+    };
+
+    let mut deps = mock_dependencies(&[]);
+    let info = mock_info("creator", &coins(0, "uusd"));
+    let _res = entry::instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let id = String::from("9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501");
+    let info = mock_info("creator", &coins(0, "uusd"));
+    let msg = ExecuteMsg::AddController {
+        address: String::from("controller_address"),
+    };
+    entry::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("controller_address", &coins(0, "uusd"));
+    let msg = ExecuteMsg::Register {
+        id: id.clone(),
+        owner: String::from("alice"),
+        duration: 100,
+        name: "alice".to_string(),
+    };
+    let res = entry::execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    // Fresh registration has nothing to clear
+    assert_eq!(res.messages.len(), 2);
+
+    // Register again after expiry and grace period
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(111);
+    let msg = ExecuteMsg::Register {
+        id: id.clone(),
+        owner: String::from("bob"),
+        duration: 100,
+        name: "alice".to_string(),
+    };
+    let res = entry::execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(res.messages.len(), 4);
+
+    let label = hex::decode(id.clone()).unwrap();
+    let subnode = keccak256(&[hex::decode(UST_BASE_NODE).unwrap(), label.clone()].concat());
+    // The registrar takes the expired node back first, so the registry starts a new generation
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: registry_address.clone(),
+            msg: to_binary(&RegistryExecuteMsg::SetSubnodeOwner {
+                node: hex::decode(UST_BASE_NODE).unwrap(),
+                label: label.clone(),
+                owner: env.contract.address.to_string(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    // The new expiry is set before clearing, the registrar owns the node again
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: registry_address.clone(),
+            msg: to_binary(&RegistryExecuteMsg::SetSubnodeExpiry {
+                node: hex::decode(UST_BASE_NODE).unwrap(),
+                label: label.clone(),
+                expires: env.block.time.seconds() + 100,
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    assert_eq!(
+        res.messages[2],
+        SubMsg::reply_on_error(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("resolver_address"),
+                msg: to_binary(&ResolverExecuteMsg::ClearRecords { node: subnode }).unwrap(),
+                funds: vec![],
+            }),
+            CLEAR_RECORDS_REPLY_ID,
+        )
+    );
+    assert_eq!(
+        res.messages[3].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: registry_address,
+            msg: to_binary(&RegistryExecuteMsg::SetSubnodeOwner {
+                node: hex::decode(UST_BASE_NODE).unwrap(),
+                label,
+                owner: String::from("bob"),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn test_register_expired_with_broken_resolver() {
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        base_node: UST_BASE_NODE.to_string(),
+        base_name: BASE_NAME.to_string(),
+        registry_address: String::from("registry_address"),
+        grace_period: Some(10),
+    };
+    let mut deps = mock_dependencies(&[]);
+    let info = mock_info("creator", &coins(0, "uusd"));
+    entry::instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let msg = ExecuteMsg::AddController {
+        address: String::from("controller_address"),
+    };
+    entry::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let id = String::from("9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501");
+    let info = mock_info("controller_address", &coins(0, "uusd"));
+    let msg = ExecuteMsg::Register {
+        id: id.clone(),
+        owner: String::from("alice"),
+        duration: 100,
+        name: "alice".to_string(),
+    };
+    entry::execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // The previous owner pointed the name at a contract that is not a resolver
+    deps.querier.with_resolver("not_a_resolver");
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(111);
+    let msg = ExecuteMsg::Register {
+        id: id.clone(),
+        owner: String::from("bob"),
+        duration: 100,
+        name: "alice".to_string(),
+    };
+    let res = entry::execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let subnode = keccak256(
+        &[
+            hex::decode(UST_BASE_NODE).unwrap(),
+            hex::decode(&id).unwrap(),
+        ]
+        .concat(),
+    );
+    assert_eq!(
+        res.messages[2],
+        SubMsg::reply_on_error(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("not_a_resolver"),
+                msg: to_binary(&ResolverExecuteMsg::ClearRecords { node: subnode }).unwrap(),
+                funds: vec![],
+            }),
+            CLEAR_RECORDS_REPLY_ID,
+        )
+    );
+    // Every other message must succeed
+    assert!(res
+        .messages
+        .iter()
+        .enumerate()
+        .all(|(i, msg)| i == 2 || msg.reply_on == ReplyOn::Never));
+
+    // Its failure is swallowed and the registration stands
+    let reply = Reply {
+        id: CLEAR_RECORDS_REPLY_ID,
+        result: ContractResult::Err(String::from("unknown variant `clear_records`")),
+    };
+    let res = entry::reply(deps.as_mut(), env.clone(), reply).unwrap();
+    assert_eq!(res.messages.len(), 0);
+    let contract = Cw721Contract::<Extension, Empty>::default();
+    let owner = contract.owner_of(deps.as_ref(), env, id, false).unwrap();
+    assert_eq!(owner.owner, "bob");
 }
 
 #[test]
@@ -894,10 +1144,7 @@ fn test_reclaim() {
         id: id.clone(),
         owner: "bob".to_string(),
     };
-    assert_eq!(
-        entry::execute(deps.as_mut(), mock_env(), info, msg).is_err(),
-        true
-    );
+    assert!(entry::execute(deps.as_mut(), mock_env(), info, msg).is_err());
 
     // Reclaim alice.ust from controller should error
     let info = mock_info("controller", &coins(0, "uusd"));
@@ -905,10 +1152,7 @@ fn test_reclaim() {
         id: id.clone(),
         owner: "controller".to_string(),
     };
-    assert_eq!(
-        entry::execute(deps.as_mut(), mock_env(), info, msg).is_err(),
-        true
-    );
+    assert!(entry::execute(deps.as_mut(), mock_env(), info, msg).is_err());
 
     // Transfer NFT from alice to bob
     let info = mock_info("alice", &coins(0, "uusd"));
@@ -937,10 +1181,7 @@ fn test_reclaim() {
         id: id.clone(),
         owner: "controller".to_string(),
     };
-    assert_eq!(
-        entry::execute(deps.as_mut(), mock_env(), info, msg).is_err(),
-        true
-    );
+    assert!(entry::execute(deps.as_mut(), mock_env(), info, msg).is_err());
 
     // Reclaim alice.ust from bob
     let info = mock_info("bob", &coins(0, "uusd"));
@@ -979,15 +1220,14 @@ fn test_set_config() {
     let msg = ExecuteMsg::SetConfig {
         grace_period: 3592000,
         registry_address: String::from("new_registry_address"),
-        owner: String::from("new_owner"),
     };
     let err = entry::execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
     assert_eq!(
         err,
-        ContractError::NotOwner {
+        ContractError::Admin(AdminError::MissingRole {
             sender: String::from("not_creator"),
-            owner: String::from("creator")
-        }
+            role: String::from("registration_admin")
+        })
     );
 
     // Set new grace period
@@ -995,7 +1235,6 @@ fn test_set_config() {
     let msg = ExecuteMsg::SetConfig {
         grace_period: 3592000,
         registry_address: String::from("new_registry_address"),
-        owner: String::from("new_owner"),
     };
     entry::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -1007,5 +1246,174 @@ fn test_set_config() {
         get_config.registry_address,
         Addr::unchecked(String::from("new_registry_address"))
     );
-    assert_eq!(get_config.owner, Addr::unchecked(String::from("new_owner")));
+    assert_eq!(get_config.owner, Addr::unchecked(String::from("creator")));
+}
+
+#[test]
+fn test_transfer_ownership() {
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        base_node: UST_BASE_NODE.to_string(),
+        base_name: BASE_NAME.to_string(),
+        registry_address: String::from("registry_address"),
+        grace_period: None,
+    };
+    let mut deps = mock_dependencies(&[]);
+    let info = mock_info("creator", &coins(0, "uusd"));
+    entry::instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::ProposeOwner {
+        owner: String::from("new_owner"),
+    };
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::NotOwner { .. }));
+    entry::execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let get_config_query = entry::query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+    let get_config: ConfigResponse = from_binary(&get_config_query).unwrap();
+    assert_eq!(get_config.owner, Addr::unchecked("creator"));
+    assert_eq!(get_config.pending_owner, Some(Addr::unchecked("new_owner")));
+
+    let msg = ExecuteMsg::AcceptOwnership {};
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Admin(AdminError::NotPendingOwner {
+            sender: String::from("alice")
+        })
+    );
+    entry::execute(deps.as_mut(), mock_env(), mock_info("new_owner", &[]), msg).unwrap();
+
+    let get_config_query = entry::query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+    let get_config: ConfigResponse = from_binary(&get_config_query).unwrap();
+    assert_eq!(get_config.owner, Addr::unchecked("new_owner"));
+    assert_eq!(get_config.pending_owner, None);
+
+    // Previous owner lost control
+    let msg = ExecuteMsg::AddController {
+        address: String::from("controller"),
+    };
+    let err = entry::execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Admin(AdminError::MissingRole { .. })
+    ));
+}
+
+#[test]
+fn test_registration_admin() {
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        base_node: UST_BASE_NODE.to_string(),
+        base_name: BASE_NAME.to_string(),
+        registry_address: String::from("registry_address"),
+        grace_period: None,
+    };
+    let mut deps = mock_dependencies(&[]);
+    let info = mock_info("creator", &coins(0, "uusd"));
+    entry::instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // Roles the registrar does not use are refused
+    let msg = ExecuteMsg::SetRole {
+        role: Role::Treasury,
+        address: Some(String::from("admin")),
+    };
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::NotOwner { .. }));
+    let err = entry::execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Admin(AdminError::UnsupportedRole {
+            role: String::from("treasury")
+        })
+    );
+
+    let controller_msg = ExecuteMsg::AddController {
+        address: String::from("controller"),
+    };
+    let info_admin = mock_info("admin", &[]);
+    let err = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        info_admin.clone(),
+        controller_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Admin(AdminError::MissingRole {
+            sender: String::from("admin"),
+            role: String::from("registration_admin")
+        })
+    );
+
+    let msg = ExecuteMsg::SetRole {
+        role: Role::RegistrationAdmin,
+        address: Some(String::from("admin")),
+    };
+    entry::execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let roles_query = entry::query(deps.as_ref(), mock_env(), QueryMsg::Roles {}).unwrap();
+    let roles: RolesResponse = from_binary(&roles_query).unwrap();
+    assert_eq!(
+        roles.roles,
+        vec![RoleAddress {
+            role: Role::RegistrationAdmin,
+            address: Addr::unchecked("admin"),
+        }]
+    );
+    entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        info_admin.clone(),
+        controller_msg.clone(),
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::SetConfig {
+        grace_period: 3592000,
+        registry_address: String::from("registry_address"),
+    };
+    entry::execute(deps.as_mut(), mock_env(), info_admin.clone(), msg).unwrap();
+    let config_query = entry::query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+    let config: ConfigResponse = from_binary(&config_query).unwrap();
+    assert_eq!(config.grace_period, 3592000);
+
+    // The registry stays with the owner
+    let msg = ExecuteMsg::SetConfig {
+        grace_period: 3592000,
+        registry_address: String::from("new_registry_address"),
+    };
+    let err = entry::execute(deps.as_mut(), mock_env(), info_admin.clone(), msg).unwrap_err();
+    assert!(matches!(err, ContractError::NotOwner { .. }));
+
+    let msg = ExecuteMsg::SetRole {
+        role: Role::RegistrationAdmin,
+        address: None,
+    };
+    entry::execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let err = entry::execute(deps.as_mut(), mock_env(), info_admin, controller_msg).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Admin(AdminError::MissingRole { .. })
+    ));
 }
@@ -1,8 +1,9 @@
 use crate::error::ContractError;
 use crate::handler::{
-    get_config, is_node_owner, query_is_approved_for_all, query_record, query_record_by_node,
-    set_approval_for_all, set_config, set_owner, set_record, set_resolver, set_subnode_owner,
-    set_ttl,
+    accept_ownership, get_config, get_roles, is_node_owner, propose_owner, query_expires,
+    query_is_approved_for_all, query_record, query_record_by_node, set_approval_for_all,
+    set_config, set_owner, set_record, set_resolver, set_role, set_subnode_expiry,
+    set_subnode_owner, set_ttl,
 };
 use crate::state::{Config, Record, CONFIG, RECORDS};
 #[cfg(not(feature = "library"))]
@@ -36,7 +37,12 @@ pub fn instantiate(
             ttl: 0,
         },
     )?;
-    Ok(Response::default())
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("node", hex::encode(vec![0u8; 32]))
+        .add_attribute("owner", info.sender)
+        .add_attribute("resolver", env.contract.address)
+        .add_attribute("ttl", "0"))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -56,6 +62,11 @@ pub fn execute(
         ExecuteMsg::SetSubnodeOwner { node, label, owner } => {
             set_subnode_owner(deps, env, info, node, label, owner)
         }
+        ExecuteMsg::SetSubnodeExpiry {
+            node,
+            label,
+            expires,
+        } => set_subnode_expiry(deps, env, info, node, label, expires),
         ExecuteMsg::SetOwner { node, owner } => set_owner(deps, env, info, node, owner),
         ExecuteMsg::SetResolver { node, resolver } => set_resolver(deps, env, info, node, resolver),
         ExecuteMsg::SetTTL { node, ttl } => set_ttl(deps, env, info, node, ttl),
@@ -64,10 +75,10 @@ pub fn execute(
             operator,
             approved,
         } => set_approval_for_all(deps, env, info, node, operator, approved),
-        ExecuteMsg::SetConfig {
-            default_resolver,
-            owner,
-        } => set_config(deps, env, info, default_resolver, owner),
+        ExecuteMsg::SetConfig { default_resolver } => set_config(deps, env, info, default_resolver),
+        ExecuteMsg::ProposeOwner { owner } => propose_owner(deps, env, info, owner),
+        ExecuteMsg::AcceptOwnership {} => accept_ownership(deps, env, info),
+        ExecuteMsg::SetRole { role, address } => set_role(deps, env, info, role, address),
     }
 }
 
@@ -75,14 +86,19 @@ pub fn execute(
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetRecord { name } => to_binary(&query_record(deps, env, name)?),
-        QueryMsg::GetRecordByNode { node } => to_binary(&query_record_by_node(deps, env, node)?),
+        QueryMsg::GetRecordByNode {
+            node,
+            include_expired,
+        } => to_binary(&query_record_by_node(deps, env, node, include_expired)?),
         QueryMsg::GetIsNodeOwner { node, address } => {
             to_binary(&is_node_owner(deps, env, node, address)?)
         }
         QueryMsg::GetIsApprovedForAll { owner, operator } => {
             to_binary(&query_is_approved_for_all(deps, env, owner, operator)?)
         }
+        QueryMsg::GetExpires { node } => to_binary(&query_expires(deps, env, node)?),
         QueryMsg::GetConfig {} => to_binary(&get_config(deps)?),
+        QueryMsg::Roles {} => to_binary(&get_roles(deps)?),
     }
 }
 
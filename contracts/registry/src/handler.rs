@@ -1,7 +1,12 @@
 use crate::error::ContractError;
-use crate::state::{Record, OPERATORS, RECORDS, CONFIG};
-use cosmwasm_std::{Deps, DepsMut, Env, MessageInfo, Response, StdResult};
-use tns::registry::{OperatorResponse, RecordResponse, ConfigResponse};
+use crate::state::{
+    Record, CONFIG, EXPIRIES, GENERATIONS, OPERATORS, PARENTS, PARENT_GENERATIONS, RECORDS,
+};
+use cosmwasm_std::{
+    attr, Attribute, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Storage,
+};
+use tns::admin::{self, Role, RolesResponse};
+use tns::registry::{ConfigResponse, GetExpiresResponse, OperatorResponse, RecordResponse};
 use tns::utils::keccak256;
 use tns::utils::namehash;
 
@@ -17,12 +22,62 @@ fn only_owner(deps: Deps, info: &MessageInfo) -> Result<bool, ContractError> {
     Ok(true)
 }
 
-fn only_authorized(deps: &DepsMut, info: &MessageInfo, node: &Vec<u8>) -> Result<bool, ContractError> {
-    let record_option = RECORDS.may_load(deps.storage, node.to_vec())?;
+fn only_role(deps: Deps, info: &MessageInfo, role: Role) -> Result<bool, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    admin::only_role(deps.storage, deps.api, &config.owner, &info.sender, role)?;
+    Ok(true)
+}
+
+// A node is expired once it or any of its parents is, or once a parent is given out again
+fn is_expired(storage: &dyn Storage, env: &Env, node: &[u8]) -> StdResult<bool> {
+    let mut node = node.to_vec();
+    loop {
+        let expires = EXPIRIES.may_load(storage, node.clone())?;
+        if matches!(expires, Some(expires) if expires < env.block.time.seconds()) {
+            return Ok(true);
+        }
+        let parent = match PARENTS.may_load(storage, node.clone())? {
+            Some(parent) => parent,
+            None => return Ok(false),
+        };
+        let linked_generation = PARENT_GENERATIONS.may_load(storage, node)?.unwrap_or(0);
+        if GENERATIONS.may_load(storage, parent.clone())?.unwrap_or(0) != linked_generation {
+            return Ok(true);
+        }
+        node = parent;
+    }
+}
+
+// An expired node given out again gets a new generation, the subnodes linked so far stay expired.
+// Renewing the expiry alone keeps the generation, names can be renewed after they expire.
+fn renew_generation(storage: &mut dyn Storage, env: &Env, node: &[u8]) -> StdResult<()> {
+    if is_expired(storage, env, node)? {
+        GENERATIONS.update(storage, node.to_vec(), |generation| -> StdResult<u64> {
+            Ok(generation.unwrap_or(0) + 1)
+        })?;
+    }
+    Ok(())
+}
+
+// Expired nodes are not owned by anyone, only their parent can take them back
+fn load_active_record(storage: &dyn Storage, env: &Env, node: &[u8]) -> StdResult<Option<Record>> {
+    if is_expired(storage, env, node)? {
+        return Ok(None);
+    }
+    RECORDS.may_load(storage, node.to_vec())
+}
+
+fn only_authorized(
+    deps: &DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    node: &[u8],
+) -> Result<bool, ContractError> {
+    let record_option = load_active_record(deps.storage, env, node)?;
     let canonical_sender = deps.api.addr_canonicalize(info.sender.as_str())?;
     if let Some(record) = record_option {
         if record.owner == canonical_sender {
-            return Ok(true)
+            return Ok(true);
         }
 
         let operator_option = OPERATORS.may_load(
@@ -35,10 +90,10 @@ fn only_authorized(deps: &DepsMut, info: &MessageInfo, node: &Vec<u8>) -> Result
             }
         }
     }
-    return Err(ContractError::NotNodeOwner {
+    Err(ContractError::NotNodeOwner {
         sender: info.sender.to_string(),
-        node: format!("{:?}", node.clone()),
-    });
+        node: format!("{:?}", node),
+    })
 }
 
 pub fn set_subnode_owner(
@@ -49,66 +104,107 @@ pub fn set_subnode_owner(
     label: Vec<u8>,
     owner: String,
 ) -> Result<Response, ContractError> {
-    only_authorized(&deps, &info, &node)?;
-    let subnode = keccak256(&[node, label].concat());
-    _set_owner(deps, env, subnode, owner)?;
-    Ok(Response::new())
+    only_authorized(&deps, &env, &info, &node)?;
+    let subnode = keccak256(&[node.clone(), label.clone()].concat());
+    renew_generation(deps.storage, &env, &subnode)?;
+    let generation = GENERATIONS
+        .may_load(deps.storage, node.clone())?
+        .unwrap_or(0);
+    PARENTS.save(deps.storage, subnode.clone(), &node)?;
+    PARENT_GENERATIONS.save(deps.storage, subnode.clone(), &generation)?;
+    let attributes = _set_owner(deps, env, subnode, owner)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_subnode_owner")
+        .add_attribute("parent_node", hex::encode(node))
+        .add_attribute("label", hex::encode(label))
+        .add_attributes(attributes))
+}
+
+pub fn set_subnode_expiry(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    node: Vec<u8>,
+    label: Vec<u8>,
+    expires: u64,
+) -> Result<Response, ContractError> {
+    only_authorized(&deps, &env, &info, &node)?;
+    let subnode = keccak256(&[node.clone(), label.clone()].concat());
+    EXPIRIES.save(deps.storage, subnode.clone(), &expires)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_subnode_expiry")
+        .add_attribute("parent_node", hex::encode(node))
+        .add_attribute("label", hex::encode(label))
+        .add_attribute("node", hex::encode(subnode))
+        .add_attribute("expires", expires.to_string()))
 }
 
+// Returns the attributes describing the record change
 fn _set_owner(
     deps: DepsMut,
     _env: Env,
     node: Vec<u8>,
     owner: String,
-) -> Result<Response, ContractError> {
+) -> Result<Vec<Attribute>, ContractError> {
     let record_option = RECORDS.may_load(deps.storage, node.clone())?;
     let canonical_owner = deps.api.addr_canonicalize(owner.as_str())?;
     if let Some(mut record) = record_option {
         record.owner = canonical_owner;
         RECORDS.save(deps.storage, node.clone(), &record)?;
-        return Ok(Response::default());
+        return Ok(vec![attr("node", hex::encode(node)), attr("owner", owner)]);
     }
 
     let config = CONFIG.load(deps.storage)?;
+    let default_resolver = deps.api.addr_humanize(&config.default_resolver)?;
 
     RECORDS.save(
         deps.storage,
-        node,
+        node.clone(),
         &Record {
             owner: canonical_owner,
             resolver: config.default_resolver,
             ttl: 0,
         },
     )?;
-    return Ok(Response::default());
+    // New records also get the default resolver and ttl
+    Ok(vec![
+        attr("node", hex::encode(node)),
+        attr("owner", owner),
+        attr("resolver", default_resolver),
+        attr("ttl", "0"),
+    ])
 }
 
 pub fn set_record(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     node: Vec<u8>,
     owner: String,
     resolver: Option<String>,
     ttl: u64,
 ) -> Result<Response, ContractError> {
-    only_authorized(&deps, &info, &node)?;
-    let owner = deps.api.addr_canonicalize(owner.as_str())?;
+    only_authorized(&deps, &env, &info, &node)?;
+    let canonical_owner = deps.api.addr_canonicalize(owner.as_str())?;
     let config = CONFIG.load(deps.storage)?;
     let default_resolver = deps.api.addr_humanize(&config.default_resolver)?;
-    let canonical_resolver = deps.api.addr_canonicalize(
-        resolver.unwrap_or(default_resolver.to_string()).as_str(),
-    )?;
+    let resolver = resolver.unwrap_or(default_resolver.to_string());
+    let canonical_resolver = deps.api.addr_canonicalize(resolver.as_str())?;
     RECORDS.save(
         deps.storage,
-        node,
+        node.clone(),
         &Record {
-            owner,
+            owner: canonical_owner,
             resolver: canonical_resolver,
             ttl,
         },
     )?;
-    Ok(Response::default())
+    Ok(Response::new()
+        .add_attribute("method", "set_record")
+        .add_attribute("node", hex::encode(node))
+        .add_attribute("owner", owner)
+        .add_attribute("resolver", resolver)
+        .add_attribute("ttl", ttl.to_string()))
 }
 
 pub fn set_owner(
@@ -118,46 +214,53 @@ pub fn set_owner(
     node: Vec<u8>,
     owner: String,
 ) -> Result<Response, ContractError> {
-    only_authorized(&deps, &info, &node)?;
-    _set_owner(deps, env, node, owner)?;
-    Ok(Response::default())
+    only_authorized(&deps, &env, &info, &node)?;
+    let attributes = _set_owner(deps, env, node, owner)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_owner")
+        .add_attributes(attributes))
 }
 
 pub fn set_ttl(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     node: Vec<u8>,
     ttl: u64,
 ) -> Result<Response, ContractError> {
-    only_authorized(&deps, &info, &node)?;
+    only_authorized(&deps, &env, &info, &node)?;
     let mut record = RECORDS.load(deps.storage, node.clone())?;
     record.ttl = ttl;
     RECORDS.save(deps.storage, node.clone(), &record)?;
-    Ok(Response::default())
+    Ok(Response::new()
+        .add_attribute("method", "set_ttl")
+        .add_attribute("node", hex::encode(node))
+        .add_attribute("ttl", ttl.to_string()))
 }
 
 pub fn set_resolver(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     node: Vec<u8>,
     resolver: Option<String>,
 ) -> Result<Response, ContractError> {
-    only_authorized(&deps, &info, &node)?;
+    only_authorized(&deps, &env, &info, &node)?;
     let mut record = RECORDS.load(deps.storage, node.clone())?;
     let config = CONFIG.load(deps.storage)?;
     let default_resolver = deps.api.addr_humanize(&config.default_resolver)?;
-    let canonical_resolver = deps.api.addr_canonicalize(
-        resolver.unwrap_or(default_resolver.to_string()).as_str(),
-    )?;
+    let resolver = resolver.unwrap_or(default_resolver.to_string());
+    let canonical_resolver = deps.api.addr_canonicalize(resolver.as_str())?;
     record.resolver = canonical_resolver;
     RECORDS.save(deps.storage, node.clone(), &record)?;
-    Ok(Response::default())
+    Ok(Response::new()
+        .add_attribute("method", "set_resolver")
+        .add_attribute("node", hex::encode(node))
+        .add_attribute("resolver", resolver))
 }
 
-pub fn is_node_owner(deps: Deps, _env: Env, node: Vec<u8>, address: String) -> StdResult<bool> {
-    let record_option = RECORDS.may_load(deps.storage, node.to_vec())?;
+pub fn is_node_owner(deps: Deps, env: Env, node: Vec<u8>, address: String) -> StdResult<bool> {
+    let record_option = load_active_record(deps.storage, &env, &node)?;
     let canonical_sender = deps.api.addr_canonicalize(&address)?;
     if let Some(record) = record_option {
         if record.owner == canonical_sender {
@@ -172,10 +275,21 @@ pub fn is_node_owner(deps: Deps, _env: Env, node: Vec<u8>, address: String) -> S
             return Ok(operator);
         }
     }
-    return Ok(false);
+    Ok(false)
 }
 
-pub fn query_record_by_node(deps: Deps, _env: Env, node: Vec<u8>) -> StdResult<RecordResponse> {
+pub fn query_record_by_node(
+    deps: Deps,
+    env: Env,
+    node: Vec<u8>,
+    include_expired: Option<bool>,
+) -> StdResult<RecordResponse> {
+    if !include_expired.unwrap_or(false) && is_expired(deps.storage, &env, &node)? {
+        return Err(StdError::generic_err(format!(
+            "Node {} is expired",
+            hex::encode(&node)
+        )));
+    }
     let record = RECORDS.load(deps.storage, node)?;
     let owner = deps.api.addr_humanize(&record.owner)?;
     let resolver = deps.api.addr_humanize(&record.resolver)?;
@@ -187,28 +301,25 @@ pub fn query_record_by_node(deps: Deps, _env: Env, node: Vec<u8>) -> StdResult<R
     })
 }
 
-pub fn query_record(deps: Deps, _env: Env, name: String) -> StdResult<RecordResponse> {
+pub fn query_record(deps: Deps, env: Env, name: String) -> StdResult<RecordResponse> {
     let node = namehash(name.as_str());
-    let record = RECORDS.load(deps.storage, node)?;
-    let owner = deps.api.addr_humanize(&record.owner)?;
-    let resolver = deps.api.addr_humanize(&record.resolver)?;
-    let ttl = record.ttl;
-    Ok(RecordResponse {
-        owner,
-        resolver,
-        ttl,
-    })
+    query_record_by_node(deps, env, node, None)
+}
+
+pub fn query_expires(deps: Deps, _env: Env, node: Vec<u8>) -> StdResult<GetExpiresResponse> {
+    let expires = EXPIRIES.may_load(deps.storage, node)?.unwrap_or(0);
+    Ok(GetExpiresResponse { expires })
 }
 
 pub fn set_approval_for_all(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     node: Vec<u8>,
     operator: String,
     approved: bool,
 ) -> Result<Response, ContractError> {
-    only_authorized(&deps, &info, &node)?;
+    only_authorized(&deps, &env, &info, &node)?;
     let sender_address = deps.api.addr_canonicalize(info.sender.as_str())?;
     let operator_address = deps.api.addr_canonicalize(operator.as_str())?;
     OPERATORS.save(
@@ -217,7 +328,11 @@ pub fn set_approval_for_all(
         &approved,
     )?;
 
-    Ok(Response::default())
+    Ok(Response::new()
+        .add_attribute("method", "set_approval_for_all")
+        .add_attribute("owner", info.sender)
+        .add_attribute("operator", operator)
+        .add_attribute("approved", approved.to_string()))
 }
 
 pub fn query_is_approved_for_all(
@@ -239,7 +354,7 @@ pub fn query_is_approved_for_all(
         }
     }
 
-    return Ok(OperatorResponse { is_approve: false });
+    Ok(OperatorResponse { is_approve: false })
 }
 
 pub fn set_config(
@@ -247,31 +362,80 @@ pub fn set_config(
     _env: Env,
     info: MessageInfo,
     default_resolver: String,
-    owner: String,
 ) -> Result<Response, ContractError> {
-    only_owner(deps.as_ref(), &info)?;
+    // Unlike the registry of a resolver, the default resolver gives no control over existing
+    // nodes, it is only set on new records
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
     let mut config = CONFIG.load(deps.storage)?;
 
-    let default_resolver = deps.api.addr_canonicalize(default_resolver.as_str())?;
-    let owner = deps.api.addr_canonicalize(owner.as_str())?;
-
-    config.default_resolver = default_resolver.clone();
-    config.owner = owner.clone();
+    config.default_resolver = deps.api.addr_canonicalize(default_resolver.as_str())?;
 
     CONFIG.save(deps.storage, &config)?;
     Ok(Response::new()
         .add_attribute("method", "set_config")
-        .add_attribute("default_resolver", default_resolver.clone().to_string())
-        .add_attribute("owner", owner.to_string())
-    )
+        .add_attribute("default_resolver", default_resolver))
+}
+
+pub fn propose_owner(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    admin::propose_owner(deps.storage, deps.api, &owner)?;
+    Ok(Response::new()
+        .add_attribute("method", "propose_owner")
+        .add_attribute("pending_owner", owner))
+}
+
+pub fn accept_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let owner = admin::accept_ownership(deps.storage, deps.api, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.owner = owner;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("method", "accept_ownership")
+        .add_attribute("owner", info.sender))
+}
+
+pub fn set_role(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    role: Role,
+    address: Option<String>,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    admin::set_role(
+        deps.storage,
+        deps.api,
+        &[Role::RegistrationAdmin],
+        &role,
+        address.as_deref(),
+    )?;
+    Ok(Response::new()
+        .add_attribute("method", "set_role")
+        .add_attribute("role", role.to_string())
+        .add_attribute("address", address.unwrap_or_default()))
 }
 
 pub fn get_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config = CONFIG.load(deps.storage)?;
     let owner = deps.api.addr_humanize(&config.owner)?;
     let default_resolver = deps.api.addr_humanize(&config.default_resolver)?;
+    let pending_owner = admin::pending_owner(deps.storage, deps.api)?;
     Ok(ConfigResponse {
         default_resolver,
         owner,
+        pending_owner,
     })
 }
+
+pub fn get_roles(deps: Deps) -> StdResult<RolesResponse> {
+    admin::get_roles(deps.storage, deps.api, &[Role::RegistrationAdmin])
+}
@@ -21,4 +21,18 @@ pub struct Record {
 
 pub const RECORDS: Map<Vec<u8>, Record> = Map::new("RECORDS");
 
+// Only nodes given an expiry by their parent, e.g. names of the registrar
+pub const EXPIRIES: Map<Vec<u8>, u64> = Map::new("EXPIRIES");
+
+// Parent of the nodes created with SetSubnodeOwner, subnodes expire with their parent.
+// Nodes created before parents were recorded are linked when their owner is set again.
+pub const PARENTS: Map<Vec<u8>, Vec<u8>> = Map::new("PARENTS");
+
+// Bumped when an expired node is given out again, so the subnodes of the previous owner stay
+// expired. Nodes without an entry are at generation 0.
+pub const GENERATIONS: Map<Vec<u8>, u64> = Map::new("GENERATIONS");
+
+// Generation of the parent when the subnode was linked to it
+pub const PARENT_GENERATIONS: Map<Vec<u8>, u64> = Map::new("PARENT_GENERATIONS");
+
 pub const OPERATORS: Map<(Vec<u8>, Vec<u8>), bool> = Map::new("OPERATORS");
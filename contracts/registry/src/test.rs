@@ -1,9 +1,17 @@
 mod tests {
     use crate::contract::{execute, instantiate, query};
     use crate::error::ContractError;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary, Addr};
-    use tns::registry::{ExecuteMsg, InstantiateMsg, OperatorResponse, QueryMsg, RecordResponse, ConfigResponse};
+    use crate::state::PARENTS;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+        MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{attr, coins, from_binary, Addr, Env, OwnedDeps};
+    use tns::admin::{AdminError, Role, RoleAddress, RolesResponse};
+    use tns::registry::{
+        ConfigResponse, ExecuteMsg, GetExpiresResponse, InstantiateMsg, OperatorResponse, QueryMsg,
+        RecordResponse,
+    };
     use tns::utils::{convert_namehash_to_hex_string, namehash, keccak256, get_label_from_name};
 
     #[test]
@@ -149,7 +157,7 @@ mod tests {
             resolver: Some(String::from("resolver_address")),
             ttl: 1
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         let res = query(
             deps.as_ref(),
@@ -187,7 +195,7 @@ mod tests {
             label: get_label_from_name(&String::from("ust")),
             owner: String::from("registrar_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         // Check .ust record
         let res = query(
@@ -215,7 +223,7 @@ mod tests {
             label: get_label_from_name(&String::from("alice")), // alice label, // alice label
             owner: String::from("controller_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         // Check alice.ust record
         let res = query(
@@ -258,7 +266,7 @@ mod tests {
             resolver: Some(String::from("resolver_address")),
             ttl: 1
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         let res = query(
             deps.as_ref(),
@@ -326,7 +334,7 @@ mod tests {
             label: get_label_from_name(&String::from("ust")),
             owner: String::from("registrar_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         // Check .ust record
         let res = query(
@@ -367,7 +375,22 @@ mod tests {
             label: get_label_from_name(&String::from("alice")), // alice label, // alice label
             owner: String::from("controller_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "set_subnode_owner"),
+                attr("parent_node", convert_namehash_to_hex_string(namehash("ust"))),
+                attr(
+                    "label",
+                    convert_namehash_to_hex_string(get_label_from_name(&String::from("alice")))
+                ),
+                attr("node", convert_namehash_to_hex_string(namehash("alice.ust"))),
+                attr("owner", "controller_address"),
+                attr("resolver", mock_env().contract.address),
+                attr("ttl", "0"),
+            ]
+        );
 
         // Check alice.ust record
         let res = query(
@@ -406,7 +429,7 @@ mod tests {
             label: get_label_from_name(&String::from("ust")),
             owner: String::from("registrar_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         // Check .ust record
         let res = query(
@@ -434,7 +457,7 @@ mod tests {
             label: get_label_from_name(&String::from("alice")), // alice label
             owner: String::from("controller_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         // Check alice.ust record
         let res = query(
@@ -472,7 +495,7 @@ mod tests {
          //  Should success if set ttl with owner address
          let info = mock_info("controller_address", &coins(0, "uusd"));
          let msg = ExecuteMsg::SetOwner { node: subnode.clone(), owner: String::from("new_owner") };
-         assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+         assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
          // Check alice.ust record
          let res = query(
@@ -511,7 +534,7 @@ mod tests {
             label: get_label_from_name(&String::from("ust")),
             owner: String::from("registrar_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         // Check .ust record
         let res = query(
@@ -539,7 +562,7 @@ mod tests {
             label: get_label_from_name(&String::from("alice")), // alice label
             owner: String::from("controller_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         // Check alice.ust record
         let res = query(
@@ -577,7 +600,7 @@ mod tests {
          //  Should success if set ttl with owner address
          let info = mock_info("controller_address", &coins(0, "uusd"));
          let msg = ExecuteMsg::SetTTL { node: subnode.clone(), ttl: 3 };
-         assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+         assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
          // Check alice.ust record
          let res = query(
@@ -616,7 +639,7 @@ mod tests {
             label: get_label_from_name(&String::from("ust")),
             owner: String::from("registrar_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         // Check .ust record
         let res = query(
@@ -644,7 +667,7 @@ mod tests {
             label: get_label_from_name(&String::from("alice")), // alice label
             owner: String::from("controller_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         // Check alice.ust record
         let res = query(
@@ -682,7 +705,15 @@ mod tests {
          //  Should success if set resolver with owner address
          let info = mock_info("controller_address", &coins(0, "uusd"));
          let msg = ExecuteMsg::SetResolver { node: subnode.clone(), resolver: Some(String::from("new_resolver_address")) };
-         assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+         assert_eq!(
+             res.attributes,
+             vec![
+                 attr("method", "set_resolver"),
+                 attr("node", convert_namehash_to_hex_string(subnode.clone())),
+                 attr("resolver", "new_resolver_address"),
+             ]
+         );
 
          // Check alice.ust record
          let res = query(
@@ -721,7 +752,7 @@ mod tests {
             label: get_label_from_name(&String::from("ust")),
             owner: String::from("registrar_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         // Register alice.ust
         let info = mock_info("registrar_address", &coins(0, "uusd"));
@@ -730,7 +761,7 @@ mod tests {
             label: get_label_from_name(&String::from("alice")), // alice label
             owner: String::from("controller_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         // Check alice.ust record
         let res = query(
@@ -748,7 +779,8 @@ mod tests {
             deps.as_ref(),
             mock_env(),
             QueryMsg::GetRecordByNode {
-                node: namehash("alice.ust")
+                node: namehash("alice.ust"),
+                include_expired: None,
             },
         )
         .unwrap();
@@ -773,7 +805,7 @@ mod tests {
             label: get_label_from_name(&String::from("ust")),
             owner: String::from("registrar_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         // Register alice.ust
         let info = mock_info("registrar_address", &coins(0, "uusd"));
@@ -782,7 +814,7 @@ mod tests {
             label: get_label_from_name(&String::from("alice")), // alice label
             owner: String::from("controller_address"),
         };
-        assert_eq!(execute(deps.as_mut(), mock_env(), info, msg).is_ok(), true);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_ok());
 
         let res = query(
             deps.as_ref(),
@@ -794,7 +826,7 @@ mod tests {
         )
         .unwrap();
         let value: bool = from_binary(&res).unwrap();
-        assert_eq!(value, true);
+        assert!(value);
 
         let res = query(
             deps.as_ref(),
@@ -806,7 +838,310 @@ mod tests {
         )
         .unwrap();
         let value: bool = from_binary(&res).unwrap();
-        assert_eq!(value, false);
+        assert!(!value);
+    }
+
+    #[test]
+    fn test_subnode_expiry() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &coins(0, "uusd"));
+        let msg = ExecuteMsg::SetSubnodeOwner {
+            node: vec![0u8; 32],
+            label: get_label_from_name(&String::from("ust")),
+            owner: String::from("registrar_address"),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("registrar_address", &coins(0, "uusd"));
+        let msg = ExecuteMsg::SetSubnodeOwner {
+            node: namehash("ust"),
+            label: get_label_from_name(&String::from("alice")),
+            owner: String::from("alice"),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Only the parent owner can set the expiry
+        let expires = mock_env().block.time.seconds() + 100;
+        let msg = ExecuteMsg::SetSubnodeExpiry {
+            node: namehash("ust"),
+            label: get_label_from_name(&String::from("alice")),
+            expires,
+        };
+        let info = mock_info("alice", &coins(0, "uusd"));
+        assert!(execute(deps.as_mut(), mock_env(), info, msg.clone()).is_err());
+        let info = mock_info("registrar_address", &coins(0, "uusd"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "set_subnode_expiry"),
+                attr("parent_node", hex::encode(namehash("ust"))),
+                attr("label", hex::encode(get_label_from_name(&String::from("alice")))),
+                attr("node", hex::encode(namehash("alice.ust"))),
+                attr("expires", expires.to_string()),
+            ]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetExpires {
+                node: namehash("alice.ust"),
+            },
+        )
+        .unwrap();
+        let value: GetExpiresResponse = from_binary(&res).unwrap();
+        assert_eq!(expires, value.expires);
+
+        // Expired names lose their owner and stop resolving
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(101);
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetIsNodeOwner {
+                node: namehash("alice.ust"),
+                address: String::from("alice"),
+            },
+        )
+        .unwrap();
+        let value: bool = from_binary(&res).unwrap();
+        assert!(!value);
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetRecord {
+                name: String::from("alice.ust"),
+            },
+        );
+        assert!(res.is_err());
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetRecordByNode {
+                node: namehash("alice.ust"),
+                include_expired: Some(true),
+            },
+        )
+        .unwrap();
+        let value: RecordResponse = from_binary(&res).unwrap();
+        assert_eq!(value.owner, String::from("alice"));
+
+        let info = mock_info("alice", &coins(0, "uusd"));
+        let msg = ExecuteMsg::SetResolver {
+            node: namehash("alice.ust"),
+            resolver: Some(String::from("resolver_address")),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotNodeOwner {
+                sender: String::from("alice"),
+                node: format!("{:?}", namehash("alice.ust")),
+            }
+        );
+
+        // Renewal by the parent restores the ownership
+        let msg = ExecuteMsg::SetSubnodeExpiry {
+            node: namehash("ust"),
+            label: get_label_from_name(&String::from("alice")),
+            expires: expires + 100,
+        };
+        let info = mock_info("registrar_address", &coins(0, "uusd"));
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::GetIsNodeOwner {
+                node: namehash("alice.ust"),
+                address: String::from("alice"),
+            },
+        )
+        .unwrap();
+        let value: bool = from_binary(&res).unwrap();
+        assert!(value);
+    }
+
+    #[test]
+    fn test_parent_expiry() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let set_subnode_owner = |node: &str, label: &str, owner: &str| {
+            ExecuteMsg::SetSubnodeOwner {
+                node: namehash(node),
+                label: get_label_from_name(&String::from(label)),
+                owner: String::from(owner),
+            }
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        let msg = ExecuteMsg::SetSubnodeOwner {
+            node: vec![0u8; 32],
+            label: get_label_from_name(&String::from("ust")),
+            owner: String::from("registrar_address"),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let registrar_info = mock_info("registrar_address", &coins(0, "uusd"));
+        let msg = set_subnode_owner("ust", "alice", "alice");
+        execute(deps.as_mut(), mock_env(), registrar_info.clone(), msg).unwrap();
+        let expires = mock_env().block.time.seconds() + 100;
+        let msg = ExecuteMsg::SetSubnodeExpiry {
+            node: namehash("ust"),
+            label: get_label_from_name(&String::from("alice")),
+            expires,
+        };
+        execute(deps.as_mut(), mock_env(), registrar_info.clone(), msg).unwrap();
+
+        let alice_info = mock_info("alice", &coins(0, "uusd"));
+        let msg = set_subnode_owner("alice.ust", "app", "bob");
+        execute(deps.as_mut(), mock_env(), alice_info.clone(), msg).unwrap();
+        let msg = set_subnode_owner("alice.ust", "legacy", "bob");
+        execute(deps.as_mut(), mock_env(), alice_info.clone(), msg).unwrap();
+        // Nodes created before the upgrade have no parent recorded
+        PARENTS.remove(deps.as_mut().storage, namehash("legacy.alice.ust"));
+
+        let is_node_owner = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                             env: Env,
+                             name: &str| {
+            let res = query(
+                deps.as_ref(),
+                env,
+                QueryMsg::GetIsNodeOwner {
+                    node: namehash(name),
+                    address: String::from("bob"),
+                },
+            )
+            .unwrap();
+            from_binary::<bool>(&res).unwrap()
+        };
+        assert!(is_node_owner(&deps, mock_env(), "app.alice.ust"));
+
+        // Subnodes expire with their parent
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(101);
+        assert!(!is_node_owner(&deps, env.clone(), "app.alice.ust"));
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetRecord {
+                name: String::from("app.alice.ust"),
+            },
+        );
+        assert!(res.is_err());
+        let bob_info = mock_info("bob", &coins(0, "uusd"));
+        let msg = ExecuteMsg::SetResolver {
+            node: namehash("app.alice.ust"),
+            resolver: Some(String::from("resolver_address")),
+        };
+        let err = execute(deps.as_mut(), env.clone(), bob_info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotNodeOwner {
+                sender: String::from("bob"),
+                node: format!("{:?}", namehash("app.alice.ust")),
+            }
+        );
+
+        // Unlinked nodes keep resolving until the parent owner sets them again
+        assert!(is_node_owner(&deps, env.clone(), "legacy.alice.ust"));
+        let msg = ExecuteMsg::SetSubnodeExpiry {
+            node: namehash("ust"),
+            label: get_label_from_name(&String::from("alice")),
+            expires: expires + 100,
+        };
+        execute(deps.as_mut(), env.clone(), registrar_info, msg).unwrap();
+        assert!(is_node_owner(&deps, env.clone(), "app.alice.ust"));
+        let msg = set_subnode_owner("alice.ust", "legacy", "bob");
+        execute(deps.as_mut(), env.clone(), alice_info, msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(100);
+        assert!(!is_node_owner(&deps, env, "legacy.alice.ust"));
+    }
+
+    #[test]
+    fn test_parent_reregistration() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let set_subnode_owner = |node: &str, label: &str, owner: &str| {
+            ExecuteMsg::SetSubnodeOwner {
+                node: namehash(node),
+                label: get_label_from_name(label),
+                owner: String::from(owner),
+            }
+        };
+        let set_subnode_expiry = |expires: u64| ExecuteMsg::SetSubnodeExpiry {
+            node: namehash("ust"),
+            label: get_label_from_name("alice"),
+            expires,
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        let msg = ExecuteMsg::SetSubnodeOwner {
+            node: vec![0u8; 32],
+            label: get_label_from_name("ust"),
+            owner: String::from("registrar_address"),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let registrar_info = mock_info("registrar_address", &coins(0, "uusd"));
+        let expires = mock_env().block.time.seconds() + 100;
+        let msg = set_subnode_expiry(expires);
+        execute(deps.as_mut(), mock_env(), registrar_info.clone(), msg).unwrap();
+        let msg = set_subnode_owner("ust", "alice", "alice");
+        execute(deps.as_mut(), mock_env(), registrar_info.clone(), msg).unwrap();
+        let alice_info = mock_info("alice", &coins(0, "uusd"));
+        let msg = set_subnode_owner("alice.ust", "app", "bob");
+        execute(deps.as_mut(), mock_env(), alice_info, msg).unwrap();
+
+        let is_node_owner = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                             env: Env,
+                             name: &str,
+                             address: &str| {
+            let res = query(
+                deps.as_ref(),
+                env,
+                QueryMsg::GetIsNodeOwner {
+                    node: namehash(name),
+                    address: String::from(address),
+                },
+            )
+            .unwrap();
+            from_binary::<bool>(&res).unwrap()
+        };
+
+        // The registrar takes the expired name back before giving it to carol
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(101);
+        let msg = set_subnode_owner("ust", "alice", "registrar_address");
+        execute(deps.as_mut(), env.clone(), registrar_info.clone(), msg).unwrap();
+        let msg = set_subnode_expiry(env.block.time.seconds() + 100);
+        execute(deps.as_mut(), env.clone(), registrar_info.clone(), msg).unwrap();
+        let msg = set_subnode_owner("ust", "alice", "carol");
+        execute(deps.as_mut(), env.clone(), registrar_info, msg).unwrap();
+        assert!(is_node_owner(&deps, env.clone(), "alice.ust", "carol"));
+
+        // Subnodes of the previous registration stay expired
+        assert!(!is_node_owner(&deps, env.clone(), "app.alice.ust", "bob"));
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetRecord {
+                name: String::from("app.alice.ust"),
+            },
+        );
+        assert!(res.is_err());
+
+        // The new owner links them to the new registration
+        let carol_info = mock_info("carol", &coins(0, "uusd"));
+        let msg = set_subnode_owner("alice.ust", "app", "dave");
+        execute(deps.as_mut(), env.clone(), carol_info, msg).unwrap();
+        assert!(is_node_owner(&deps, env, "app.alice.ust", "dave"));
     }
 
     #[test]
@@ -819,7 +1154,6 @@ mod tests {
 
         let msg = ExecuteMsg::SetConfig {
             default_resolver: String::from("new_resolver_address"),
-            owner: String::from("new_owner"),
         };
         let info = mock_info("owner", &coins(0, "uusd"));
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -831,7 +1165,8 @@ mod tests {
             res,
             ConfigResponse {
                 default_resolver: Addr::unchecked(String::from("new_resolver_address")),
-                owner: Addr::unchecked(String::from("new_owner"))
+                owner: Addr::unchecked(String::from("owner")),
+                pending_owner: None,
             }
         );
     }
@@ -846,62 +1181,127 @@ mod tests {
 
         let msg = ExecuteMsg::SetConfig {
             default_resolver: String::from("new_resolver_address"),
-            owner: String::from("new_owner"),
         };
         let info = mock_info("not_owner", &coins(0, "uusd"));
         let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
 
         assert_eq!(
             err,
-            ContractError::NotOwner {
+            ContractError::Admin(AdminError::MissingRole {
                 sender: String::from("not_owner"),
-                owner: String::from("owner")
-            }
+                role: String::from("registration_admin")
+            })
         );
     }
 
     #[test]
-    fn test_set_config_transfer_owner() {
+    fn test_transfer_ownership() {
         let mut deps = mock_dependencies(&[]);
 
         let msg = InstantiateMsg {};
         let info = mock_info("owner", &coins(0, "uusd"));
-        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let msg = ExecuteMsg::SetConfig {
-            default_resolver: String::from("new_resolver_address"),
+        let msg = ExecuteMsg::ProposeOwner {
             owner: String::from("new_owner"),
         };
-        let info = mock_info("owner", &coins(0, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("not_owner", &[]), msg.clone())
+            .unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let msg = QueryMsg::GetConfig {};
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        // Nothing changes until the proposed owner accepts
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
         let res: ConfigResponse = from_binary(&res).unwrap();
         assert_eq!(
             res,
             ConfigResponse {
-                default_resolver: Addr::unchecked(String::from("new_resolver_address")),
-                owner: Addr::unchecked(String::from("new_owner"))
+                default_resolver: Addr::unchecked(MOCK_CONTRACT_ADDR),
+                owner: Addr::unchecked("owner"),
+                pending_owner: Some(Addr::unchecked("new_owner")),
             }
         );
 
-        let msg = ExecuteMsg::SetConfig {
-            default_resolver: String::from("new_resolver_address"),
-            owner: String::from("owner"),
-        };
-        let info = mock_info("new_owner", &coins(0, "uusd"));
-        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::AcceptOwnership {};
+        let err = execute(deps.as_mut(), mock_env(), mock_info("not_owner", &[]), msg.clone())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Admin(AdminError::NotPendingOwner {
+                sender: String::from("not_owner")
+            })
+        );
+        execute(deps.as_mut(), mock_env(), mock_info("new_owner", &[]), msg).unwrap();
 
-        let msg = QueryMsg::GetConfig {};
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
         let res: ConfigResponse = from_binary(&res).unwrap();
         assert_eq!(
             res,
             ConfigResponse {
-                default_resolver: Addr::unchecked(String::from("new_resolver_address")),
-                owner: Addr::unchecked(String::from("owner"))
+                default_resolver: Addr::unchecked(MOCK_CONTRACT_ADDR),
+                owner: Addr::unchecked("new_owner"),
+                pending_owner: None,
             }
         );
+
+        // Previous owner lost control
+        let msg = ExecuteMsg::SetConfig {
+            default_resolver: String::from("new_resolver_address"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Admin(AdminError::MissingRole { .. })));
+    }
+
+    #[test]
+    fn test_registration_admin() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {};
+        let info = mock_info("owner", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let config_msg = ExecuteMsg::SetConfig {
+            default_resolver: String::from("new_resolver_address"),
+        };
+        let admin_info = mock_info("admin", &[]);
+        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), config_msg.clone())
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Admin(AdminError::MissingRole { .. })));
+
+        let msg = ExecuteMsg::SetRole {
+            role: Role::RegistrationAdmin,
+            address: Some(String::from("admin")),
+        };
+        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), msg.clone())
+            .unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Roles {}).unwrap();
+        let res: RolesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.roles,
+            vec![RoleAddress {
+                role: Role::RegistrationAdmin,
+                address: Addr::unchecked("admin"),
+            }]
+        );
+        execute(deps.as_mut(), mock_env(), admin_info.clone(), config_msg.clone()).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(res.default_resolver, Addr::unchecked("new_resolver_address"));
+
+        // Ownership stays with the owner
+        let msg = ExecuteMsg::ProposeOwner {
+            owner: String::from("admin"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+
+        let msg = ExecuteMsg::SetRole {
+            role: Role::RegistrationAdmin,
+            address: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), admin_info, config_msg).unwrap_err();
+        assert!(matches!(err, ContractError::Admin(AdminError::MissingRole { .. })));
     }
 }
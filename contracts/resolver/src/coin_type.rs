@@ -0,0 +1,221 @@
+use crate::error::ContractError;
+use bech32::{u5, FromBase32, ToBase32, Variant};
+use cosmwasm_std::{Api, CanonicalAddr, StdError, StdResult};
+use tns::utils::keccak256;
+
+const HARDENED: u64 = 0x80000000;
+
+/// How addresses of a coin type are written, keyed by SLIP-44 index.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AddressFormat {
+    /// The host chain, validated and canonicalized by the api
+    Terra,
+    /// Cosmos chains, bech32 with the given human readable part
+    Bech32(&'static str),
+    /// EVM chains, 20 bytes hex with EIP-55 checksum
+    Evm,
+    /// BTC-style chains, base58check with the P2PKH and P2SH version bytes,
+    /// and bech32 segwit addresses with the given human readable part if supported
+    Base58Check {
+        p2pkh: u8,
+        p2sh: u8,
+        segwit: Option<&'static str>,
+    },
+    /// Unknown coin types are stored as given
+    Raw,
+}
+
+pub fn address_format(coin_type: u64) -> AddressFormat {
+    // Coin types are accepted with or without the hardened bit, like LUNA_COIN_TYPE
+    match coin_type & !HARDENED {
+        330 => AddressFormat::Terra,
+        118 => AddressFormat::Bech32("cosmos"),
+        394 => AddressFormat::Bech32("cro"),
+        459 => AddressFormat::Bech32("kava"),
+        494 => AddressFormat::Bech32("band"),
+        529 => AddressFormat::Bech32("secret"),
+        60 | 61 => AddressFormat::Evm,
+        0 => AddressFormat::Base58Check {
+            p2pkh: 0x00,
+            p2sh: 0x05,
+            segwit: Some("bc"),
+        },
+        2 => AddressFormat::Base58Check {
+            p2pkh: 0x30,
+            p2sh: 0x32,
+            segwit: Some("ltc"),
+        },
+        3 => AddressFormat::Base58Check {
+            p2pkh: 0x1e,
+            p2sh: 0x16,
+            segwit: None,
+        },
+        _ => AddressFormat::Raw,
+    }
+}
+
+/// Converts a text address into the binary form stored by the resolver.
+/// BTC-style addresses are stored as their output script like ENS does.
+pub fn encode_address(
+    api: &dyn Api,
+    coin_type: u64,
+    address: &str,
+) -> Result<Vec<u8>, ContractError> {
+    let invalid = || ContractError::InvalidAddress {
+        coin_type,
+        address: address.to_string(),
+    };
+    match address_format(coin_type) {
+        AddressFormat::Terra => {
+            let address = api.addr_validate(address).map_err(|_| invalid())?;
+            let canonical = api.addr_canonicalize(address.as_str())?;
+            Ok(canonical.to_vec())
+        }
+        AddressFormat::Bech32(hrp) => {
+            let (decoded_hrp, data, variant) = bech32::decode(address).map_err(|_| invalid())?;
+            if decoded_hrp != hrp || variant != Variant::Bech32 {
+                return Err(invalid());
+            }
+            let bytes = Vec::<u8>::from_base32(&data).map_err(|_| invalid())?;
+            if bytes.len() != 20 && bytes.len() != 32 {
+                return Err(invalid());
+            }
+            Ok(bytes)
+        }
+        AddressFormat::Evm => {
+            let hex_part = address.strip_prefix("0x").ok_or_else(invalid)?;
+            if hex_part.len() != 40 {
+                return Err(invalid());
+            }
+            let bytes = hex::decode(hex_part).map_err(|_| invalid())?;
+            // Mixed case addresses carry a checksum which has to match
+            let is_mixed_case = hex_part.chars().any(|c| c.is_ascii_uppercase())
+                && hex_part.chars().any(|c| c.is_ascii_lowercase());
+            if is_mixed_case && to_checksum_address(&bytes) != address {
+                return Err(invalid());
+            }
+            Ok(bytes)
+        }
+        AddressFormat::Base58Check {
+            p2pkh,
+            p2sh,
+            segwit,
+        } => {
+            if let Some(hrp) = segwit {
+                if address.to_lowercase().starts_with(&format!("{}1", hrp)) {
+                    return encode_segwit(hrp, address).ok_or_else(invalid);
+                }
+            }
+            let payload = bs58::decode(address)
+                .with_check(None)
+                .into_vec()
+                .map_err(|_| invalid())?;
+            if payload.len() != 21 {
+                return Err(invalid());
+            }
+            let hash = &payload[1..];
+            if payload[0] == p2pkh {
+                Ok([&[0x76, 0xa9, 0x14], hash, &[0x88, 0xac]].concat())
+            } else if payload[0] == p2sh {
+                Ok([&[0xa9, 0x14], hash, &[0x87]].concat())
+            } else {
+                Err(invalid())
+            }
+        }
+        AddressFormat::Raw => Ok(address.as_bytes().to_vec()),
+    }
+}
+
+/// Renders a stored binary address back into its text form.
+pub fn decode_address(api: &dyn Api, coin_type: u64, data: &[u8]) -> StdResult<String> {
+    let invalid = || {
+        StdError::generic_err(format!(
+            "Invalid stored address for coin type {}",
+            coin_type
+        ))
+    };
+    match address_format(coin_type) {
+        AddressFormat::Terra => Ok(api
+            .addr_humanize(&CanonicalAddr::from(data.to_vec()))?
+            .to_string()),
+        AddressFormat::Bech32(hrp) => {
+            bech32::encode(hrp, data.to_base32(), Variant::Bech32).map_err(|_| invalid())
+        }
+        AddressFormat::Evm => Ok(to_checksum_address(data)),
+        AddressFormat::Base58Check {
+            p2pkh,
+            p2sh,
+            segwit,
+        } => {
+            if let Some(address) = segwit.and_then(|hrp| decode_segwit(hrp, data)) {
+                return Ok(address);
+            }
+            let payload = match data {
+                [0x76, 0xa9, 0x14, hash @ .., 0x88, 0xac] if hash.len() == 20 => {
+                    [&[p2pkh], hash].concat()
+                }
+                [0xa9, 0x14, hash @ .., 0x87] if hash.len() == 20 => [&[p2sh], hash].concat(),
+                _ => return Err(invalid()),
+            };
+            Ok(bs58::encode(payload).with_check().into_string())
+        }
+        AddressFormat::Raw => String::from_utf8(data.to_vec()).map_err(StdError::invalid_utf8),
+    }
+}
+
+/// Segwit output script of a bech32 address: version opcode, program length and program.
+fn encode_segwit(hrp: &str, address: &str) -> Option<Vec<u8>> {
+    let (decoded_hrp, data, variant) = bech32::decode(address).ok()?;
+    let (version, program) = data.split_first()?;
+    let version = version.to_u8();
+    let program = Vec::<u8>::from_base32(program).ok()?;
+    // Version 0 is bech32 with 20 or 32 bytes, later versions are bech32m (BIP-350)
+    let valid = match version {
+        0 => variant == Variant::Bech32 && (program.len() == 20 || program.len() == 32),
+        1..=16 => variant == Variant::Bech32m && (2..=40).contains(&program.len()),
+        _ => false,
+    };
+    if decoded_hrp != hrp || !valid {
+        return None;
+    }
+    let opcode = if version == 0 { 0x00 } else { 0x50 + version };
+    Some([&[opcode, program.len() as u8], program.as_slice()].concat())
+}
+
+fn decode_segwit(hrp: &str, script: &[u8]) -> Option<String> {
+    let (opcode, program) = match script {
+        [opcode, len, program @ ..] if *len as usize == program.len() => (*opcode, program),
+        _ => return None,
+    };
+    let (version, variant) = match opcode {
+        0x00 => (0, Variant::Bech32),
+        0x51..=0x60 => (opcode - 0x50, Variant::Bech32m),
+        _ => return None,
+    };
+    let mut data = vec![u5::try_from_u8(version).ok()?];
+    data.extend(program.to_base32());
+    bech32::encode(hrp, data, variant).ok()
+}
+
+/// EIP-55 mixed case checksum encoding.
+fn to_checksum_address(bytes: &[u8]) -> String {
+    let lower = hex::encode(bytes);
+    let hash = keccak256(lower.as_bytes());
+    let checksummed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = if i % 2 == 0 {
+                hash[i / 2] >> 4
+            } else {
+                hash[i / 2] & 0x0f
+            };
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{}", checksummed)
+}
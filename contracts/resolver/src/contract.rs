@@ -1,7 +1,10 @@
 use crate::error::ContractError;
 use crate::handler::{
-    get_config, query_address, query_content_hash, query_terra_address, query_text_data,
-    set_address, set_config, set_content_hash, set_terra_address, set_text_data,
+    accept_ownership, clear_records, get_config, get_roles, migrate_addresses, multicall,
+    propose_owner, query_address, query_addresses, query_content_hash, query_name, query_records,
+    query_records_batch, query_terra_address, query_text_data, query_text_keys, query_version,
+    set_address, set_config, set_content_hash, set_name, set_role, set_terra_address,
+    set_text_data,
 };
 use crate::state::{Config, CONFIG};
 #[cfg(not(feature = "library"))]
@@ -49,11 +52,19 @@ pub fn execute(
             set_text_data(deps, env, info, node, key, value)
         }
         ExecuteMsg::SetContentHash { node, hash } => set_content_hash(deps, env, info, node, hash),
+        ExecuteMsg::SetName { node, name } => set_name(deps, env, info, node, name),
+        ExecuteMsg::ClearRecords { node } => clear_records(deps, env, info, node),
+        ExecuteMsg::Multicall { calls } => multicall(deps, env, info, calls),
         ExecuteMsg::SetConfig {
             interface_id,
             registry_address,
-            owner,
-        } => set_config(deps, env, info, interface_id, registry_address, owner),
+        } => set_config(deps, env, info, interface_id, registry_address),
+        ExecuteMsg::ProposeOwner { owner } => propose_owner(deps, env, info, owner),
+        ExecuteMsg::AcceptOwnership {} => accept_ownership(deps, env, info),
+        ExecuteMsg::MigrateAddresses { start_after, limit } => {
+            migrate_addresses(deps, env, info, start_after, limit)
+        }
+        ExecuteMsg::SetRole { role, address } => set_role(deps, env, info, role, address),
     }
 }
 
@@ -66,7 +77,46 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GetTerraAddress { node } => to_binary(&query_terra_address(deps, env, node)?),
         QueryMsg::GetTextData { node, key } => to_binary(&query_text_data(deps, env, node, key)?),
         QueryMsg::GetContentHash { node } => to_binary(&query_content_hash(deps, env, node)?),
+        QueryMsg::GetName { node } => to_binary(&query_name(deps, env, node)?),
+        QueryMsg::GetVersion { node } => to_binary(&query_version(deps, env, node)?),
+        QueryMsg::GetRecords {
+            node,
+            coin_types,
+            text_keys,
+            include_content_hash,
+        } => to_binary(&query_records(
+            deps,
+            env,
+            node,
+            coin_types,
+            text_keys,
+            include_content_hash,
+        )?),
+        QueryMsg::GetRecordsBatch {
+            nodes,
+            coin_types,
+            text_keys,
+            include_content_hash,
+        } => to_binary(&query_records_batch(
+            deps,
+            env,
+            nodes,
+            coin_types,
+            text_keys,
+            include_content_hash,
+        )?),
+        QueryMsg::ListTextKeys {
+            node,
+            start_after,
+            limit,
+        } => to_binary(&query_text_keys(deps, env, node, start_after, limit)?),
+        QueryMsg::ListAddresses {
+            node,
+            start_after,
+            limit,
+        } => to_binary(&query_addresses(deps, env, node, start_after, limit)?),
         QueryMsg::GetConfig {} => to_binary(&get_config(deps)?),
+        QueryMsg::Roles {} => to_binary(&get_roles(deps)?),
     }
 }
 
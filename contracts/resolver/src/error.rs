@@ -1,5 +1,6 @@
 use cosmwasm_std::StdError;
 use thiserror::Error;
+use tns::admin::AdminError;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -15,11 +16,20 @@ pub enum ContractError {
         owner: String
     },
 
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
     #[error("NotNodeOwner: Sender {sender} is not node owner of {node}.")]
     NotNodeOwner {
         sender: String,
         node: String
     },
+
+    #[error("InvalidAddress: {address} is not a valid address for coin type {coin_type}.")]
+    InvalidAddress { coin_type: u64, address: String },
+
+    #[error("NotAllowedInMulticall: Only record updates can be batched.")]
+    NotAllowedInMulticall {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
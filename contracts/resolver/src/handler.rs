@@ -1,16 +1,28 @@
+use crate::coin_type::{decode_address, encode_address};
 use crate::error::ContractError;
 use crate::state::CONTENT_HASH;
+use crate::state::NAMES;
 use crate::state::TEXT_DATA;
-use crate::state::{ADDRESSES, CONFIG};
+use crate::state::{ADDRESSES, CONFIG, LEGACY_ADDRESSES, VERSIONS};
 use cosmwasm_std::{
-    to_binary, Deps, DepsMut, Env, MessageInfo, QueryRequest, Response, StdResult, WasmQuery,
+    attr, to_binary, Api, Attribute, Binary, Deps, DepsMut, Env, Event, MessageInfo, Order,
+    QueryRequest, Response, StdError, StdResult, Storage, WasmQuery,
 };
-use cw_storage_plus::U64Key;
+use cw_storage_plus::{Bound, U64Key};
+use std::convert::TryInto;
+use tns::admin::{self, Role, RolesResponse};
 use tns::registry::QueryMsg as RegistryQueryMsg;
-use tns::resolver::{AddressResponse, ConfigResponse, ContentHashResponse, TextDataResponse};
+use tns::resolver::{
+    AddressRecord, AddressResponse, AddressesResponse, CoinAddress, ConfigResponse,
+    ContentHashResponse, ExecuteMsg, NameResponse, RecordsBatchResponse, RecordsResponse,
+    TextDataResponse, TextKeysResponse, TextRecord, VersionResponse,
+};
 
 const LUNA_COIN_TYPE: u64 = 0x8000014a;
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 fn only_owner(deps: Deps, info: &MessageInfo) -> Result<bool, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
@@ -23,10 +35,16 @@ fn only_owner(deps: Deps, info: &MessageInfo) -> Result<bool, ContractError> {
     Ok(true)
 }
 
+fn only_role(deps: Deps, info: &MessageInfo, role: Role) -> Result<bool, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    admin::only_role(deps.storage, deps.api, &config.owner, &info.sender, role)?;
+    Ok(true)
+}
+
 pub fn only_authorized(
     deps: &DepsMut,
     info: &MessageInfo,
-    node: &Vec<u8>,
+    node: &[u8],
 ) -> Result<bool, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
@@ -37,7 +55,7 @@ pub fn only_authorized(
     let is_node_owner: bool = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: registry_address.clone(),
         msg: to_binary(&RegistryQueryMsg::GetIsNodeOwner {
-            node: node.clone(),
+            node: node.to_vec(),
             address: info.sender.to_string(),
         })?,
     }))?;
@@ -45,10 +63,95 @@ pub fn only_authorized(
     if is_node_owner {
         return Ok(true);
     }
-    return Err(ContractError::NotNodeOwner {
+    Err(ContractError::NotNodeOwner {
         sender: info.sender.to_string(),
-        node: format!("{:?}", node.clone()),
-    });
+        node: format!("{:?}", node),
+    })
+}
+
+fn versioned_node(storage: &dyn Storage, node: &[u8]) -> StdResult<Vec<u8>> {
+    let version = VERSIONS.may_load(storage, node.to_vec())?.unwrap_or(0);
+    // Version 0 keeps the plain node so records set before versioning stay readable
+    if version == 0 {
+        return Ok(node.to_vec());
+    }
+    Ok([node, &version.to_be_bytes()].concat())
+}
+
+fn _set_address(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    node: Vec<u8>,
+    coin_type: u64,
+    address: String,
+) -> Result<Vec<Attribute>, ContractError> {
+    let data = encode_address(api, coin_type, &address)?;
+    // Report the normalized form that queries will return
+    let address = decode_address(api, coin_type, &data)?;
+    // Replaces a text address left from before coin type validation
+    LEGACY_ADDRESSES.remove(storage, (node.clone(), U64Key::from(coin_type)));
+    let versioned = versioned_node(storage, &node)?;
+    ADDRESSES.save(
+        storage,
+        (versioned, U64Key::from(coin_type)),
+        &Binary::from(data),
+    )?;
+    Ok(vec![
+        attr("method", "set_address"),
+        attr("node", hex::encode(node)),
+        attr("coin_type", coin_type.to_string()),
+        attr("address", address),
+    ])
+}
+
+fn _set_text_data(
+    storage: &mut dyn Storage,
+    node: Vec<u8>,
+    key: String,
+    value: String,
+) -> StdResult<Vec<Attribute>> {
+    let versioned = versioned_node(storage, &node)?;
+    TEXT_DATA.save(storage, (versioned, key.clone()), &value)?;
+    Ok(vec![
+        attr("method", "set_text_data"),
+        attr("node", hex::encode(node)),
+        attr("key", key),
+        attr("value", value),
+    ])
+}
+
+fn _set_content_hash(
+    storage: &mut dyn Storage,
+    node: Vec<u8>,
+    hash: Vec<u8>,
+) -> StdResult<Vec<Attribute>> {
+    let versioned = versioned_node(storage, &node)?;
+    CONTENT_HASH.save(storage, versioned, &hash)?;
+    Ok(vec![
+        attr("method", "set_content_hash"),
+        attr("node", hex::encode(node)),
+        attr("hash", hex::encode(hash)),
+    ])
+}
+
+fn _set_name(storage: &mut dyn Storage, node: Vec<u8>, name: String) -> StdResult<Vec<Attribute>> {
+    let versioned = versioned_node(storage, &node)?;
+    NAMES.save(storage, versioned, &name)?;
+    Ok(vec![
+        attr("method", "set_name"),
+        attr("node", hex::encode(node)),
+        attr("name", name),
+    ])
+}
+
+fn _clear_records(storage: &mut dyn Storage, node: Vec<u8>) -> StdResult<Vec<Attribute>> {
+    let version = VERSIONS.may_load(storage, node.clone())?.unwrap_or(0) + 1;
+    VERSIONS.save(storage, node.clone(), &version)?;
+    Ok(vec![
+        attr("method", "clear_records"),
+        attr("node", hex::encode(node)),
+        attr("version", version.to_string()),
+    ])
 }
 
 pub fn set_address(
@@ -60,8 +163,8 @@ pub fn set_address(
     address: String,
 ) -> Result<Response, ContractError> {
     only_authorized(&deps, &info, &node)?;
-    ADDRESSES.save(deps.storage, (node, U64Key::from(coin_type)), &address)?;
-    Ok(Response::default())
+    let attributes = _set_address(deps.storage, deps.api, node, coin_type, address)?;
+    Ok(Response::new().add_attributes(attributes))
 }
 
 pub fn set_terra_address(
@@ -71,15 +174,7 @@ pub fn set_terra_address(
     node: Vec<u8>,
     address: String,
 ) -> Result<Response, ContractError> {
-    let terra_address = deps.api.addr_validate(address.as_str())?;
-    return set_address(
-        deps,
-        env,
-        info,
-        node,
-        LUNA_COIN_TYPE,
-        terra_address.to_string(),
-    );
+    set_address(deps, env, info, node, LUNA_COIN_TYPE, address)
 }
 
 pub fn query_address(
@@ -88,12 +183,14 @@ pub fn query_address(
     node: Vec<u8>,
     coin_type: u64,
 ) -> StdResult<AddressResponse> {
-    let address = ADDRESSES.load(deps.storage, (node, U64Key::from(coin_type)))?;
-    Ok(AddressResponse { address: address })
+    let node = versioned_node(deps.storage, &node)?;
+    let data = ADDRESSES.load(deps.storage, (node, U64Key::from(coin_type)))?;
+    let address = decode_address(deps.api, coin_type, &data)?;
+    Ok(AddressResponse { address })
 }
 
 pub fn query_terra_address(deps: Deps, env: Env, node: Vec<u8>) -> StdResult<AddressResponse> {
-    return query_address(deps, env, node, LUNA_COIN_TYPE);
+    query_address(deps, env, node, LUNA_COIN_TYPE)
 }
 
 pub fn set_text_data(
@@ -105,8 +202,8 @@ pub fn set_text_data(
     value: String,
 ) -> Result<Response, ContractError> {
     only_authorized(&deps, &info, &node)?;
-    TEXT_DATA.save(deps.storage, (node, key), &value)?;
-    Ok(Response::default())
+    let attributes = _set_text_data(deps.storage, node, key, value)?;
+    Ok(Response::new().add_attributes(attributes))
 }
 
 pub fn query_text_data(
@@ -115,6 +212,7 @@ pub fn query_text_data(
     node: Vec<u8>,
     key: String,
 ) -> StdResult<TextDataResponse> {
+    let node = versioned_node(deps.storage, &node)?;
     let value = TEXT_DATA.load(deps.storage, (node, key))?;
     Ok(TextDataResponse {
         data: value.to_string(),
@@ -129,48 +227,346 @@ pub fn set_content_hash(
     hash: Vec<u8>,
 ) -> Result<Response, ContractError> {
     only_authorized(&deps, &info, &node)?;
-    CONTENT_HASH.save(deps.storage, node, &hash)?;
-    Ok(Response::default())
+    let attributes = _set_content_hash(deps.storage, node, hash)?;
+    Ok(Response::new().add_attributes(attributes))
 }
 
 pub fn query_content_hash(deps: Deps, _env: Env, node: Vec<u8>) -> StdResult<ContentHashResponse> {
+    let node = versioned_node(deps.storage, &node)?;
     let value = CONTENT_HASH.load(deps.storage, node)?;
     Ok(ContentHashResponse { hash: value })
 }
 
+pub fn set_name(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    node: Vec<u8>,
+    name: String,
+) -> Result<Response, ContractError> {
+    only_authorized(&deps, &info, &node)?;
+    let attributes = _set_name(deps.storage, node, name)?;
+    Ok(Response::new().add_attributes(attributes))
+}
+
+pub fn clear_records(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    node: Vec<u8>,
+) -> Result<Response, ContractError> {
+    only_authorized(&deps, &info, &node)?;
+    let attributes = _clear_records(deps.storage, node)?;
+    Ok(Response::new().add_attributes(attributes))
+}
+
+pub fn multicall(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    calls: Vec<ExecuteMsg>,
+) -> Result<Response, ContractError> {
+    // Each node is checked against the registry only once
+    let mut authorized_nodes: Vec<Vec<u8>> = vec![];
+    let mut response = Response::new().add_attribute("method", "multicall");
+    for call in calls {
+        let node = match &call {
+            ExecuteMsg::SetAddress { node, .. }
+            | ExecuteMsg::SetTerraAddress { node, .. }
+            | ExecuteMsg::SetTextData { node, .. }
+            | ExecuteMsg::SetContentHash { node, .. }
+            | ExecuteMsg::SetName { node, .. }
+            | ExecuteMsg::ClearRecords { node } => node.clone(),
+            _ => return Err(ContractError::NotAllowedInMulticall {}),
+        };
+        if !authorized_nodes.contains(&node) {
+            only_authorized(&deps, &info, &node)?;
+            authorized_nodes.push(node);
+        }
+
+        let attributes = match call {
+            ExecuteMsg::SetAddress {
+                node,
+                coin_type,
+                address,
+            } => _set_address(deps.storage, deps.api, node, coin_type, address)?,
+            ExecuteMsg::SetTerraAddress { node, address } => {
+                _set_address(deps.storage, deps.api, node, LUNA_COIN_TYPE, address)?
+            }
+            ExecuteMsg::SetTextData { node, key, value } => {
+                _set_text_data(deps.storage, node, key, value)?
+            }
+            ExecuteMsg::SetContentHash { node, hash } => {
+                _set_content_hash(deps.storage, node, hash)?
+            }
+            ExecuteMsg::SetName { node, name } => _set_name(deps.storage, node, name)?,
+            ExecuteMsg::ClearRecords { node } => _clear_records(deps.storage, node)?,
+            _ => return Err(ContractError::NotAllowedInMulticall {}),
+        };
+        // One event per update so repeated keys stay apart
+        response = response.add_event(Event::new("multicall_update").add_attributes(attributes));
+    }
+    Ok(response)
+}
+
+/// Moves text addresses from before coin type validation into their binary form.
+/// Addresses that do not parse for their coin type are dropped.
+/// Moves up to `limit` text addresses after `start_after` to their binary form.
+/// Addresses that do not parse for their coin type are kept and listed in `invalid`.
+pub fn migrate_addresses(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|key| hex::decode(key).map(Bound::exclusive))
+        .transpose()
+        .map_err(|_| {
+            StdError::generic_err("start_after must be the last key of a previous migration")
+        })?;
+    let legacy = LEGACY_ADDRESSES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(Vec<u8>, String)>>>()?;
+    let last = legacy.last().map(|(key, _)| hex::encode(key));
+    let mut migrated = 0u64;
+    let mut invalid: Vec<String> = vec![];
+    for (key, address) in legacy {
+        // Composite keys are the length prefixed node followed by the coin type
+        let node_len = u16::from_be_bytes([key[0], key[1]]) as usize;
+        let node = key[2..2 + node_len].to_vec();
+        let coin_type = u64::from_be_bytes(
+            key[2 + node_len..]
+                .try_into()
+                .map_err(|_| StdError::generic_err("invalid legacy address key"))?,
+        );
+        match encode_address(deps.api, coin_type, &address) {
+            Ok(data) => {
+                let storage_key = (node, U64Key::from(coin_type));
+                LEGACY_ADDRESSES.remove(deps.storage, storage_key.clone());
+                ADDRESSES.save(deps.storage, storage_key, &Binary::from(data))?;
+                migrated += 1;
+            }
+            Err(_) => invalid.push(format!("{}:{}", hex::encode(node), coin_type)),
+        }
+    }
+    Ok(Response::new()
+        .add_attribute("method", "migrate_addresses")
+        .add_attribute("migrated", migrated.to_string())
+        .add_attribute("invalid", invalid.join(","))
+        .add_attribute("last", last.unwrap_or_default()))
+}
+
+pub fn query_version(deps: Deps, _env: Env, node: Vec<u8>) -> StdResult<VersionResponse> {
+    let version = VERSIONS.may_load(deps.storage, node)?.unwrap_or(0);
+    Ok(VersionResponse { version })
+}
+
+pub fn query_name(deps: Deps, _env: Env, node: Vec<u8>) -> StdResult<NameResponse> {
+    let node = versioned_node(deps.storage, &node)?;
+    let name = NAMES.load(deps.storage, node)?;
+    Ok(NameResponse { name })
+}
+
+pub fn query_records(
+    deps: Deps,
+    _env: Env,
+    node: Vec<u8>,
+    coin_types: Vec<u64>,
+    text_keys: Vec<String>,
+    include_content_hash: bool,
+) -> StdResult<RecordsResponse> {
+    let versioned = versioned_node(deps.storage, &node)?;
+    let addresses = coin_types
+        .into_iter()
+        .map(|coin_type| {
+            let address = ADDRESSES
+                .may_load(deps.storage, (versioned.clone(), U64Key::from(coin_type)))?
+                .map(|data| decode_address(deps.api, coin_type, &data))
+                .transpose()?;
+            Ok(AddressRecord { coin_type, address })
+        })
+        .collect::<StdResult<Vec<AddressRecord>>>()?;
+    let texts = text_keys
+        .into_iter()
+        .map(|key| {
+            let value = TEXT_DATA.may_load(deps.storage, (versioned.clone(), key.clone()))?;
+            Ok(TextRecord { key, value })
+        })
+        .collect::<StdResult<Vec<TextRecord>>>()?;
+    let content_hash = if include_content_hash {
+        CONTENT_HASH.may_load(deps.storage, versioned)?
+    } else {
+        None
+    };
+    Ok(RecordsResponse {
+        node,
+        addresses,
+        texts,
+        content_hash,
+    })
+}
+
+pub fn query_records_batch(
+    deps: Deps,
+    env: Env,
+    nodes: Vec<Vec<u8>>,
+    coin_types: Vec<u64>,
+    text_keys: Vec<String>,
+    include_content_hash: bool,
+) -> StdResult<RecordsBatchResponse> {
+    let records = nodes
+        .into_iter()
+        .map(|node| {
+            query_records(
+                deps,
+                env.clone(),
+                node,
+                coin_types.clone(),
+                text_keys.clone(),
+                include_content_hash,
+            )
+        })
+        .collect::<StdResult<Vec<RecordsResponse>>>()?;
+    Ok(RecordsBatchResponse { records })
+}
+
+pub fn query_text_keys(
+    deps: Deps,
+    _env: Env,
+    node: Vec<u8>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TextKeysResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let keys = TEXT_DATA
+        .prefix(versioned_node(deps.storage, &node)?)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|key| String::from_utf8(key).map_err(StdError::invalid_utf8))
+        .collect::<StdResult<Vec<String>>>()?;
+    Ok(TextKeysResponse { keys })
+}
+
+pub fn query_addresses(
+    deps: Deps,
+    _env: Env,
+    node: Vec<u8>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<AddressesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive_int);
+
+    let addresses = ADDRESSES
+        .prefix(versioned_node(deps.storage, &node)?)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, data) = item?;
+            let coin_type = u64::from_be_bytes(
+                key.as_slice()
+                    .try_into()
+                    .map_err(|_| StdError::generic_err("Invalid coin type key"))?,
+            );
+            let address = decode_address(deps.api, coin_type, &data)?;
+            Ok(CoinAddress { coin_type, address })
+        })
+        .collect::<StdResult<Vec<CoinAddress>>>()?;
+    Ok(AddressesResponse { addresses })
+}
+
 pub fn set_config(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     interface_id: u64,
     registry_address: String,
-    owner: String,
 ) -> Result<Response, ContractError> {
-    only_owner(deps.as_ref(), &info)?;
+    only_role(deps.as_ref(), &info, Role::RegistrationAdmin)?;
     let mut config = CONFIG.load(deps.storage)?;
-
-    let registry_address = deps.api.addr_canonicalize(registry_address.as_str())?;
-    let owner = deps.api.addr_canonicalize(owner.as_str())?;
+    let canonical_registry = deps.api.addr_canonicalize(registry_address.as_str())?;
+    // Node ownership is checked against the registry, only the owner can repoint it
+    if canonical_registry != config.registry_address {
+        only_owner(deps.as_ref(), &info)?;
+    }
 
     config.interface_id = interface_id;
-    config.registry_address = registry_address.clone();
-    config.owner = owner.clone();
+    config.registry_address = canonical_registry;
 
     CONFIG.save(deps.storage, &config)?;
     Ok(Response::new()
         .add_attribute("method", "set_config")
         .add_attribute("interface_id", interface_id.to_string())
-        .add_attribute("registry_address", registry_address.clone().to_string())
-        .add_attribute("owner", owner.clone().to_string()))
+        .add_attribute("registry_address", registry_address))
+}
+
+pub fn propose_owner(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    admin::propose_owner(deps.storage, deps.api, &owner)?;
+    Ok(Response::new()
+        .add_attribute("method", "propose_owner")
+        .add_attribute("pending_owner", owner))
+}
+
+pub fn accept_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let owner = admin::accept_ownership(deps.storage, deps.api, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.owner = owner;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("method", "accept_ownership")
+        .add_attribute("owner", info.sender))
+}
+
+pub fn set_role(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    role: Role,
+    address: Option<String>,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    admin::set_role(
+        deps.storage,
+        deps.api,
+        &[Role::RegistrationAdmin],
+        &role,
+        address.as_deref(),
+    )?;
+    Ok(Response::new()
+        .add_attribute("method", "set_role")
+        .add_attribute("role", role.to_string())
+        .add_attribute("address", address.unwrap_or_default()))
 }
 
 pub fn get_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config = CONFIG.load(deps.storage)?;
     let owner = deps.api.addr_humanize(&config.owner)?;
     let registry_address = deps.api.addr_humanize(&config.registry_address)?;
+    let pending_owner = admin::pending_owner(deps.storage, deps.api)?;
     Ok(ConfigResponse {
         interface_id: config.interface_id,
         registry_address,
         owner,
+        pending_owner,
     })
 }
+
+pub fn get_roles(deps: Deps) -> StdResult<RolesResponse> {
+    admin::get_roles(deps.storage, deps.api, &[Role::RegistrationAdmin])
+}
@@ -1,3 +1,4 @@
+pub mod coin_type;
 pub mod contract;
 mod error;
 pub mod handler;
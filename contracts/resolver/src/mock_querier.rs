@@ -100,7 +100,7 @@ impl WasmMockQuerier {
                 msg,
             }) => match from_binary(msg) {
                 Ok(RegistryQueryMsg::GetIsNodeOwner { node: _, address }) => {
-                    if address == String::from("owner_address") {
+                    if address == "owner_address" {
                         SystemResult::Ok(ContractResult::Ok(to_binary(&true).unwrap()))
                     } else {
                         SystemResult::Ok(ContractResult::Ok(to_binary(&false).unwrap()))
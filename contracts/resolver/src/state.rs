@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::CanonicalAddr;
+use cosmwasm_std::{Binary, CanonicalAddr};
 use cw_storage_plus::{Item, Map, U64Key};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -13,8 +13,16 @@ pub struct Config {
 
 pub const CONFIG: Item<Config> = Item::new("CONFIG");
 
-pub const ADDRESSES: Map<(Vec<u8>, U64Key), String> = Map::new("ADDRESSES");
+// Addresses in their canonical binary form, see coin_type.rs
+pub const ADDRESSES: Map<(Vec<u8>, U64Key), Binary> = Map::new("ADDRESS_BYTES");
+// Text addresses stored before coin type validation, moved to ADDRESSES by MigrateAddresses
+pub const LEGACY_ADDRESSES: Map<(Vec<u8>, U64Key), String> = Map::new("ADDRESSES");
 
 pub const TEXT_DATA: Map<(Vec<u8>, String), String> = Map::new("TEXT");
 
 pub const CONTENT_HASH: Map<Vec<u8>, Vec<u8>> = Map::new("CONTENT_HASH");
+
+pub const NAMES: Map<Vec<u8>, String> = Map::new("NAMES");
+
+// Record version of each node, all records are stored under the versioned node
+pub const VERSIONS: Map<Vec<u8>, u64> = Map::new("VERSIONS");
@@ -1,14 +1,19 @@
 mod tests {
-    use crate::contract::{execute, instantiate, query};
+    use crate::contract::{execute, instantiate, migrate, query};
     use crate::error::ContractError;
     use crate::mock_querier::mock_dependencies;
+    use crate::state::LEGACY_ADDRESSES;
     use cosmwasm_std::testing::{mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary, Addr};
+    use cosmwasm_std::{attr, coins, from_binary, Addr, Event, Order, StdResult};
+    use cw_storage_plus::U64Key;
+    use tns::admin::{AdminError, Role, RoleAddress, RolesResponse};
     use tns::resolver::{
-        AddressResponse, ConfigResponse, ContentHashResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
-        TextDataResponse,
+        AddressRecord, AddressResponse, AddressesResponse, CoinAddress, ConfigResponse,
+        ContentHashResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, NameResponse, QueryMsg,
+        RecordsBatchResponse, RecordsResponse, TextDataResponse, TextKeysResponse, TextRecord,
+        VersionResponse,
     };
-    use tns::utils::namehash;
+    use tns::utils::{get_reverse_node, namehash};
 
     #[test]
     fn test_non_owner_cannot_set_address() {
@@ -84,6 +89,105 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_address_validation() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            interface_id: 1,
+            registry_address: String::from("registry_address"),
+        };
+        let info = mock_info("owner", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("owner_address", &coins(0, "uusd"));
+        let cases = [
+            // Lowercase EVM addresses are returned checksummed
+            (
+                60,
+                "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+                "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            ),
+            (
+                0,
+                "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa",
+                "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa",
+            ),
+            (
+                0,
+                "32Wnq4482y6gaTH9vYmjaorU6iZFt2Tonc",
+                "32Wnq4482y6gaTH9vYmjaorU6iZFt2Tonc",
+            ),
+            // Segwit addresses are returned in lowercase
+            (
+                0,
+                "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4",
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            ),
+            (
+                0,
+                "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3",
+                "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3",
+            ),
+            (
+                0,
+                "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+                "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+            ),
+            (
+                118,
+                "cosmos1qurswpc8qurswpc8qurswpc8qurswpc8nn86qp",
+                "cosmos1qurswpc8qurswpc8qurswpc8qurswpc8nn86qp",
+            ),
+            (1, "any_address", "any_address"),
+        ];
+        for (coin_type, address, expected) in cases {
+            let msg = ExecuteMsg::SetAddress {
+                node: namehash("alice.ust"),
+                coin_type,
+                address: String::from(address),
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+            let query_msg = QueryMsg::GetAddress {
+                node: namehash("alice.ust"),
+                coin_type,
+            };
+            let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+            let res: AddressResponse = from_binary(&res).unwrap();
+            assert_eq!(res.address, expected);
+        }
+
+        let invalid_cases = [
+            // Wrong checksum
+            (60, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+            (60, "0x5aaeb6053f3e94c9b9a09f33669435e7ef1bea"),
+            (0, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb"),
+            // Litecoin version byte on Bitcoin
+            (0, "LVuDpNCSSj6pQ7t9Pv6d6sUkLKoqDEVUnJ"),
+            // Segwit version 0 encoded as bech32m, and a testnet address
+            (0, "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh"),
+            (0, "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"),
+            // Wrong human readable part
+            (118, "secret1qurswpc8qurswpc8qurswpc8qurswpc83knnaa"),
+        ];
+        for (coin_type, address) in invalid_cases {
+            let msg = ExecuteMsg::SetAddress {
+                node: namehash("alice.ust"),
+                coin_type,
+                address: String::from(address),
+            };
+            let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::InvalidAddress {
+                    coin_type,
+                    address: String::from(address),
+                }
+            );
+        }
+    }
+
     #[test]
     fn test_set_text_data() {
         let mut deps = mock_dependencies(&[]);
@@ -201,6 +305,550 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_migrate_legacy_addresses() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            interface_id: 1,
+            registry_address: String::from("registry_address"),
+        };
+        let info = mock_info("owner", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Records written as text before coin type validation
+        let node = namehash("alice.ust");
+        let legacy = [
+            (0, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+            (60, "not_an_address"),
+            (118, "cosmos1qurswpc8qurswpc8qurswpc8qurswpc8nn86qp"),
+            (0x8000014a, "alice_address"),
+        ];
+        for (coin_type, address) in legacy {
+            LEGACY_ADDRESSES
+                .save(
+                    deps.as_mut().storage,
+                    (node.clone(), U64Key::from(coin_type)),
+                    &String::from(address),
+                )
+                .unwrap();
+        }
+        // Setting an address replaces the legacy one
+        let msg = ExecuteMsg::SetAddress {
+            node: node.clone(),
+            coin_type: 118,
+            address: String::from("cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu"),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner_address", &[]),
+            msg,
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::MigrateAddresses {
+            start_after: None,
+            limit: Some(2),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap();
+        let last = format!(
+            "{}{}{}",
+            hex::encode((node.len() as u16).to_be_bytes()),
+            hex::encode(&node),
+            hex::encode(60u64.to_be_bytes())
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "migrate_addresses"),
+                attr("migrated", "1"),
+                attr("invalid", format!("{}:60", hex::encode(&node))),
+                attr("last", last.clone()),
+            ]
+        );
+        let msg = ExecuteMsg::MigrateAddresses {
+            start_after: Some(last),
+            limit: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(res.attributes[1], attr("migrated", "1"));
+        assert_eq!(res.attributes[2], attr("invalid", ""));
+
+        let expected = [
+            (0, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+            (118, "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu"),
+            (0x8000014a, "alice_address"),
+        ];
+        for (coin_type, address) in expected {
+            let query_msg = QueryMsg::GetAddress {
+                node: node.clone(),
+                coin_type,
+            };
+            let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+            let res: AddressResponse = from_binary(&res).unwrap();
+            assert_eq!(res.address, address);
+        }
+
+        // The invalid address is kept for its owner to fix
+        let query_msg = QueryMsg::GetAddress {
+            node: node.clone(),
+            coin_type: 60,
+        };
+        query(deps.as_ref(), mock_env(), query_msg).unwrap_err();
+        assert_eq!(
+            LEGACY_ADDRESSES
+                .range(deps.as_ref().storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<(Vec<u8>, String)>>>()
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Migrating again leaves the records alone
+        let msg = ExecuteMsg::MigrateAddresses {
+            start_after: None,
+            limit: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(res.attributes[1], attr("migrated", "0"));
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        let query_msg = QueryMsg::GetTerraAddress { node };
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let res: AddressResponse = from_binary(&res).unwrap();
+        assert_eq!(res.address, "alice_address");
+    }
+
+    #[test]
+    fn test_set_name() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            interface_id: 1,
+            registry_address: String::from("registry_address"),
+        };
+        let info = mock_info("owner", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Non node owner cannot set name
+        let msg = ExecuteMsg::SetName {
+            node: get_reverse_node("alice_address"),
+            name: String::from("alice.ust"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &coins(0, "uusd")),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotNodeOwner {
+                sender: String::from("anyone"),
+                node: format!("{:?}", get_reverse_node("alice_address"))
+            }
+        );
+
+        // Set name
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner_address", &coins(0, "uusd")),
+            msg,
+        )
+        .unwrap();
+
+        let query_msg = QueryMsg::GetName {
+            node: get_reverse_node("alice_address"),
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let res: NameResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            NameResponse {
+                name: String::from("alice.ust")
+            },
+            res
+        );
+    }
+
+    #[test]
+    fn test_get_records() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            interface_id: 1,
+            registry_address: String::from("registry_address"),
+        };
+        let info = mock_info("owner", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("owner_address", &coins(0, "uusd"));
+        let msgs = vec![
+            ExecuteMsg::SetTerraAddress {
+                node: namehash("alice.ust"),
+                address: String::from("alice_address"),
+            },
+            ExecuteMsg::SetTextData {
+                node: namehash("alice.ust"),
+                key: String::from("email"),
+                value: String::from("alice@tns.money"),
+            },
+            ExecuteMsg::SetContentHash {
+                node: namehash("alice.ust"),
+                hash: Vec::from("alice"),
+            },
+            ExecuteMsg::SetTextData {
+                node: namehash("bob.ust"),
+                key: String::from("twitter"),
+                value: String::from("@bob"),
+            },
+        ];
+        for msg in msgs {
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        }
+
+        let query_msg = QueryMsg::GetRecords {
+            node: namehash("alice.ust"),
+            coin_types: vec![0x8000014a, 60],
+            text_keys: vec![String::from("email"), String::from("twitter")],
+            include_content_hash: true,
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let res: RecordsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            RecordsResponse {
+                node: namehash("alice.ust"),
+                addresses: vec![
+                    AddressRecord {
+                        coin_type: 0x8000014a,
+                        address: Some(String::from("alice_address")),
+                    },
+                    AddressRecord {
+                        coin_type: 60,
+                        address: None,
+                    },
+                ],
+                texts: vec![
+                    TextRecord {
+                        key: String::from("email"),
+                        value: Some(String::from("alice@tns.money")),
+                    },
+                    TextRecord {
+                        key: String::from("twitter"),
+                        value: None,
+                    },
+                ],
+                content_hash: Some(Vec::from("alice")),
+            }
+        );
+
+        let query_msg = QueryMsg::GetRecordsBatch {
+            nodes: vec![namehash("alice.ust"), namehash("bob.ust")],
+            coin_types: vec![],
+            text_keys: vec![String::from("twitter")],
+            include_content_hash: false,
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let res: RecordsBatchResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            RecordsBatchResponse {
+                records: vec![
+                    RecordsResponse {
+                        node: namehash("alice.ust"),
+                        addresses: vec![],
+                        texts: vec![TextRecord {
+                            key: String::from("twitter"),
+                            value: None,
+                        }],
+                        content_hash: None,
+                    },
+                    RecordsResponse {
+                        node: namehash("bob.ust"),
+                        addresses: vec![],
+                        texts: vec![TextRecord {
+                            key: String::from("twitter"),
+                            value: Some(String::from("@bob")),
+                        }],
+                        content_hash: None,
+                    },
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn test_list_records() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            interface_id: 1,
+            registry_address: String::from("registry_address"),
+        };
+        let info = mock_info("owner", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("owner_address", &coins(0, "uusd"));
+        for key in ["url", "email", "twitter"] {
+            let msg = ExecuteMsg::SetTextData {
+                node: namehash("alice.ust"),
+                key: String::from(key),
+                value: String::from("value"),
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        }
+        for (coin_type, address) in [
+            (0x8000014a, "alice_address"),
+            (60, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+            (0, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+        ] {
+            let msg = ExecuteMsg::SetAddress {
+                node: namehash("alice.ust"),
+                coin_type,
+                address: String::from(address),
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        }
+        // Records of other nodes should not be listed
+        let msg = ExecuteMsg::SetTextData {
+            node: namehash("bob.ust"),
+            key: String::from("avatar"),
+            value: String::from("value"),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let query_msg = QueryMsg::ListTextKeys {
+            node: namehash("alice.ust"),
+            start_after: None,
+            limit: Some(2),
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let res: TextKeysResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.keys,
+            vec![String::from("email"), String::from("twitter")]
+        );
+
+        let query_msg = QueryMsg::ListTextKeys {
+            node: namehash("alice.ust"),
+            start_after: Some(String::from("twitter")),
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let res: TextKeysResponse = from_binary(&res).unwrap();
+        assert_eq!(res.keys, vec![String::from("url")]);
+
+        let query_msg = QueryMsg::ListAddresses {
+            node: namehash("alice.ust"),
+            start_after: Some(0),
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let res: AddressesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.addresses,
+            vec![
+                CoinAddress {
+                    coin_type: 60,
+                    address: String::from("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+                },
+                CoinAddress {
+                    coin_type: 0x8000014a,
+                    address: String::from("alice_address"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clear_records() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            interface_id: 1,
+            registry_address: String::from("registry_address"),
+        };
+        let info = mock_info("owner", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("owner_address", &coins(0, "uusd"));
+        let msg = ExecuteMsg::SetTerraAddress {
+            node: namehash("alice.ust"),
+            address: String::from("alice_address"),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetTextData {
+            node: namehash("alice.ust"),
+            key: String::from("url"),
+            value: String::from("https://alice.com"),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // Non node owner cannot clear records
+        let msg = ExecuteMsg::ClearRecords {
+            node: namehash("alice.ust"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &coins(0, "uusd")),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotNodeOwner {
+                sender: String::from("anyone"),
+                node: format!("{:?}", namehash("alice.ust"))
+            }
+        );
+
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let query_msg = QueryMsg::GetVersion {
+            node: namehash("alice.ust"),
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let res: VersionResponse = from_binary(&res).unwrap();
+        assert_eq!(res.version, 1);
+
+        let query_msg = QueryMsg::GetTerraAddress {
+            node: namehash("alice.ust"),
+        };
+        assert!(query(deps.as_ref(), mock_env(), query_msg.clone()).is_err());
+        let query_msg_keys = QueryMsg::ListTextKeys {
+            node: namehash("alice.ust"),
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg_keys).unwrap();
+        let res: TextKeysResponse = from_binary(&res).unwrap();
+        assert!(res.keys.is_empty());
+
+        // Records set after clearing belong to the new version
+        let msg = ExecuteMsg::SetTerraAddress {
+            node: namehash("alice.ust"),
+            address: String::from("bob_address"),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let res: AddressResponse = from_binary(&res).unwrap();
+        assert_eq!(res.address, "bob_address");
+    }
+
+    #[test]
+    fn test_multicall() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            interface_id: 1,
+            registry_address: String::from("registry_address"),
+        };
+        let info = mock_info("owner", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let calls = vec![
+            ExecuteMsg::SetTerraAddress {
+                node: namehash("alice.ust"),
+                address: String::from("alice_address"),
+            },
+            ExecuteMsg::SetTextData {
+                node: namehash("alice.ust"),
+                key: String::from("url"),
+                value: String::from("https://alice.com"),
+            },
+            ExecuteMsg::SetContentHash {
+                node: namehash("alice.ust"),
+                hash: vec![1, 2, 3],
+            },
+        ];
+
+        // Non node owner cannot set records
+        let msg = ExecuteMsg::Multicall {
+            calls: calls.clone(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &coins(0, "uusd")),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotNodeOwner {
+                sender: String::from("anyone"),
+                node: format!("{:?}", namehash("alice.ust"))
+            }
+        );
+
+        // Config updates cannot be batched
+        let msg = ExecuteMsg::Multicall {
+            calls: vec![ExecuteMsg::SetConfig {
+                interface_id: 2,
+                registry_address: String::from("registry_address"),
+            }],
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner_address", &coins(0, "uusd")),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotAllowedInMulticall {});
+
+        let msg = ExecuteMsg::Multicall { calls };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner_address", &coins(0, "uusd")),
+            msg,
+        )
+        .unwrap();
+        let node = hex::encode(namehash("alice.ust"));
+        assert_eq!(res.attributes, vec![attr("method", "multicall")]);
+        assert_eq!(
+            res.events,
+            vec![
+                Event::new("multicall_update").add_attributes(vec![
+                    attr("method", "set_address"),
+                    attr("node", node.clone()),
+                    attr("coin_type", 0x8000014a_u64.to_string()),
+                    attr("address", "alice_address"),
+                ]),
+                Event::new("multicall_update").add_attributes(vec![
+                    attr("method", "set_text_data"),
+                    attr("node", node.clone()),
+                    attr("key", "url"),
+                    attr("value", "https://alice.com"),
+                ]),
+                Event::new("multicall_update").add_attributes(vec![
+                    attr("method", "set_content_hash"),
+                    attr("node", node),
+                    attr("hash", "010203"),
+                ]),
+            ]
+        );
+
+        let query_msg = QueryMsg::GetRecords {
+            node: namehash("alice.ust"),
+            coin_types: vec![0x8000014a],
+            text_keys: vec![String::from("url")],
+            include_content_hash: true,
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let res: RecordsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.addresses[0].address,
+            Some(String::from("alice_address"))
+        );
+        assert_eq!(res.texts[0].value, Some(String::from("https://alice.com")));
+        assert_eq!(res.content_hash, Some(vec![1, 2, 3]));
+    }
+
     #[test]
     fn test_set_config() {
         let mut deps = mock_dependencies(&[]);
@@ -215,7 +863,6 @@ mod tests {
         let msg = ExecuteMsg::SetConfig {
             interface_id: 2,
             registry_address: String::from("new_registry_address"),
-            owner: String::from("new_owner"),
         };
         let info = mock_info("owner", &coins(0, "uusd"));
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -228,7 +875,8 @@ mod tests {
             ConfigResponse {
                 interface_id: 2,
                 registry_address: Addr::unchecked(String::from("new_registry_address")),
-                owner: Addr::unchecked(String::from("new_owner"))
+                owner: Addr::unchecked(String::from("owner")),
+                pending_owner: None,
             }
         );
     }
@@ -247,22 +895,21 @@ mod tests {
         let msg = ExecuteMsg::SetConfig {
             interface_id: 2,
             registry_address: String::from("new_registry_address"),
-            owner: String::from("new_owner"),
         };
         let info = mock_info("not_owner", &coins(0, "uusd"));
         let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
 
         assert_eq!(
             err,
-            ContractError::NotOwner {
+            ContractError::Admin(AdminError::MissingRole {
                 sender: String::from("not_owner"),
-                owner: String::from("owner")
-            }
+                role: String::from("registration_admin")
+            })
         );
     }
 
     #[test]
-    fn test_set_config_transfer_owner() {
+    fn test_transfer_ownership() {
         let mut deps = mock_dependencies(&[]);
 
         let msg = InstantiateMsg {
@@ -270,46 +917,166 @@ mod tests {
             registry_address: String::from("registry_address"),
         };
         let info = mock_info("owner", &coins(0, "uusd"));
-        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let msg = ExecuteMsg::SetConfig {
-            interface_id: 2,
-            registry_address: String::from("new_registry_address"),
+        let msg = ExecuteMsg::ProposeOwner {
             owner: String::from("new_owner"),
         };
-        let info = mock_info("owner", &coins(0, "uusd"));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("not_owner", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
-        let msg = QueryMsg::GetConfig {};
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        // Nothing changes until the proposed owner accepts
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
         let res: ConfigResponse = from_binary(&res).unwrap();
         assert_eq!(
             res,
             ConfigResponse {
-                interface_id: 2,
-                registry_address: Addr::unchecked(String::from("new_registry_address")),
-                owner: Addr::unchecked(String::from("new_owner"))
+                interface_id: 1,
+                registry_address: Addr::unchecked("registry_address"),
+                owner: Addr::unchecked("owner"),
+                pending_owner: Some(Addr::unchecked("new_owner")),
             }
         );
 
+        let msg = ExecuteMsg::AcceptOwnership {};
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("not_owner", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Admin(AdminError::NotPendingOwner {
+                sender: String::from("not_owner")
+            })
+        );
+        execute(deps.as_mut(), mock_env(), mock_info("new_owner", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            ConfigResponse {
+                interface_id: 1,
+                registry_address: Addr::unchecked("registry_address"),
+                owner: Addr::unchecked("new_owner"),
+                pending_owner: None,
+            }
+        );
+
+        // Previous owner lost control
         let msg = ExecuteMsg::SetConfig {
-            interface_id: 3,
+            interface_id: 2,
             registry_address: String::from("new_registry_address"),
-            owner: String::from("owner"),
         };
-        let info = mock_info("new_owner", &coins(0, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
+    }
+
+    #[test]
+    fn test_registration_admin() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            interface_id: 1,
+            registry_address: String::from("registry_address"),
+        };
+        let info = mock_info("owner", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let config_msg = ExecuteMsg::SetConfig {
+            interface_id: 2,
+            registry_address: String::from("registry_address"),
+        };
+        let admin_info = mock_info("admin", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            config_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
+
+        let msg = ExecuteMsg::SetRole {
+            role: Role::RegistrationAdmin,
+            address: Some(String::from("admin")),
+        };
+        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
         execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Roles {}).unwrap();
+        let res: RolesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.roles,
+            vec![RoleAddress {
+                role: Role::RegistrationAdmin,
+                address: Addr::unchecked("admin"),
+            }]
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            config_msg.clone(),
+        )
+        .unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(res.interface_id, 2);
 
-        let msg = QueryMsg::GetConfig {};
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        // The registry stays with the owner
+        let msg = ExecuteMsg::SetConfig {
+            interface_id: 2,
+            registry_address: String::from("new_registry_address"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
         let res: ConfigResponse = from_binary(&res).unwrap();
         assert_eq!(
-            res,
-            ConfigResponse {
-                interface_id: 3,
-                registry_address: Addr::unchecked(String::from("new_registry_address")),
-                owner: Addr::unchecked(String::from("owner"))
-            }
+            res.registry_address,
+            Addr::unchecked("new_registry_address")
         );
+        let config_msg = ExecuteMsg::SetConfig {
+            interface_id: 2,
+            registry_address: String::from("new_registry_address"),
+        };
+
+        // Roles can not be batched
+        let msg = ExecuteMsg::Multicall {
+            calls: vec![ExecuteMsg::SetRole {
+                role: Role::RegistrationAdmin,
+                address: None,
+            }],
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::NotAllowedInMulticall {});
+
+        let msg = ExecuteMsg::SetRole {
+            role: Role::RegistrationAdmin,
+            address: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), admin_info, config_msg).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Admin(AdminError::MissingRole { .. })
+        ));
     }
 }
@@ -0,0 +1,65 @@
+use crate::error::ContractError;
+use crate::handler::{
+    accept_ownership, get_config, propose_owner, query_primary_name, query_reverse_node,
+    set_config, set_name,
+};
+use crate::state::{Config, CONFIG};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use tns::reverse_registrar::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let registry_address = deps.api.addr_canonicalize(msg.registry_address.as_str())?;
+    let resolver_address = deps.api.addr_canonicalize(msg.resolver_address.as_str())?;
+    let owner = deps.api.addr_canonicalize(info.sender.as_str())?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            registry_address,
+            resolver_address,
+            owner,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("owner", info.sender))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::SetName { name } => set_name(deps, env, info, name),
+        ExecuteMsg::SetConfig {
+            registry_address,
+            resolver_address,
+        } => set_config(deps, env, info, registry_address, resolver_address),
+        ExecuteMsg::ProposeOwner { owner } => propose_owner(deps, env, info, owner),
+        ExecuteMsg::AcceptOwnership {} => accept_ownership(deps, env, info),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetReverseNode { address } => to_binary(&query_reverse_node(deps, address)?),
+        QueryMsg::GetPrimaryName { address } => to_binary(&query_primary_name(deps, env, address)?),
+        QueryMsg::GetConfig {} => to_binary(&get_config(deps)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    Ok(Response::default())
+}
@@ -0,0 +1,18 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+use tns::admin::AdminError;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("NotOwner: Sender is {sender}, but owner is {owner}.")]
+    NotOwner { sender: String, owner: String },
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+}
@@ -0,0 +1,209 @@
+use crate::error::ContractError;
+use crate::state::CONFIG;
+use cosmwasm_std::{
+    to_binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, QueryRequest, Response, StdResult,
+    WasmMsg, WasmQuery,
+};
+use tns::admin;
+use tns::registry::{
+    ExecuteMsg as RegistryExecuteMsg, QueryMsg as RegistryQueryMsg, RecordResponse,
+};
+use tns::resolver::{
+    AddressResponse, ExecuteMsg as ResolverExecuteMsg, NameResponse, QueryMsg as ResolverQueryMsg,
+};
+use tns::reverse_registrar::{ConfigResponse, PrimaryNameResponse, ReverseNodeResponse};
+use tns::utils::{
+    convert_namehash_to_hex_string, get_label_from_name, get_reverse_node, namehash,
+    ADDR_REVERSE_NAME,
+};
+
+fn only_owner(deps: Deps, info: &MessageInfo) -> Result<bool, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if sender != config.owner {
+        return Err(ContractError::NotOwner {
+            sender: info.sender.to_string(),
+            owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        });
+    }
+    Ok(true)
+}
+
+pub fn set_name(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let registry_address = deps.api.addr_humanize(&config.registry_address)?;
+    let resolver_address = deps.api.addr_humanize(&config.resolver_address)?;
+
+    let label = get_label_from_name(info.sender.as_str());
+    let node = get_reverse_node(info.sender.as_str());
+
+    // Keep this contract as the owner of the reverse node so that it can write the record
+    let set_subnode_owner_registry_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: registry_address.to_string(),
+        msg: to_binary(&RegistryExecuteMsg::SetSubnodeOwner {
+            node: namehash(ADDR_REVERSE_NAME),
+            label,
+            owner: env.contract.address.to_string(),
+        })?,
+        funds: vec![],
+    });
+
+    let registry_set_resolver_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: registry_address.to_string(),
+        msg: to_binary(&RegistryExecuteMsg::SetResolver {
+            node: node.clone(),
+            resolver: Some(resolver_address.to_string()),
+        })?,
+        funds: vec![],
+    });
+
+    let set_name_resolver_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: resolver_address.to_string(),
+        msg: to_binary(&ResolverExecuteMsg::SetName {
+            node: node.clone(),
+            name: name.clone(),
+        })?,
+        funds: vec![],
+    });
+
+    Ok(Response::new()
+        .add_messages(vec![
+            set_subnode_owner_registry_msg,
+            registry_set_resolver_msg,
+            set_name_resolver_msg,
+        ])
+        .add_attribute("method", "set_name")
+        .add_attribute("address", info.sender)
+        .add_attribute("name", name)
+        .add_attribute("node", convert_namehash_to_hex_string(node)))
+}
+
+pub fn set_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    registry_address: String,
+    resolver_address: String,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    let mut config = CONFIG.load(deps.storage)?;
+
+    config.registry_address = deps.api.addr_canonicalize(registry_address.as_str())?;
+    config.resolver_address = deps.api.addr_canonicalize(resolver_address.as_str())?;
+
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_config")
+        .add_attribute("registry_address", registry_address)
+        .add_attribute("resolver_address", resolver_address))
+}
+
+pub fn propose_owner(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    admin::propose_owner(deps.storage, deps.api, &owner)?;
+    Ok(Response::new()
+        .add_attribute("method", "propose_owner")
+        .add_attribute("pending_owner", owner))
+}
+
+pub fn accept_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let owner = admin::accept_ownership(deps.storage, deps.api, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.owner = owner;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("method", "accept_ownership")
+        .add_attribute("owner", info.sender))
+}
+
+pub fn query_reverse_node(deps: Deps, address: String) -> StdResult<ReverseNodeResponse> {
+    let address = deps.api.addr_validate(address.as_str())?;
+    Ok(ReverseNodeResponse {
+        node: get_reverse_node(address.as_str()),
+    })
+}
+
+pub fn query_primary_name(
+    deps: Deps,
+    _env: Env,
+    address: String,
+) -> StdResult<PrimaryNameResponse> {
+    let address = deps.api.addr_validate(address.as_str())?;
+    let config = CONFIG.load(deps.storage)?;
+    let registry_address = deps
+        .api
+        .addr_humanize(&config.registry_address)?
+        .to_string();
+
+    // The reverse record is only trusted if the forward record still points back to the address
+    let name = lookup_name(deps, &registry_address, address.as_str()).filter(|name| {
+        lookup_terra_address(deps, &registry_address, name).as_deref() == Some(address.as_str())
+    });
+    Ok(PrimaryNameResponse { name })
+}
+
+fn lookup_resolver(deps: Deps, registry_address: &str, node: &[u8]) -> Option<String> {
+    let record: RecordResponse = deps
+        .querier
+        .query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: registry_address.to_string(),
+            msg: to_binary(&RegistryQueryMsg::GetRecordByNode {
+                node: node.to_vec(),
+                include_expired: None,
+            })
+            .ok()?,
+        }))
+        .ok()?;
+    Some(record.resolver.to_string())
+}
+
+fn lookup_name(deps: Deps, registry_address: &str, address: &str) -> Option<String> {
+    let node = get_reverse_node(address);
+    let resolver_address = lookup_resolver(deps, registry_address, &node)?;
+    let name_response: NameResponse = deps
+        .querier
+        .query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: resolver_address,
+            msg: to_binary(&ResolverQueryMsg::GetName { node }).ok()?,
+        }))
+        .ok()?;
+    Some(name_response.name)
+}
+
+fn lookup_terra_address(deps: Deps, registry_address: &str, name: &str) -> Option<String> {
+    let node = namehash(name);
+    let resolver_address = lookup_resolver(deps, registry_address, &node)?;
+    let address_response: AddressResponse = deps
+        .querier
+        .query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: resolver_address,
+            msg: to_binary(&ResolverQueryMsg::GetTerraAddress { node }).ok()?,
+        }))
+        .ok()?;
+    Some(address_response.address)
+}
+
+pub fn get_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pending_owner = admin::pending_owner(deps.storage, deps.api)?;
+    Ok(ConfigResponse {
+        registry_address: deps.api.addr_humanize(&config.registry_address)?,
+        resolver_address: deps.api.addr_humanize(&config.resolver_address)?,
+        owner: deps.api.addr_humanize(&config.owner)?,
+        pending_owner,
+    })
+}
@@ -0,0 +1,119 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Addr, Coin, ContractResult, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+};
+use std::collections::HashMap;
+use tns::registry::{QueryMsg as RegistryQueryMsg, RecordResponse};
+use tns::resolver::{AddressResponse, NameResponse, QueryMsg as ResolverQueryMsg};
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+/// this uses our CustomQuerier.
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: custom_querier,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    // node => resolver address at the registry
+    resolvers: HashMap<Vec<u8>, String>,
+    // node => name at the resolver
+    names: HashMap<Vec<u8>, String>,
+    // node => terra address at the resolver
+    terra_addresses: HashMap<Vec<u8>, String>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: _,
+                msg,
+            }) => {
+                if let Ok(RegistryQueryMsg::GetRecordByNode { node, .. }) = from_binary(msg) {
+                    return match self.resolvers.get(&node) {
+                        Some(resolver) => SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&RecordResponse {
+                                owner: Addr::unchecked("owner"),
+                                resolver: Addr::unchecked(resolver),
+                                ttl: 0,
+                            })
+                            .unwrap(),
+                        )),
+                        None => SystemResult::Ok(ContractResult::Err(String::from("not found"))),
+                    };
+                }
+                match from_binary(msg) {
+                    Ok(ResolverQueryMsg::GetName { node }) => match self.names.get(&node) {
+                        Some(name) => SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&NameResponse { name: name.clone() }).unwrap(),
+                        )),
+                        None => SystemResult::Ok(ContractResult::Err(String::from("not found"))),
+                    },
+                    Ok(ResolverQueryMsg::GetTerraAddress { node }) => {
+                        match self.terra_addresses.get(&node) {
+                            Some(address) => SystemResult::Ok(ContractResult::Ok(
+                                to_binary(&AddressResponse {
+                                    address: address.clone(),
+                                })
+                                .unwrap(),
+                            )),
+                            None => {
+                                SystemResult::Ok(ContractResult::Err(String::from("not found")))
+                            }
+                        }
+                    }
+                    _ => panic!("DO NOT ENTER HERE"),
+                }
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            resolvers: HashMap::new(),
+            names: HashMap::new(),
+            terra_addresses: HashMap::new(),
+        }
+    }
+
+    pub fn with_resolver(&mut self, node: Vec<u8>, resolver: &str) {
+        self.resolvers.insert(node, resolver.to_string());
+    }
+
+    pub fn with_name(&mut self, node: Vec<u8>, name: &str) {
+        self.names.insert(node, name.to_string());
+    }
+
+    pub fn with_terra_address(&mut self, node: Vec<u8>, address: &str) {
+        self.terra_addresses.insert(node, address.to_string());
+    }
+}
@@ -0,0 +1,14 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::CanonicalAddr;
+use cw_storage_plus::Item;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub registry_address: CanonicalAddr,
+    pub resolver_address: CanonicalAddr,
+    pub owner: CanonicalAddr,
+}
+
+pub const CONFIG: Item<Config> = Item::new("CONFIG");
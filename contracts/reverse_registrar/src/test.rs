@@ -0,0 +1,266 @@
+mod tests {
+    use crate::contract::{execute, instantiate, query};
+    use crate::error::ContractError;
+    use crate::mock_querier::mock_dependencies;
+    use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{coins, from_binary, to_binary, Addr, CosmosMsg, WasmMsg};
+    use tns::admin::AdminError;
+    use tns::registry::ExecuteMsg as RegistryExecuteMsg;
+    use tns::resolver::ExecuteMsg as ResolverExecuteMsg;
+    use tns::reverse_registrar::{
+        ConfigResponse, ExecuteMsg, InstantiateMsg, PrimaryNameResponse, QueryMsg,
+        ReverseNodeResponse,
+    };
+    use tns::utils::{
+        convert_namehash_to_hex_string, get_label_from_name, get_reverse_node, namehash,
+    };
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registry_address: String::from("registry_address"),
+            resolver_address: String::from("resolver_address"),
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            ConfigResponse {
+                registry_address: Addr::unchecked("registry_address"),
+                resolver_address: Addr::unchecked("resolver_address"),
+                owner: Addr::unchecked("creator"),
+                pending_owner: None,
+            }
+        );
+    }
+
+    // Test with https://swolfeyes.github.io/ethereum-namehash-calculator/
+    #[test]
+    fn test_reverse_node() {
+        assert_eq!(
+            convert_namehash_to_hex_string(namehash("addr.reverse")),
+            "91d1777781884d03a6757a803996e38de2a42967fb37eeaca72729271025a9e2"
+        );
+
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registry_address: String::from("registry_address"),
+            resolver_address: String::from("resolver_address"),
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let query_msg = QueryMsg::GetReverseNode {
+            address: String::from("alice"),
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let res: ReverseNodeResponse = from_binary(&res).unwrap();
+        assert_eq!(res.node, namehash("alice.addr.reverse"));
+    }
+
+    #[test]
+    fn test_set_name() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registry_address: String::from("registry_address"),
+            resolver_address: String::from("resolver_address"),
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetName {
+            name: String::from("alice.ust"),
+        };
+        let info = mock_info("alice", &coins(0, "uusd"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let set_subnode_owner_registry_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("registry_address"),
+            msg: to_binary(&RegistryExecuteMsg::SetSubnodeOwner {
+                node: namehash("addr.reverse"),
+                label: get_label_from_name(&String::from("alice")),
+                owner: String::from(MOCK_CONTRACT_ADDR),
+            })
+            .unwrap(),
+            funds: vec![],
+        });
+
+        let registry_set_resolver_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("registry_address"),
+            msg: to_binary(&RegistryExecuteMsg::SetResolver {
+                node: get_reverse_node("alice"),
+                resolver: Some(String::from("resolver_address")),
+            })
+            .unwrap(),
+            funds: vec![],
+        });
+
+        let set_name_resolver_msg: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("resolver_address"),
+            msg: to_binary(&ResolverExecuteMsg::SetName {
+                node: get_reverse_node("alice"),
+                name: String::from("alice.ust"),
+            })
+            .unwrap(),
+            funds: vec![],
+        });
+
+        assert_eq!(res.messages.len(), 3); // Claim, Set resolver, Set name
+        assert_eq!(res.messages[0].msg, set_subnode_owner_registry_msg);
+        assert_eq!(res.messages[1].msg, registry_set_resolver_msg);
+        assert_eq!(res.messages[2].msg, set_name_resolver_msg);
+    }
+
+    #[test]
+    fn test_get_primary_name() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registry_address: String::from("registry_address"),
+            resolver_address: String::from("resolver_address"),
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let query_msg = QueryMsg::GetPrimaryName {
+            address: String::from("alice"),
+        };
+
+        // No reverse record
+        let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
+        let res: PrimaryNameResponse = from_binary(&res).unwrap();
+        assert_eq!(res, PrimaryNameResponse { name: None });
+
+        // Reverse record without forward record
+        deps.querier
+            .with_resolver(get_reverse_node("alice"), "resolver_address");
+        deps.querier
+            .with_name(get_reverse_node("alice"), "alice.ust");
+        let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
+        let res: PrimaryNameResponse = from_binary(&res).unwrap();
+        assert_eq!(res, PrimaryNameResponse { name: None });
+
+        // Forward record points to someone else
+        deps.querier
+            .with_resolver(namehash("alice.ust"), "resolver_address");
+        deps.querier
+            .with_terra_address(namehash("alice.ust"), "bob");
+        let res = query(deps.as_ref(), mock_env(), query_msg.clone()).unwrap();
+        let res: PrimaryNameResponse = from_binary(&res).unwrap();
+        assert_eq!(res, PrimaryNameResponse { name: None });
+
+        // Forward record points back to the address
+        deps.querier
+            .with_terra_address(namehash("alice.ust"), "alice");
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let res: PrimaryNameResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            PrimaryNameResponse {
+                name: Some(String::from("alice.ust"))
+            }
+        );
+    }
+
+    #[test] // Should return error if set config with non-owner
+    fn test_cannot_set_config_if_not_owner() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registry_address: String::from("registry_address"),
+            resolver_address: String::from("resolver_address"),
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetConfig {
+            registry_address: String::from("new_registry_address"),
+            resolver_address: String::from("new_resolver_address"),
+        };
+        let info = mock_info("not_owner", &coins(0, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotOwner {
+                sender: String::from("not_owner"),
+                owner: String::from("creator")
+            }
+        );
+
+        let info = mock_info("creator", &coins(0, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            ConfigResponse {
+                registry_address: Addr::unchecked("new_registry_address"),
+                resolver_address: Addr::unchecked("new_resolver_address"),
+                owner: Addr::unchecked("creator"),
+                pending_owner: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_transfer_ownership() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            registry_address: String::from("registry_address"),
+            resolver_address: String::from("resolver_address"),
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::ProposeOwner {
+            owner: String::from("new_owner"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("not_owner", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // Nothing changes until the proposed owner accepts
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(res.owner, Addr::unchecked("creator"));
+        assert_eq!(res.pending_owner, Some(Addr::unchecked("new_owner")));
+
+        let msg = ExecuteMsg::AcceptOwnership {};
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("not_owner", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Admin(AdminError::NotPendingOwner {
+                sender: String::from("not_owner")
+            })
+        );
+        execute(deps.as_mut(), mock_env(), mock_info("new_owner", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(res.owner, Addr::unchecked("new_owner"));
+        assert_eq!(res.pending_owner, None);
+
+        // Previous owner lost control
+        let msg = ExecuteMsg::SetConfig {
+            registry_address: String::from("new_registry_address"),
+            resolver_address: String::from("new_resolver_address"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+    }
+}
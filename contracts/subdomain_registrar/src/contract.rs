@@ -0,0 +1,121 @@
+use crate::error::ContractError;
+use crate::handler::{
+    accept_ownership, configure, disable, get_config, get_expires, get_pricing, get_rent_price,
+    is_available, propose_owner, register, renew, set_config,
+};
+use crate::state::{Config, CONFIG};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+};
+use tns::subdomain_registrar::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let registry_address = deps.api.addr_canonicalize(msg.registry_address.as_str())?;
+    let resolver_address = deps.api.addr_canonicalize(msg.resolver_address.as_str())?;
+    let owner = deps.api.addr_canonicalize(info.sender.as_str())?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            registry_address,
+            resolver_address,
+            owner,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("owner", info.sender))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Configure {
+            node,
+            price,
+            denom,
+            max_duration,
+            min_label_length,
+            max_label_length,
+            allowed_characters,
+        } => configure(
+            deps,
+            env,
+            info,
+            node,
+            price,
+            denom,
+            max_duration,
+            min_label_length,
+            max_label_length,
+            allowed_characters,
+        ),
+        ExecuteMsg::Disable { node } => disable(deps, env, info, node),
+        ExecuteMsg::Register {
+            parent_node,
+            label,
+            owner,
+            duration,
+            address,
+        } => register(
+            deps,
+            env,
+            info,
+            parent_node,
+            label,
+            owner,
+            duration,
+            address,
+        ),
+        ExecuteMsg::Renew {
+            parent_node,
+            label,
+            duration,
+        } => renew(deps, env, info, parent_node, label, duration),
+        ExecuteMsg::SetConfig {
+            registry_address,
+            resolver_address,
+        } => set_config(deps, env, info, registry_address, resolver_address),
+        ExecuteMsg::ProposeOwner { owner } => propose_owner(deps, env, info, owner),
+        ExecuteMsg::AcceptOwnership {} => accept_ownership(deps, env, info),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetPricing { node } => to_binary(&get_pricing(deps, node)?),
+        QueryMsg::GetRentPrice {
+            parent_node,
+            label,
+            duration,
+        } => to_binary(
+            &get_rent_price(deps, parent_node, label, duration)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+        ),
+        QueryMsg::GetExpires { parent_node, label } => {
+            to_binary(&get_expires(deps, parent_node, label)?)
+        }
+        QueryMsg::IsAvailable { parent_node, label } => {
+            to_binary(&is_available(deps, env, parent_node, label)?)
+        }
+        QueryMsg::GetConfig {} => to_binary(&get_config(deps)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    Ok(Response::default())
+}
@@ -0,0 +1,39 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+use tns::admin::AdminError;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("NotOwner: Sender is {sender}, but owner is {owner}.")]
+    NotOwner { sender: String, owner: String },
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("NotNodeOwner: Sender {sender} is not node owner of {node}.")]
+    NotNodeOwner { sender: String, node: String },
+
+    #[error("NotApproved: The subdomain registrar is not an operator of {owner}.")]
+    NotApproved { owner: String },
+
+    #[error("SubdomainsNotForSale: Subdomains of {node} are not for sale.")]
+    SubdomainsNotForSale { node: String },
+
+    #[error("UnavailabledName")]
+    UnavailabledName {},
+
+    #[error("InvalidLabel: {label} is not allowed by the parent owner.")]
+    InvalidLabel { label: String },
+
+    #[error("InvalidDuration: Duration {duration} must be between 1 and {max_duration}.")]
+    InvalidDuration { duration: u64, max_duration: u64 },
+
+    #[error("UnexpectedFund: {denom} is not accepted.")]
+    UnexpectedFund { denom: String },
+
+    #[error("InsufficientFund")]
+    InsufficientFund { amount: Uint128, required: Uint128 },
+}
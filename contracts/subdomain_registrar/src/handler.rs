@@ -0,0 +1,482 @@
+use crate::error::ContractError;
+use crate::state::{Config, Pricing, CONFIG, EXPIRES, PRICINGS};
+use cosmwasm_std::{
+    to_binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, QueryRequest, Response, StdResult,
+    Uint128, WasmMsg, WasmQuery,
+};
+use terraswap::asset::{Asset, AssetInfo};
+use tns::admin;
+use tns::registry::{
+    ExecuteMsg as RegistryExecuteMsg, OperatorResponse, QueryMsg as RegistryQueryMsg,
+    RecordResponse,
+};
+use tns::resolver::ExecuteMsg as ResolverExecuteMsg;
+use tns::subdomain_registrar::{
+    ConfigResponse, GetExpiresResponse, IsAvailableResponse, PricingResponse, RentPriceResponse,
+};
+use tns::utils::keccak256;
+use unicode_segmentation::UnicodeSegmentation;
+
+const YEAR: u64 = 31_536_000;
+
+fn only_owner(deps: Deps, info: &MessageInfo) -> Result<bool, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if sender != config.owner {
+        return Err(ContractError::NotOwner {
+            sender: info.sender.to_string(),
+            owner: deps.api.addr_humanize(&config.owner)?.to_string(),
+        });
+    }
+    Ok(true)
+}
+
+fn only_node_owner(deps: Deps, info: &MessageInfo, node: &[u8]) -> Result<bool, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let is_node_owner: bool = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: deps
+            .api
+            .addr_humanize(&config.registry_address)?
+            .to_string(),
+        msg: to_binary(&RegistryQueryMsg::GetIsNodeOwner {
+            node: node.to_vec(),
+            address: info.sender.to_string(),
+        })?,
+    }))?;
+    if !is_node_owner {
+        return Err(ContractError::NotNodeOwner {
+            sender: info.sender.to_string(),
+            node: format!("{:?}", node),
+        });
+    }
+    Ok(true)
+}
+
+fn query_record(
+    deps: Deps,
+    config: &Config,
+    node: &[u8],
+    include_expired: bool,
+) -> StdResult<RecordResponse> {
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: deps
+            .api
+            .addr_humanize(&config.registry_address)?
+            .to_string(),
+        msg: to_binary(&RegistryQueryMsg::GetRecordByNode {
+            node: node.to_vec(),
+            include_expired: Some(include_expired),
+        })?,
+    }))
+}
+
+fn registry_msg(deps: Deps, msg: &RegistryExecuteMsg) -> StdResult<CosmosMsg> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps
+            .api
+            .addr_humanize(&config.registry_address)?
+            .to_string(),
+        msg: to_binary(msg)?,
+        funds: vec![],
+    }))
+}
+
+fn get_subnode(parent_node: &[u8], label: &str) -> Vec<u8> {
+    let label_hash = keccak256(label.as_bytes());
+    keccak256(&[parent_node.to_vec(), label_hash].concat())
+}
+
+/// Loads the pricing of a parent which is still selling subdomains
+fn load_enabled_pricing(deps: Deps, parent_node: &[u8]) -> Result<Pricing, ContractError> {
+    match PRICINGS.may_load(deps.storage, parent_node.to_vec())? {
+        Some(pricing) if pricing.enabled => Ok(pricing),
+        _ => Err(ContractError::SubdomainsNotForSale {
+            node: hex::encode(parent_node),
+        }),
+    }
+}
+
+/// Loads the enabled pricing if it was set by the current owner of the parent
+fn load_sale_pricing(
+    deps: Deps,
+    config: &Config,
+    parent_node: &[u8],
+) -> Result<Pricing, ContractError> {
+    let pricing = load_enabled_pricing(deps, parent_node)?;
+    // Pricing set by a previous owner of the parent does not bind the current one
+    let parent_record = query_record(deps, config, parent_node, false)?;
+    if deps.api.addr_canonicalize(parent_record.owner.as_str())? != pricing.owner {
+        return Err(ContractError::SubdomainsNotForSale {
+            node: hex::encode(parent_node),
+        });
+    }
+    Ok(pricing)
+}
+
+fn validate_label(pricing: &Pricing, label: &str) -> Result<(), ContractError> {
+    let invalid = || ContractError::InvalidLabel {
+        label: label.to_string(),
+    };
+    if label.is_empty() || label.contains('.') {
+        return Err(invalid());
+    }
+    let label_length = label.graphemes(true).count() as u32;
+    if label_length < pricing.min_label_length || label_length > pricing.max_label_length {
+        return Err(invalid());
+    }
+    if let Some(allowed_characters) = &pricing.allowed_characters {
+        if !label.chars().all(|c| allowed_characters.contains(c)) {
+            return Err(invalid());
+        }
+    }
+    Ok(())
+}
+
+fn validate_duration(pricing: &Pricing, duration: u64) -> Result<(), ContractError> {
+    if duration == 0 || duration > pricing.max_duration {
+        return Err(ContractError::InvalidDuration {
+            duration,
+            max_duration: pricing.max_duration,
+        });
+    }
+    Ok(())
+}
+
+fn get_cost(pricing: &Pricing, duration: u64) -> Uint128 {
+    pricing.price.multiply_ratio(duration, YEAR)
+}
+
+/// Checks the payment, sends the price to the parent owner and refunds the excess
+fn collect_payment(
+    deps: Deps,
+    info: &MessageInfo,
+    pricing: &Pricing,
+    duration: u64,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    // Coins of another denom would be stuck in the contract
+    if let Some(unexpected) = info
+        .funds
+        .iter()
+        .find(|fund| fund.denom != pricing.denom && !fund.amount.is_zero())
+    {
+        return Err(ContractError::UnexpectedFund {
+            denom: unexpected.denom.clone(),
+        });
+    }
+    let cost = get_cost(pricing, duration);
+    let amount = info
+        .funds
+        .iter()
+        .filter(|fund| fund.denom == pricing.denom)
+        .map(|fund| fund.amount)
+        .sum::<Uint128>();
+    if amount < cost {
+        return Err(ContractError::InsufficientFund {
+            amount,
+            required: cost,
+        });
+    }
+
+    let native_asset = |amount: Uint128| Asset {
+        info: AssetInfo::NativeToken {
+            denom: pricing.denom.clone(),
+        },
+        amount,
+    };
+    let mut messages = vec![];
+    if !cost.is_zero() {
+        let parent_owner = deps.api.addr_humanize(&pricing.owner)?;
+        messages.push(native_asset(cost).into_msg(&deps.querier, parent_owner)?);
+    }
+    if amount > cost {
+        messages.push(native_asset(amount - cost).into_msg(&deps.querier, info.sender.clone())?);
+    }
+    Ok(messages)
+}
+
+fn is_subnode_available(deps: Deps, env: &Env, config: &Config, subnode: &[u8]) -> StdResult<bool> {
+    if let Some(expires) = EXPIRES.may_load(deps.storage, subnode.to_vec())? {
+        return Ok(expires < env.block.time.seconds());
+    }
+    // Subdomains not sold here belong to whoever the parent owner gave them to
+    Ok(query_record(deps, config, subnode, true).is_err())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn configure(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    node: Vec<u8>,
+    price: Uint128,
+    denom: String,
+    max_duration: u64,
+    min_label_length: u32,
+    max_label_length: u32,
+    allowed_characters: Option<String>,
+) -> Result<Response, ContractError> {
+    only_node_owner(deps.as_ref(), &info, &node)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    // Subnodes are created on behalf of the owner, so the registrar needs to be an operator
+    let operator: OperatorResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: deps
+            .api
+            .addr_humanize(&config.registry_address)?
+            .to_string(),
+        msg: to_binary(&RegistryQueryMsg::GetIsApprovedForAll {
+            owner: info.sender.to_string(),
+            operator: env.contract.address.to_string(),
+        })?,
+    }))?;
+    if !operator.is_approve {
+        return Err(ContractError::NotApproved {
+            owner: info.sender.to_string(),
+        });
+    }
+
+    PRICINGS.save(
+        deps.storage,
+        node.clone(),
+        &Pricing {
+            owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+            price,
+            denom: denom.clone(),
+            max_duration,
+            min_label_length,
+            max_label_length,
+            allowed_characters,
+            enabled: true,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("method", "configure")
+        .add_attribute("node", hex::encode(&node))
+        .add_attribute("owner", info.sender)
+        .add_attribute("price", price)
+        .add_attribute("denom", denom)
+        .add_attribute("max_duration", max_duration.to_string()))
+}
+
+pub fn disable(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    node: Vec<u8>,
+) -> Result<Response, ContractError> {
+    only_node_owner(deps.as_ref(), &info, &node)?;
+    let mut pricing = load_enabled_pricing(deps.as_ref(), &node)?;
+    pricing.enabled = false;
+    PRICINGS.save(deps.storage, node.clone(), &pricing)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "disable")
+        .add_attribute("node", hex::encode(&node)))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn register(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    parent_node: Vec<u8>,
+    label: String,
+    owner: String,
+    duration: u64,
+    address: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let pricing = load_sale_pricing(deps.as_ref(), &config, &parent_node)?;
+    validate_label(&pricing, &label)?;
+    validate_duration(&pricing, duration)?;
+    let subnode = get_subnode(&parent_node, &label);
+    if !is_subnode_available(deps.as_ref(), &env, &config, &subnode)? {
+        return Err(ContractError::UnavailabledName {});
+    }
+    let owner = deps.api.addr_validate(owner.as_str())?;
+    let mut messages = collect_payment(deps.as_ref(), &info, &pricing, duration)?;
+
+    let expires = env.block.time.seconds() + duration;
+    EXPIRES.save(deps.storage, subnode.clone(), &expires)?;
+
+    // Own the subnode first so the resolver accepts the records
+    let resolver_address = deps.api.addr_humanize(&config.resolver_address)?;
+    messages.push(registry_msg(
+        deps.as_ref(),
+        &RegistryExecuteMsg::SetSubnodeOwner {
+            node: parent_node.clone(),
+            label: keccak256(label.as_bytes()),
+            owner: env.contract.address.to_string(),
+        },
+    )?);
+    messages.push(registry_msg(
+        deps.as_ref(),
+        &RegistryExecuteMsg::SetResolver {
+            node: subnode.clone(),
+            resolver: Some(resolver_address.to_string()),
+        },
+    )?);
+    if let Some(address) = address {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: resolver_address.to_string(),
+            msg: to_binary(&ResolverExecuteMsg::SetTerraAddress {
+                node: subnode.clone(),
+                address,
+            })?,
+            funds: vec![],
+        }));
+    }
+    messages.push(registry_msg(
+        deps.as_ref(),
+        &RegistryExecuteMsg::SetOwner {
+            node: subnode.clone(),
+            owner: owner.to_string(),
+        },
+    )?);
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "register")
+        .add_attribute("parent_node", hex::encode(&parent_node))
+        .add_attribute("label", label)
+        .add_attribute("node", hex::encode(&subnode))
+        .add_attribute("owner", owner)
+        .add_attribute("expires", expires.to_string()))
+}
+
+pub fn renew(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    parent_node: Vec<u8>,
+    label: String,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let pricing = load_sale_pricing(deps.as_ref(), &config, &parent_node)?;
+    validate_duration(&pricing, duration)?;
+    let subnode = get_subnode(&parent_node, &label);
+    let current_expires = EXPIRES
+        .may_load(deps.storage, subnode.clone())?
+        .filter(|expires| *expires >= env.block.time.seconds())
+        .ok_or(ContractError::UnavailabledName {})?;
+    let messages = collect_payment(deps.as_ref(), &info, &pricing, duration)?;
+
+    let expires = current_expires + duration;
+    EXPIRES.save(deps.storage, subnode.clone(), &expires)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "renew")
+        .add_attribute("node", hex::encode(&subnode))
+        .add_attribute("expires", expires.to_string()))
+}
+
+pub fn set_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    registry_address: String,
+    resolver_address: String,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.registry_address = deps.api.addr_canonicalize(registry_address.as_str())?;
+    config.resolver_address = deps.api.addr_canonicalize(resolver_address.as_str())?;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_config")
+        .add_attribute("registry_address", registry_address)
+        .add_attribute("resolver_address", resolver_address))
+}
+
+pub fn propose_owner(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+    only_owner(deps.as_ref(), &info)?;
+    admin::propose_owner(deps.storage, deps.api, &owner)?;
+    Ok(Response::new()
+        .add_attribute("method", "propose_owner")
+        .add_attribute("pending_owner", owner))
+}
+
+pub fn accept_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let owner = admin::accept_ownership(deps.storage, deps.api, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.owner = owner;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("method", "accept_ownership")
+        .add_attribute("owner", info.sender))
+}
+
+pub fn get_pricing(deps: Deps, node: Vec<u8>) -> StdResult<PricingResponse> {
+    let pricing = PRICINGS.load(deps.storage, node)?;
+    Ok(PricingResponse {
+        owner: deps.api.addr_humanize(&pricing.owner)?,
+        price: pricing.price,
+        denom: pricing.denom,
+        max_duration: pricing.max_duration,
+        min_label_length: pricing.min_label_length,
+        max_label_length: pricing.max_label_length,
+        allowed_characters: pricing.allowed_characters,
+        enabled: pricing.enabled,
+    })
+}
+
+pub fn get_rent_price(
+    deps: Deps,
+    parent_node: Vec<u8>,
+    label: String,
+    duration: u64,
+) -> Result<RentPriceResponse, ContractError> {
+    let pricing = load_enabled_pricing(deps, &parent_node)?;
+    validate_label(&pricing, &label)?;
+    validate_duration(&pricing, duration)?;
+    Ok(RentPriceResponse {
+        price: get_cost(&pricing, duration),
+        denom: pricing.denom,
+    })
+}
+
+pub fn get_expires(
+    deps: Deps,
+    parent_node: Vec<u8>,
+    label: String,
+) -> StdResult<GetExpiresResponse> {
+    let subnode = get_subnode(&parent_node, &label);
+    let expires = EXPIRES.may_load(deps.storage, subnode)?.unwrap_or(0);
+    Ok(GetExpiresResponse { expires })
+}
+
+pub fn is_available(
+    deps: Deps,
+    env: Env,
+    parent_node: Vec<u8>,
+    label: String,
+) -> StdResult<IsAvailableResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let subnode = get_subnode(&parent_node, &label);
+    let available = is_subnode_available(deps, &env, &config, &subnode)?;
+    Ok(IsAvailableResponse { available })
+}
+
+pub fn get_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pending_owner = admin::pending_owner(deps.storage, deps.api)?;
+    Ok(ConfigResponse {
+        registry_address: deps.api.addr_humanize(&config.registry_address)?,
+        resolver_address: deps.api.addr_humanize(&config.resolver_address)?,
+        owner: deps.api.addr_humanize(&config.owner)?,
+        pending_owner,
+    })
+}
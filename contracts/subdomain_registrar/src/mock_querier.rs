@@ -0,0 +1,127 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Addr, Coin, ContractResult, Decimal, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+};
+use std::collections::HashMap;
+use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
+use tns::registry::{OperatorResponse, QueryMsg as RegistryQueryMsg, RecordResponse};
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+/// this uses our CustomQuerier.
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: custom_querier,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<TerraQueryWrapper>,
+    tax_querier: TaxQuerier,
+    // Owners of the nodes known to the mocked registry
+    records: HashMap<Vec<u8>, String>,
+}
+
+#[derive(Clone, Default)]
+pub struct TaxQuerier {
+    rate: Decimal,
+    caps: HashMap<String, Uint128>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<TerraQueryWrapper> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<TerraQueryWrapper>) -> QuerierResult {
+        match &request {
+            QueryRequest::Custom(TerraQueryWrapper { route, query_data }) => {
+                if &TerraRoute::Treasury == route {
+                    match query_data {
+                        TerraQuery::TaxRate {} => {
+                            let res = TaxRateResponse {
+                                rate: self.tax_querier.rate,
+                            };
+                            SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+                        }
+                        TerraQuery::TaxCap { denom } => {
+                            let cap = self
+                                .tax_querier
+                                .caps
+                                .get(denom)
+                                .copied()
+                                .unwrap_or_default();
+                            let res = TaxCapResponse { cap };
+                            SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+                        }
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
+                } else {
+                    panic!("DO NOT ENTER HERE")
+                }
+            }
+            QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: _,
+                msg,
+            }) => match from_binary(msg) {
+                Ok(RegistryQueryMsg::GetIsNodeOwner { node: _, address }) => SystemResult::Ok(
+                    ContractResult::Ok(to_binary(&(address == "owner_address")).unwrap()),
+                ),
+                Ok(RegistryQueryMsg::GetIsApprovedForAll { owner, operator: _ }) => {
+                    SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&OperatorResponse {
+                            is_approve: owner == "owner_address",
+                        })
+                        .unwrap(),
+                    ))
+                }
+                Ok(RegistryQueryMsg::GetRecordByNode { node, .. }) => match self.records.get(&node)
+                {
+                    Some(owner) => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&RecordResponse {
+                            owner: Addr::unchecked(owner),
+                            resolver: Addr::unchecked("resolver_address"),
+                            ttl: 0,
+                        })
+                        .unwrap(),
+                    )),
+                    None => SystemResult::Ok(ContractResult::Err(String::from("Record not found"))),
+                },
+                _ => panic!("DO NOT ENTER HERE"),
+            },
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<TerraQueryWrapper>) -> Self {
+        WasmMockQuerier {
+            base,
+            tax_querier: TaxQuerier::default(),
+            records: HashMap::new(),
+        }
+    }
+
+    pub fn with_record(&mut self, node: Vec<u8>, owner: &str) {
+        self.records.insert(node, owner.to_string());
+    }
+}
@@ -0,0 +1,33 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub registry_address: CanonicalAddr,
+    pub resolver_address: CanonicalAddr,
+    pub owner: CanonicalAddr,
+}
+
+pub const CONFIG: Item<Config> = Item::new("CONFIG");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Pricing {
+    /// Owner of the parent node when the pricing was set, sales stop if the node changes hands
+    pub owner: CanonicalAddr,
+    pub price: Uint128,
+    pub denom: String,
+    pub max_duration: u64,
+    pub min_label_length: u32,
+    pub max_label_length: u32,
+    pub allowed_characters: Option<String>,
+    pub enabled: bool,
+}
+
+// Keyed by parent node
+pub const PRICINGS: Map<Vec<u8>, Pricing> = Map::new("PRICINGS");
+
+// Expiry of the subdomains sold by this contract, keyed by subnode
+pub const EXPIRES: Map<Vec<u8>, u64> = Map::new("EXPIRES");
@@ -0,0 +1,545 @@
+#[cfg(test)]
+mod tests {
+    use crate::contract::{execute, instantiate, query};
+    use crate::error::ContractError;
+    use crate::mock_querier::mock_dependencies;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{
+        coins, from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, OwnedDeps, Uint128, WasmMsg,
+    };
+    use tns::admin::AdminError;
+    use tns::registry::ExecuteMsg as RegistryExecuteMsg;
+    use tns::resolver::ExecuteMsg as ResolverExecuteMsg;
+    use tns::subdomain_registrar::{
+        ConfigResponse, ExecuteMsg, GetExpiresResponse, InstantiateMsg, IsAvailableResponse,
+        PricingResponse, QueryMsg, RentPriceResponse,
+    };
+    use tns::utils::{get_label_from_name, namehash};
+
+    use crate::mock_querier::WasmMockQuerier;
+
+    const YEAR: u64 = 31_536_000;
+
+    fn setup_contract() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        deps.querier
+            .with_record(namehash("ourapp.ust"), "owner_address");
+        let msg = InstantiateMsg {
+            registry_address: String::from("registry_address"),
+            resolver_address: String::from("resolver_address"),
+        };
+        let info = mock_info("creator", &coins(0, "uusd"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::Configure {
+            node: namehash("ourapp.ust"),
+            price: Uint128::from(10_000_000u128),
+            denom: String::from("uusd"),
+            max_duration: 2 * YEAR,
+            min_label_length: 3,
+            max_label_length: 10,
+            allowed_characters: Some(String::from("abcdefghijklmnopqrstuvwxyz0123456789-")),
+        };
+        let info = mock_info("owner_address", &coins(0, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        deps
+    }
+
+    fn register_msg(label: &str, duration: u64) -> ExecuteMsg {
+        ExecuteMsg::Register {
+            parent_node: namehash("ourapp.ust"),
+            label: String::from(label),
+            owner: String::from("alice"),
+            duration,
+            address: Some(String::from("alice")),
+        }
+    }
+
+    fn query_available(
+        deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+        label: &str,
+        seconds: u64,
+    ) -> bool {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(seconds);
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::IsAvailable {
+                parent_node: namehash("ourapp.ust"),
+                label: String::from(label),
+            },
+        )
+        .unwrap();
+        let value: IsAvailableResponse = from_binary(&res).unwrap();
+        value.available
+    }
+
+    #[test]
+    fn test_configure() {
+        let mut deps = setup_contract();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPricing {
+                node: namehash("ourapp.ust"),
+            },
+        )
+        .unwrap();
+        let value: PricingResponse = from_binary(&res).unwrap();
+        assert_eq!("owner_address", value.owner.as_str());
+        assert_eq!(Uint128::from(10_000_000u128), value.price);
+        assert!(value.enabled);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetRentPrice {
+                parent_node: namehash("ourapp.ust"),
+                label: String::from("alice"),
+                duration: YEAR / 2,
+            },
+        )
+        .unwrap();
+        let value: RentPriceResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(5_000_000u128), value.price);
+        assert_eq!("uusd", value.denom);
+
+        // Only the node owner can configure its subdomains
+        let msg = ExecuteMsg::Configure {
+            node: namehash("ourapp.ust"),
+            price: Uint128::zero(),
+            denom: String::from("uusd"),
+            max_duration: YEAR,
+            min_label_length: 1,
+            max_label_length: 64,
+            allowed_characters: None,
+        };
+        let info = mock_info("alice", &coins(0, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotNodeOwner {
+                sender: String::from("alice"),
+                node: format!("{:?}", namehash("ourapp.ust")),
+            }
+        );
+
+        let msg = ExecuteMsg::Disable {
+            node: namehash("ourapp.ust"),
+        };
+        let info = mock_info("owner_address", &coins(0, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("bob", &coins(10_000_000, "uusd"));
+        let err =
+            execute(deps.as_mut(), mock_env(), info, register_msg("alice", YEAR)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SubdomainsNotForSale {
+                node: hex::encode(namehash("ourapp.ust")),
+            }
+        );
+    }
+
+    #[test]
+    fn test_register() {
+        let mut deps = setup_contract();
+
+        let info = mock_info("bob", &coins(9_999_999, "uusd"));
+        let err =
+            execute(deps.as_mut(), mock_env(), info, register_msg("alice", YEAR)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientFund {
+                amount: Uint128::from(9_999_999u128),
+                required: Uint128::from(10_000_000u128),
+            }
+        );
+
+        for label in ["al", "Alice", "alice.bob", "abcdefghijk"] {
+            let info = mock_info("bob", &coins(10_000_000, "uusd"));
+            let err =
+                execute(deps.as_mut(), mock_env(), info, register_msg(label, YEAR)).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::InvalidLabel {
+                    label: String::from(label),
+                }
+            );
+        }
+
+        let info = mock_info("bob", &coins(30_000_000, "uusd"));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            register_msg("alice", 3 * YEAR),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidDuration {
+                duration: 3 * YEAR,
+                max_duration: 2 * YEAR,
+            }
+        );
+
+        let info = mock_info("bob", &coins(10_000_000, "uusd"));
+        let res = execute(deps.as_mut(), mock_env(), info, register_msg("alice", YEAR)).unwrap();
+        let node = namehash("alice.ourapp.ust");
+        let registry_msg = |msg: &RegistryExecuteMsg| {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("registry_address"),
+                msg: to_binary(msg).unwrap(),
+                funds: vec![],
+            })
+        };
+        let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(
+            messages,
+            vec![
+                // Revenue goes to the parent owner
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: String::from("owner_address"),
+                    amount: coins(10_000_000, "uusd"),
+                }),
+                registry_msg(&RegistryExecuteMsg::SetSubnodeOwner {
+                    node: namehash("ourapp.ust"),
+                    label: get_label_from_name(&String::from("alice")),
+                    owner: String::from(MOCK_CONTRACT_ADDR),
+                }),
+                registry_msg(&RegistryExecuteMsg::SetResolver {
+                    node: node.clone(),
+                    resolver: Some(String::from("resolver_address")),
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: String::from("resolver_address"),
+                    msg: to_binary(&ResolverExecuteMsg::SetTerraAddress {
+                        node: node.clone(),
+                        address: String::from("alice"),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                registry_msg(&RegistryExecuteMsg::SetOwner {
+                    node: node.clone(),
+                    owner: String::from("alice"),
+                }),
+            ]
+        );
+        assert!(!query_available(&deps, "alice", 0));
+
+        let info = mock_info("bob", &coins(10_000_000, "uusd"));
+        let err =
+            execute(deps.as_mut(), mock_env(), info, register_msg("alice", YEAR)).unwrap_err();
+        assert_eq!(err, ContractError::UnavailabledName {});
+
+        // Anyone can renew
+        let msg = ExecuteMsg::Renew {
+            parent_node: namehash("ourapp.ust"),
+            label: String::from("alice"),
+            duration: YEAR,
+        };
+        let info = mock_info("carol", &coins(10_000_000, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetExpires {
+                parent_node: namehash("ourapp.ust"),
+                label: String::from("alice"),
+            },
+        )
+        .unwrap();
+        let value: GetExpiresResponse = from_binary(&res).unwrap();
+        assert_eq!(mock_env().block.time.seconds() + 2 * YEAR, value.expires);
+
+        // Expired subdomains can be bought again
+        assert!(!query_available(&deps, "alice", 2 * YEAR));
+        assert!(query_available(&deps, "alice", 2 * YEAR + 1));
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2 * YEAR + 1);
+        let info = mock_info("bob", &coins(10_000_000, "uusd"));
+        execute(deps.as_mut(), env, info, register_msg("alice", YEAR)).unwrap();
+    }
+
+    #[test]
+    fn test_register_unavailable() {
+        let mut deps = setup_contract();
+
+        // Subdomains created by the parent owner directly are not for sale
+        deps.querier
+            .with_record(namehash("taken.ourapp.ust"), "owner_address");
+        assert!(!query_available(&deps, "taken", 0));
+        let info = mock_info("bob", &coins(10_000_000, "uusd"));
+        let err =
+            execute(deps.as_mut(), mock_env(), info, register_msg("taken", YEAR)).unwrap_err();
+        assert_eq!(err, ContractError::UnavailabledName {});
+
+        // Pricing of a previous parent owner does not apply
+        deps.querier
+            .with_record(namehash("ourapp.ust"), "new_owner");
+        let info = mock_info("bob", &coins(10_000_000, "uusd"));
+        let err =
+            execute(deps.as_mut(), mock_env(), info, register_msg("alice", YEAR)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SubdomainsNotForSale {
+                node: hex::encode(namehash("ourapp.ust")),
+            }
+        );
+    }
+
+    fn renew_msg(label: &str, duration: u64) -> ExecuteMsg {
+        ExecuteMsg::Renew {
+            parent_node: namehash("ourapp.ust"),
+            label: String::from(label),
+            duration,
+        }
+    }
+
+    fn bank_send(to_address: &str, amount: u128) -> CosmosMsg {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from(to_address),
+            amount: coins(amount, "uusd"),
+        })
+    }
+
+    #[test]
+    fn test_payment() {
+        let mut deps = setup_contract();
+
+        // Other denoms would be kept by the contract
+        let info = mock_info(
+            "bob",
+            &[Coin::new(10_000_000, "uusd"), Coin::new(1, "uluna")],
+        );
+        let err =
+            execute(deps.as_mut(), mock_env(), info, register_msg("alice", YEAR)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnexpectedFund {
+                denom: String::from("uluna"),
+            }
+        );
+        let info = mock_info("bob", &coins(10_000_000, "uluna"));
+        let err =
+            execute(deps.as_mut(), mock_env(), info, register_msg("alice", YEAR)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnexpectedFund {
+                denom: String::from("uluna"),
+            }
+        );
+
+        // The parent owner gets the price, the excess goes back to the sender
+        let info = mock_info("bob", &coins(15_000_000, "uusd"));
+        let res = execute(deps.as_mut(), mock_env(), info, register_msg("alice", YEAR)).unwrap();
+        assert_eq!(res.messages[0].msg, bank_send("owner_address", 10_000_000));
+        assert_eq!(res.messages[1].msg, bank_send("bob", 5_000_000));
+
+        // Renewals are routed the same way
+        let info = mock_info("carol", &coins(4_000_000, "uusd"));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            renew_msg("alice", YEAR / 2),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientFund {
+                amount: Uint128::from(4_000_000u128),
+                required: Uint128::from(5_000_000u128),
+            }
+        );
+        let info = mock_info("carol", &coins(6_000_000, "uusd"));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            renew_msg("alice", YEAR / 2),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|message| message.msg)
+                .collect::<Vec<CosmosMsg>>(),
+            vec![
+                bank_send("owner_address", 5_000_000),
+                bank_send("carol", 1_000_000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pricing_change() {
+        let mut deps = setup_contract();
+        let info = mock_info("bob", &coins(10_000_000, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, register_msg("alice", YEAR)).unwrap();
+
+        // New prices apply to the next purchases only
+        let msg = ExecuteMsg::Configure {
+            node: namehash("ourapp.ust"),
+            price: Uint128::from(20_000_000u128),
+            denom: String::from("uusd"),
+            max_duration: YEAR,
+            min_label_length: 1,
+            max_label_length: 10,
+            allowed_characters: None,
+        };
+        let info = mock_info("owner_address", &coins(0, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(!query_available(&deps, "alice", YEAR));
+
+        let info = mock_info("bob", &coins(10_000_000, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, register_msg("b", YEAR)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientFund {
+                amount: Uint128::from(10_000_000u128),
+                required: Uint128::from(20_000_000u128),
+            }
+        );
+        let info = mock_info("bob", &coins(20_000_000, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, register_msg("b", YEAR)).unwrap();
+
+        let info = mock_info("carol", &coins(20_000_000, "uusd"));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            renew_msg("alice", 2 * YEAR),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidDuration {
+                duration: 2 * YEAR,
+                max_duration: YEAR,
+            }
+        );
+        let info = mock_info("carol", &coins(20_000_000, "uusd"));
+        let res = execute(deps.as_mut(), mock_env(), info, renew_msg("alice", YEAR)).unwrap();
+        assert_eq!(res.messages[0].msg, bank_send("owner_address", 20_000_000));
+
+        // Free subdomains need no payment
+        let msg = ExecuteMsg::Configure {
+            node: namehash("ourapp.ust"),
+            price: Uint128::zero(),
+            denom: String::from("uusd"),
+            max_duration: YEAR,
+            min_label_length: 1,
+            max_label_length: 10,
+            allowed_characters: None,
+        };
+        let info = mock_info("owner_address", &coins(0, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("bob", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, register_msg("c", YEAR)).unwrap();
+        assert_eq!(res.messages.len(), 4);
+    }
+
+    #[test]
+    fn test_parent_owner_change() {
+        let mut deps = setup_contract();
+        let info = mock_info("bob", &coins(10_000_000, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, register_msg("alice", YEAR)).unwrap();
+
+        // Payments of the previous owner's pricing stop once the parent changed hands
+        deps.querier
+            .with_record(namehash("ourapp.ust"), "new_owner");
+        let info = mock_info("carol", &coins(10_000_000, "uusd"));
+        let err = execute(deps.as_mut(), mock_env(), info, renew_msg("alice", YEAR)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SubdomainsNotForSale {
+                node: hex::encode(namehash("ourapp.ust")),
+            }
+        );
+
+        // The previous owner gets sales back with the parent
+        deps.querier
+            .with_record(namehash("ourapp.ust"), "owner_address");
+        let info = mock_info("carol", &coins(10_000_000, "uusd"));
+        let res = execute(deps.as_mut(), mock_env(), info, renew_msg("alice", YEAR)).unwrap();
+        assert_eq!(res.messages[0].msg, bank_send("owner_address", 10_000_000));
+    }
+
+    #[test]
+    fn test_transfer_ownership() {
+        let mut deps = setup_contract();
+
+        let msg = ExecuteMsg::ProposeOwner {
+            owner: String::from("new_owner"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("not_owner", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // Nothing changes until the proposed owner accepts
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            ConfigResponse {
+                registry_address: Addr::unchecked("registry_address"),
+                resolver_address: Addr::unchecked("resolver_address"),
+                owner: Addr::unchecked("creator"),
+                pending_owner: Some(Addr::unchecked("new_owner")),
+            }
+        );
+
+        let msg = ExecuteMsg::AcceptOwnership {};
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("not_owner", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Admin(AdminError::NotPendingOwner {
+                sender: String::from("not_owner")
+            })
+        );
+        execute(deps.as_mut(), mock_env(), mock_info("new_owner", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::SetConfig {
+            registry_address: String::from("registry_address"),
+            resolver_address: String::from("new_resolver_address"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+        execute(deps.as_mut(), mock_env(), mock_info("new_owner", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let res: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            ConfigResponse {
+                registry_address: Addr::unchecked("registry_address"),
+                resolver_address: Addr::unchecked("new_resolver_address"),
+                owner: Addr::unchecked("new_owner"),
+                pending_owner: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use cosmwasm_std::{Addr, Api, CanonicalAddr, StdError, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+// Proposed owner, who becomes the owner by accepting
+pub const PENDING_OWNER: Item<CanonicalAddr> = Item::new("PENDING_OWNER");
+// Holder of each delegated role
+pub const ROLES: Map<String, CanonicalAddr> = Map::new("ROLES");
+
+/// Admin permissions the owner can delegate, the owner always holds every role.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Prices, premiums and accepted payments
+    PricingAdmin,
+    /// Registration switches, name lists, owner registrations, controllers and the settings of
+    /// the registrar, registry and resolver
+    RegistrationAdmin,
+    /// Collected fees and their distribution
+    Treasury,
+}
+
+impl Role {
+    /// Every role, in the order `Roles` queries list them
+    pub fn all() -> [Role; 3] {
+        [Role::PricingAdmin, Role::RegistrationAdmin, Role::Treasury]
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Role::PricingAdmin => write!(f, "pricing_admin"),
+            Role::RegistrationAdmin => write!(f, "registration_admin"),
+            Role::Treasury => write!(f, "treasury"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoleAddress {
+    pub role: Role,
+    pub address: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RolesResponse {
+    pub roles: Vec<RoleAddress>,
+}
+
+/// Failed admin checks, wrapped by the error of each contract.
+#[derive(Error, Debug, PartialEq)]
+pub enum AdminError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("NotPendingOwner: Sender {sender} is not the pending owner.")]
+    NotPendingOwner { sender: String },
+
+    #[error("MissingRole: Sender {sender} is neither the owner nor the {role}.")]
+    MissingRole { sender: String, role: String },
+
+    #[error("UnsupportedRole: {role} is not used by this contract.")]
+    UnsupportedRole { role: String },
+}
+
+/// Fails unless `sender` is the contract `owner` or holds `role`, the owner holds every role.
+pub fn only_role(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    owner: &CanonicalAddr,
+    sender: &Addr,
+    role: Role,
+) -> Result<(), AdminError> {
+    let canonical_sender = api.addr_canonicalize(sender.as_str())?;
+    if &canonical_sender == owner
+        || ROLES.may_load(storage, role.to_string())? == Some(canonical_sender)
+    {
+        return Ok(());
+    }
+    Err(AdminError::MissingRole {
+        sender: sender.to_string(),
+        role: role.to_string(),
+    })
+}
+
+/// First step of an ownership transfer, the caller checks the sender is the owner.
+pub fn propose_owner(storage: &mut dyn Storage, api: &dyn Api, owner: &str) -> StdResult<()> {
+    let pending_owner = api.addr_canonicalize(owner)?;
+    PENDING_OWNER.save(storage, &pending_owner)
+}
+
+/// Clears the transfer proposed to `sender` and returns the new owner the contract must store.
+pub fn accept_ownership(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    sender: &Addr,
+) -> Result<CanonicalAddr, AdminError> {
+    let canonical_sender = api.addr_canonicalize(sender.as_str())?;
+    if PENDING_OWNER.may_load(storage)? != Some(canonical_sender.clone()) {
+        return Err(AdminError::NotPendingOwner {
+            sender: sender.to_string(),
+        });
+    }
+    PENDING_OWNER.remove(storage);
+    Ok(canonical_sender)
+}
+
+pub fn pending_owner(storage: &dyn Storage, api: &dyn Api) -> StdResult<Option<Addr>> {
+    match PENDING_OWNER.may_load(storage)? {
+        Some(pending_owner) => Ok(Some(api.addr_humanize(&pending_owner)?)),
+        None => Ok(None),
+    }
+}
+
+/// Delegates `role` to `address`, or revokes it if unset. Only the `supported` roles of the
+/// contract are accepted, the caller checks the sender is the owner.
+pub fn set_role(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    supported: &[Role],
+    role: &Role,
+    address: Option<&str>,
+) -> Result<(), AdminError> {
+    if !supported.contains(role) {
+        return Err(AdminError::UnsupportedRole {
+            role: role.to_string(),
+        });
+    }
+    match address {
+        Some(address) => {
+            let address = api.addr_canonicalize(address)?;
+            ROLES.save(storage, role.to_string(), &address)?;
+        }
+        None => ROLES.remove(storage, role.to_string()),
+    }
+    Ok(())
+}
+
+pub fn get_roles(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    supported: &[Role],
+) -> StdResult<RolesResponse> {
+    let mut roles = vec![];
+    for role in supported {
+        if let Some(address) = ROLES.may_load(storage, role.to_string())? {
+            roles.push(RoleAddress {
+                role: role.clone(),
+                address: api.addr_humanize(&address)?,
+            });
+        }
+    }
+    Ok(RolesResponse { roles })
+}
@@ -1,7 +1,12 @@
+pub mod admin;
 mod error;
+pub mod name_wrapper;
+pub mod oracle;
 pub mod registrar;
 pub mod registry;
 pub mod resolver;
+pub mod reverse_registrar;
+pub mod subdomain_registrar;
 pub mod utils;
 
 pub use crate::error::ContractError;
@@ -0,0 +1,203 @@
+use cosmwasm_std::{Addr, Binary};
+use cw0::Expiration;
+use cw721::Cw721ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Owner controlled fuses, they can only be burned once the parent gave up control
+pub const CANNOT_UNWRAP: u32 = 1;
+pub const CANNOT_BURN_FUSES: u32 = 1 << 1;
+pub const CANNOT_TRANSFER: u32 = 1 << 2;
+pub const CANNOT_SET_RESOLVER: u32 = 1 << 3;
+pub const CANNOT_SET_TTL: u32 = 1 << 4;
+pub const CANNOT_CREATE_SUBDOMAIN: u32 = 1 << 5;
+// Parent controlled fuses
+pub const PARENT_CANNOT_CONTROL: u32 = 1 << 16;
+
+pub const OWNER_CONTROLLED_FUSES: u32 = 0xffff;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub registry_address: String,
+    pub registrar_address: String,
+
+    /// Name of the NFT contract
+    pub name: String,
+    /// Symbol of the NFT contract
+    pub symbol: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Wraps a node owned by the sender in the registry and mints it to `owner`.
+    /// The wrapper has to be approved as registry operator of the sender beforehand.
+    /// Names of the registrar are wrapped by sending their token with `ReceiveMsg::Wrap`.
+    Wrap {
+        parent_node: Vec<u8>,
+        label: Vec<u8>,
+        owner: String,
+        resolver: Option<String>,
+    },
+    /// Wraps a registrar name, the registrar token stays with the wrapper until unwrapped.
+    ReceiveNft(Cw721ReceiveMsg),
+    /// Burns the wrapped token and gives the registry node back to `new_owner`.
+    /// Registrar names also get their registrar token back.
+    Unwrap {
+        node: Vec<u8>,
+        new_owner: String,
+    },
+    /// Burns owner controlled fuses of a wrapped node.
+    SetFuses {
+        node: Vec<u8>,
+        fuses: u32,
+    },
+    /// Burns fuses of a child node and extends its expiry, called by the parent owner.
+    SetChildFuses {
+        parent_node: Vec<u8>,
+        label: Vec<u8>,
+        fuses: u32,
+        expiry: u64,
+    },
+    /// Creates or replaces a wrapped child node, called by the parent owner.
+    SetSubnodeOwner {
+        parent_node: Vec<u8>,
+        label: Vec<u8>,
+        owner: String,
+        fuses: u32,
+        expiry: u64,
+    },
+    /// Extends the fuse expiry of a wrapped registrar name after it was renewed.
+    SyncExpiry {
+        node: Vec<u8>,
+    },
+    SetResolver {
+        node: Vec<u8>,
+        resolver: Option<String>,
+    },
+    SetTTL {
+        node: Vec<u8>,
+        ttl: u64,
+    },
+    SetConfig {
+        registry_address: String,
+        registrar_address: String,
+    },
+    /// Proposes a new owner, who takes over with `AcceptOwnership`
+    ProposeOwner {
+        owner: String,
+    },
+    AcceptOwnership {},
+
+    /// Transfer is a base message to move a token to another account without triggering actions
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    /// Send is a base message to transfer a token to a contract and trigger an action
+    /// on the receiving contract.
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    /// Allows operator to transfer / send the token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted Approval
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    /// Allows operator to transfer / send any token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted ApproveAll permission
+    RevokeAll {
+        operator: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    /// Mints the wrapped name to `owner`, its fuses expire with the registration.
+    Wrap {
+        owner: String,
+        resolver: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Fuses are reported as 0 once the expiry has passed.
+    /// Return type: WrappedDataResponse
+    GetData {
+        node: Vec<u8>,
+    },
+    GetConfig {},
+
+    /// Return the owner of the given token, error if token does not exist
+    /// Return type: OwnerOfResponse
+    OwnerOf {
+        token_id: String,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
+    },
+    /// List all operators that can access all of the owner's tokens.
+    /// Return type: `ApprovedForAllResponse`
+    ApprovedForAll {
+        owner: String,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Total number of tokens issued
+    NumTokens {},
+    /// Returns top-level metadata about the contract: `ContractInfoResponse`
+    ContractInfo {},
+    /// Returns metadata about one particular token: `NftInfoResponse`
+    NftInfo {
+        token_id: String,
+    },
+    /// Returns all tokens owned by the given address, [] if unset.
+    /// Return type: TokensResponse.
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Lists all token_ids controlled by the contract.
+    /// Return type: TokensResponse.
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WrappedDataResponse {
+    pub owner: Addr,
+    pub fuses: u32,
+    pub expiry: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub registry_address: Addr,
+    pub registrar_address: Addr,
+    pub owner: Addr,
+    pub pending_owner: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
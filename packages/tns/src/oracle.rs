@@ -0,0 +1,18 @@
+use cosmwasm_std::Decimal;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terraswap::asset::AssetInfo;
+
+/// Interface of price feed contracts used to convert prices out of uusd.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Return type: ExchangeRateResponse
+    ExchangeRate { asset: AssetInfo },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExchangeRateResponse {
+    /// Amount of the asset worth one uusd
+    pub rate: Decimal,
+}
@@ -1,3 +1,4 @@
+use crate::admin::Role;
 use cosmwasm_std::{Addr, Binary, BlockInfo};
 use cw0::Expiration;
 use cw_storage_plus::{Index, IndexList, MultiIndex};
@@ -31,6 +32,8 @@ pub enum QueryMsg {
     GetRegistry {},
     GetGracePeriod {},
     GetConfig {},
+    /// Return type: RolesResponse
+    Roles {},
     Minter {},
 
     /// Return the owner of the given token, error if token does not exist
@@ -105,7 +108,17 @@ pub enum ExecuteMsg<T> {
     SetConfig {
         grace_period: u64,
         registry_address: String,
-        owner: String
+    },
+    /// Proposes a new owner, who takes over with `AcceptOwnership`
+    ProposeOwner {
+        owner: String,
+    },
+    AcceptOwnership {},
+    /// Delegates `role` to `address`, or revokes it if unset.
+    /// Only the registration admin, who manages controllers and the grace period, is supported.
+    SetRole {
+        role: Role,
+        address: Option<String>,
     },
     Renew {
         id: String,
@@ -115,6 +128,10 @@ pub enum ExecuteMsg<T> {
         id: String,
         owner: String,
     },
+    /// Copies the expiry of `ids` to the registry, for names registered before it tracked them
+    SyncExpiries {
+        ids: Vec<String>,
+    },
 
     /// Transfer is a base message to move a token to another account without triggering actions
     TransferNft {
@@ -184,6 +201,7 @@ pub struct ConfigResponse {
     pub grace_period: u64,
     pub registry_address: Addr,
     pub owner: Addr,
+    pub pending_owner: Option<Addr>,
     pub base_node: Vec<u8>,
     pub base_name: String,
 }
@@ -1,3 +1,4 @@
+use crate::admin::Role;
 use cosmwasm_std::Addr;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -31,25 +32,51 @@ pub enum ExecuteMsg {
         node: Vec<u8>,
         ttl: u64,
     },
+    /// Sets when `label` under `node` expires, called by the owner of `node`.
+    /// Expired nodes lose their owner until the expiry is extended.
+    SetSubnodeExpiry {
+        node: Vec<u8>,
+        label: Vec<u8>,
+        expires: u64,
+    },
     SetApprovalForAll {
         node: Vec<u8>,
         operator: String,
         approved: bool,
     },
+    /// The default resolver is only given to new records, the registration admin can change it
     SetConfig {
         default_resolver: String,
-        owner: String
-    }
+    },
+    /// Proposes a new owner, who takes over with `AcceptOwnership`
+    ProposeOwner {
+        owner: String,
+    },
+    AcceptOwnership {},
+    /// Delegates `role` to `address`, or revokes it if unset.
+    /// The registration admin manages the default resolver.
+    SetRole {
+        role: Role,
+        address: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
+    /// Expired nodes do not resolve
     GetRecord { name: String },
-    GetRecordByNode { node: Vec<u8> },
+    /// Expired nodes are only returned with `include_expired`
+    GetRecordByNode {
+        node: Vec<u8>,
+        include_expired: Option<bool>,
+    },
     GetIsNodeOwner { node: Vec<u8>, address: String },
     GetIsApprovedForAll { owner: String, operator: String },
+    GetExpires { node: Vec<u8> },
     GetConfig {},
+    /// Return type: RolesResponse
+    Roles {},
 }
 
 // We define a custom struct for each query response
@@ -60,6 +87,12 @@ pub struct RecordResponse {
     pub ttl: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GetExpiresResponse {
+    /// 0 if the node never expires
+    pub expires: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct OperatorResponse {
     pub is_approve: bool,
@@ -69,6 +102,7 @@ pub struct OperatorResponse {
 pub struct ConfigResponse {
     pub default_resolver: Addr,
     pub owner: Addr,
+    pub pending_owner: Option<Addr>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -1,3 +1,4 @@
+use crate::admin::Role;
 use cosmwasm_std::Addr;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -29,21 +30,101 @@ pub enum ExecuteMsg {
         node: Vec<u8>,
         hash: Vec<u8>,
     },
+    SetName {
+        node: Vec<u8>,
+        name: String,
+    },
+    /// Drops every record of the node by moving it to a new record version.
+    ClearRecords {
+        node: Vec<u8>,
+    },
+    /// Applies several record updates in one transaction, authorizing each node once.
+    /// Only record updates are allowed, admin messages and nested `Multicall` are rejected.
+    Multicall {
+        calls: Vec<ExecuteMsg>,
+    },
+    /// Moves up to `limit` addresses stored as text before coin type validation to their
+    /// binary form, anyone can call it. `start_after` is the `last` attribute of the previous
+    /// call. Addresses invalid for their coin type stay as they are and are listed in `invalid`.
+    MigrateAddresses {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The registration admin can change the interface id, only the owner can change the registry
     SetConfig {
         interface_id: u64,
         registry_address: String,
+    },
+    /// Proposes a new owner, who takes over with `AcceptOwnership`
+    ProposeOwner {
         owner: String,
     },
+    AcceptOwnership {},
+    /// Delegates `role` to `address`, or revokes it if unset.
+    /// The registration admin manages the interface id.
+    SetRole {
+        role: Role,
+        address: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    GetAddress { node: Vec<u8>, coin_type: u64 },
-    GetTextData { node: Vec<u8>, key: String },
-    GetTerraAddress { node: Vec<u8> },
-    GetContentHash { node: Vec<u8> },
+    GetAddress {
+        node: Vec<u8>,
+        coin_type: u64,
+    },
+    GetTextData {
+        node: Vec<u8>,
+        key: String,
+    },
+    GetTerraAddress {
+        node: Vec<u8>,
+    },
+    GetContentHash {
+        node: Vec<u8>,
+    },
+    GetName {
+        node: Vec<u8>,
+    },
+    /// Return type: VersionResponse
+    GetVersion {
+        node: Vec<u8>,
+    },
+    /// Return the requested records of a node in one response, missing entries are `None`.
+    /// Return type: RecordsResponse
+    GetRecords {
+        node: Vec<u8>,
+        coin_types: Vec<u64>,
+        text_keys: Vec<String>,
+        include_content_hash: bool,
+    },
+    /// Same as `GetRecords` for several nodes at once.
+    /// Return type: RecordsBatchResponse
+    GetRecordsBatch {
+        nodes: Vec<Vec<u8>>,
+        coin_types: Vec<u64>,
+        text_keys: Vec<String>,
+        include_content_hash: bool,
+    },
+    /// Lists the text keys set for a node in lexicographical ordering.
+    /// Return type: TextKeysResponse
+    ListTextKeys {
+        node: Vec<u8>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Lists the addresses set for a node ordered by coin type.
+    /// Return type: AddressesResponse
+    ListAddresses {
+        node: Vec<u8>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
     GetConfig {},
+    /// Return type: RolesResponse
+    Roles {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -61,11 +142,66 @@ pub struct ContentHashResponse {
     pub hash: Vec<u8>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NameResponse {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AddressRecord {
+    pub coin_type: u64,
+    pub address: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TextRecord {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RecordsResponse {
+    pub node: Vec<u8>,
+    pub addresses: Vec<AddressRecord>,
+    pub texts: Vec<TextRecord>,
+    /// Always `None` unless `include_content_hash` is set
+    pub content_hash: Option<Vec<u8>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RecordsBatchResponse {
+    pub records: Vec<RecordsResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VersionResponse {
+    pub version: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TextKeysResponse {
+    /// If there are more than `limit`, use the last key as `start_after` in future queries
+    pub keys: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CoinAddress {
+    pub coin_type: u64,
+    pub address: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AddressesResponse {
+    /// If there are more than `limit`, use the last coin type as `start_after` in future queries
+    pub addresses: Vec<CoinAddress>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub interface_id: u64,
     pub registry_address: Addr,
     pub owner: Addr,
+    pub pending_owner: Option<Addr>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -0,0 +1,63 @@
+use cosmwasm_std::Addr;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub registry_address: String,
+    /// Resolver that stores the `Name` record of every reverse node
+    pub resolver_address: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Claim `<sender>.addr.reverse` and point it at `name`
+    SetName {
+        name: String,
+    },
+    SetConfig {
+        registry_address: String,
+        resolver_address: String,
+    },
+    /// Proposes a new owner, who takes over with `AcceptOwnership`
+    ProposeOwner {
+        owner: String,
+    },
+    AcceptOwnership {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    GetReverseNode {
+        address: String,
+    },
+    /// Return the name set for the address, only if that name still resolves back to it.
+    /// Return type: PrimaryNameResponse
+    GetPrimaryName {
+        address: String,
+    },
+    GetConfig {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReverseNodeResponse {
+    pub node: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PrimaryNameResponse {
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub registry_address: Addr,
+    pub resolver_address: Addr,
+    pub owner: Addr,
+    pub pending_owner: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
@@ -0,0 +1,119 @@
+use cosmwasm_std::{Addr, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub registry_address: String,
+    pub resolver_address: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Opens subdomains of `node` for sale, called by the node owner.
+    /// The registrar has to be approved as registry operator of the sender beforehand.
+    Configure {
+        node: Vec<u8>,
+        /// Price of one year in `denom`
+        price: Uint128,
+        denom: String,
+        /// Longest duration in seconds that can be bought at once
+        max_duration: u64,
+        min_label_length: u32,
+        max_label_length: u32,
+        /// Characters allowed in labels, any character is allowed if unset
+        allowed_characters: Option<String>,
+    },
+    /// Stops selling subdomains of `node`, called by the node owner.
+    Disable {
+        node: Vec<u8>,
+    },
+    /// Buys `label` under `parent_node` for `duration` seconds, paying in the configured denom.
+    Register {
+        parent_node: Vec<u8>,
+        label: String,
+        owner: String,
+        duration: u64,
+        address: Option<String>,
+    },
+    Renew {
+        parent_node: Vec<u8>,
+        label: String,
+        duration: u64,
+    },
+    SetConfig {
+        registry_address: String,
+        resolver_address: String,
+    },
+    /// Proposes a new owner, who takes over with `AcceptOwnership`
+    ProposeOwner {
+        owner: String,
+    },
+    AcceptOwnership {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Return type: PricingResponse
+    GetPricing {
+        node: Vec<u8>,
+    },
+    /// Return type: RentPriceResponse
+    GetRentPrice {
+        parent_node: Vec<u8>,
+        label: String,
+        duration: u64,
+    },
+    /// Return type: GetExpiresResponse
+    GetExpires {
+        parent_node: Vec<u8>,
+        label: String,
+    },
+    /// Return type: IsAvailableResponse
+    IsAvailable {
+        parent_node: Vec<u8>,
+        label: String,
+    },
+    GetConfig {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PricingResponse {
+    pub owner: Addr,
+    pub price: Uint128,
+    pub denom: String,
+    pub max_duration: u64,
+    pub min_label_length: u32,
+    pub max_label_length: u32,
+    pub allowed_characters: Option<String>,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RentPriceResponse {
+    pub price: Uint128,
+    pub denom: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GetExpiresResponse {
+    pub expires: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IsAvailableResponse {
+    pub available: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub registry_address: Addr,
+    pub resolver_address: Addr,
+    pub owner: Addr,
+    pub pending_owner: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
@@ -2,16 +2,19 @@ use base64;
 use chrono::{DateTime, NaiveDateTime, Utc};
 use hex;
 use tiny_keccak::Keccak;
+use unicode_script::{Script, UnicodeScript};
 use unicode_segmentation::UnicodeSegmentation;
 
 pub fn keccak256(i: &[u8]) -> Vec<u8> {
     let mut o = vec![0u8; 32];
     Keccak::keccak256(i, &mut o);
-    return o;
+    o
 }
 
-pub fn get_label_from_name(name: &String) -> Vec<u8> {
-    keccak256(name.as_bytes())
+// Labels that can not be normalized are hashed as they are, same as in `namehash`
+pub fn get_label_from_name(name: &str) -> Vec<u8> {
+    let label = normalize_name(name).unwrap_or_else(|_| name.to_string());
+    keccak256(label.as_bytes())
 }
 
 pub fn get_token_id_from_label(label: &Vec<u8>) -> String {
@@ -26,21 +29,86 @@ pub fn namehash(name: &str) -> Vec<u8> {
     let mut labels: Vec<&str> = name.split(".").collect();
     labels.reverse();
     for label in labels.iter() {
-        let mut labelhash = [0u8; 32];
-        Keccak::keccak256(label.as_bytes(), &mut labelhash);
-        node.append(&mut labelhash.to_vec());
-        labelhash = [0u8; 32];
-        Keccak::keccak256(node.as_slice(), &mut labelhash);
-        node = labelhash.to_vec();
+        node.append(&mut get_label_from_name(label));
+        node = keccak256(node.as_slice());
     }
     node
 }
 
+// Script combinations allowed in a single label on top of Latin, see UTS-39 "Highly Restrictive"
+const ALLOWED_SCRIPT_SETS: &[&[Script]] = &[
+    &[Script::Han, Script::Hiragana, Script::Katakana],
+    &[Script::Han, Script::Hangul],
+    &[Script::Han, Script::Bopomofo],
+];
+
+// Cyrillic and Greek letters that render like lowercase Latin ones
+const LATIN_LOOKALIKES: &[char] = &[
+    'а', 'е', 'о', 'р', 'с', 'у', 'х', 'і', 'ј', 'ѕ', 'һ', 'ԁ', 'ԛ', 'ԝ', 'ӏ', 'ү', 'α', 'ι', 'κ',
+    'ν', 'ο', 'ρ', 'υ', 'χ',
+];
+
+fn check_scripts(label: &str) -> Result<(), String> {
+    let mut scripts: Vec<Script> = vec![];
+    for c in label.chars() {
+        let script = c.script();
+        if script != Script::Common && script != Script::Inherited && !scripts.contains(&script) {
+            scripts.push(script);
+        }
+    }
+    if scripts.len() > 1 {
+        let non_latin: Vec<&Script> = scripts.iter().filter(|s| **s != Script::Latin).collect();
+        let allowed = ALLOWED_SCRIPT_SETS
+            .iter()
+            .any(|set| non_latin.iter().all(|script| set.contains(script)));
+        if !allowed {
+            return Err(format!("Name mixes the scripts {:?}", scripts));
+        }
+    }
+    if let [Script::Cyrillic] | [Script::Greek] = scripts.as_slice() {
+        let confusable = label
+            .chars()
+            .all(|c| c.script() != scripts[0] || LATIN_LOOKALIKES.contains(&c));
+        if confusable {
+            return Err(String::from("Name is confusable with a Latin name"));
+        }
+    }
+    Ok(())
+}
+
+/// Maps a label to its canonical form following UTS-46 (case folding, NFC, STD3 rules) and
+/// rejects labels mixing scripts or spelling a Latin name with lookalike letters.
+pub fn normalize_name(name: &str) -> Result<String, String> {
+    let (normalized, result) = idna::Config::default()
+        .use_std3_ascii_rules(true)
+        .to_unicode(name);
+    if let Err(errors) = result {
+        return Err(format!("Name is not UTS-46 compliant: {:?}", errors));
+    }
+    if normalized.is_empty() {
+        return Err(String::from("Name is empty"));
+    }
+    if normalized.contains('.') {
+        return Err(String::from("Name must be a single label"));
+    }
+    if normalized.starts_with('-') {
+        return Err(String::from("Name must not start with a hyphen"));
+    }
+    check_scripts(&normalized)?;
+    Ok(normalized)
+}
+
 pub fn convert_namehash_to_hex_string(namehash: Vec<u8>) -> String {
     hex::encode(namehash)
 }
 
-const COLORS: &'static [&'static [&str]] = &[
+pub const ADDR_REVERSE_NAME: &str = "addr.reverse";
+
+pub fn get_reverse_node(address: &str) -> Vec<u8> {
+    namehash(&format!("{}.{}", address, ADDR_REVERSE_NAME))
+}
+
+const COLORS: &[&[&str]] = &[
     &["#F5A4C7", "#F5A4C7", "#FF6483"],
     &["#A1A3A5", "#F5A4C7", "#636466"],
     &["#ABAAF9", "#ABAAF9", "#2A4EF5"],
@@ -70,8 +138,7 @@ pub fn generate_image(name: String, timestamp: u64) -> String {
         .collect::<Vec<String>>();
     let n_line = names.len();
     let mut name_tags = String::from("");
-    for i in 0..n_line {
-        let name = names[i].clone();
+    for (i, name) in names.iter().enumerate() {
         let y = if n_line == 1 {
             245
         } else {
@@ -89,7 +156,7 @@ pub fn generate_image(name: String, timestamp: u64) -> String {
         name_tags += &name_tag;
     }
 
-    return String::from("data:image/svg+xml;base64,")
+    String::from("data:image/svg+xml;base64,")
         + &base64::encode(format!(
             r###"
         <svg width="500" height="500" viewBox="0 0 500 500" fill="none" xmlns="http://www.w3.org/2000/svg">
@@ -128,5 +195,5 @@ pub fn generate_image(name: String, timestamp: u64) -> String {
             c1 = color[1],
             c2 = color[2],
             name_tags = name_tags
-        ));
+        ))
 }